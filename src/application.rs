@@ -15,7 +15,12 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{cell::RefCell, collections::HashMap, env, os::unix::process::CommandExt as _, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    env,
+    os::unix::process::CommandExt as _,
+    rc::Rc,
+};
 
 use glib::Object;
 use gtk::{gdk::Display, gio, glib, glib::Type, prelude::*};
@@ -24,12 +29,13 @@ use tracing::{debug, info, warn};
 use crate::{
     clip_box::ClipBox,
     model::{
-        LaunchMode, MonitorListModelExt as _, Viewport, WallpaperConfig, WallpaperSource,
-        WallpaperType, WindowLayout, WindowRole,
+        LaunchMode, MonitorListModelExt as _, Viewport, WallpaperConfig, WindowInfo, WindowLayout,
+        WindowRole,
     },
     monitor_watcher::MonitorWatcher,
-    renderer::{Renderer, RendererWidget},
+    renderer::{ActiveRenderer, Mirror, Renderer, RendererMap, RendererWidget},
     settings_watcher::RenderSettings,
+    transition_box::TransitionBox,
     window::{HotaruApplicationWindow, Position},
 };
 
@@ -49,134 +55,221 @@ impl HotaruApplication {
 
     /// Build the UI and store active renderers in the shared state.
     ///
-    /// The `renderers` parameter is a shared vec that is populated with the
-    /// primary renderers created during this call. It is cleared first to
-    /// remove any previously active renderers.
+    /// The `renderers` parameter is a shared map that is populated with the
+    /// primary renderers created during this call, keyed by connector. It
+    /// is cleared first to remove any previously active renderers. Returns
+    /// the layout built, which a later monitor change is diffed against.
     pub fn build_ui(
         &self,
         config: &WallpaperConfig,
         settings: &RenderSettings,
-        renderers: &Rc<RefCell<Vec<Renderer>>>,
+        renderers: &Rc<RefCell<RendererMap>>,
         launch_mode: LaunchMode,
-    ) {
-        let monitor_map = MonitorWatcher::monitors().unwrap().monitor_map().unwrap();
-        info!("Monitor map: {:#?}", monitor_map);
+    ) -> WindowLayout {
+        let layout = current_layout(config);
+        let mut primary_widgets = RendererMap::new();
+        self.build_windows(
+            &layout.windows,
+            settings,
+            &mut primary_widgets,
+            RendererMap::new(),
+            launch_mode,
+        );
 
-        let layout = WindowLayout::new(config, &monitor_map);
-        info!("Window layout: {:#?}", layout);
-        let mut primary_widgets = HashMap::new();
+        // Store renderers in shared state
+        renderers.replace(primary_widgets);
+        layout
+    }
 
-        // The layout orders primaries before clones, so a clone's source
-        // renderer is always in `primary_widgets` by the time we reach it.
-        for info in &layout.windows {
-            let window = HotaruApplicationWindow::new(self, launch_mode);
-            window.set_monitor_connector(info.monitor.as_str());
-            window.set_position(Position {
-                x: info.geometry.x,
-                y: info.geometry.y,
+    /// Give each of `windows` its content, adding the primaries' renderers
+    /// to `renderers` and attaching each clone to its source's entry there.
+    /// A window still open on the monitor is reused, keeping what it shows
+    /// until the new renderer's first frame; otherwise one is created.
+    /// `retired` holds the renderers that window showed, by monitor; each is
+    /// stopped once its content has transitioned away.
+    pub fn build_windows(
+        &self,
+        windows: &[WindowInfo],
+        settings: &RenderSettings,
+        renderers: &mut RendererMap,
+        mut retired: RendererMap,
+        launch_mode: LaunchMode,
+    ) {
+        // Layouts order primaries before clones, so a clone's source
+        // renderer is always in `renderers` by the time we reach it.
+        for info in windows {
+            let reused = self.window_on(&info.monitor);
+            let window = reused.clone().unwrap_or_else(|| {
+                let window = HotaruApplicationWindow::new(self, launch_mode);
+                window.set_monitor_connector(info.monitor.as_str());
+                window
             });
-            window.set_size_request(info.geometry.width, info.geometry.height);
+            place_window(&window, info);
             debug!(
                 "window size request: {}x{}",
                 info.geometry.width, info.geometry.height
             );
-            window.set_title(Some(&info.title));
 
             let child: Option<gtk::Widget> = match &info.role {
                 WindowRole::Primary {
                     wallpaper_type,
                     wallpaper_source,
+                    wpe_properties,
                 } => {
-                    let renderer = build_renderer(wallpaper_type, wallpaper_source, settings);
+                    let settings = settings.with_overrides(&info.overrides);
+                    let renderer = Renderer::with_source(
+                        wallpaper_type,
+                        wallpaper_source,
+                        wpe_properties,
+                        settings.video_renderer,
+                        settings.enable_graphics_offload,
+                    );
                     renderer.set_content_fit(settings.content_fit);
                     renderer.set_volume(settings.volume);
                     renderer.set_mute(settings.mute);
+                    if let Renderer::Playlist(playlist) = &renderer {
+                        playlist.set_transition(settings.transition);
+                    }
                     let widget = renderer.widget().clone();
-                    primary_widgets.insert(info.monitor.clone(), renderer);
+                    renderers.insert(
+                        info.monitor.clone(),
+                        ActiveRenderer {
+                            renderer,
+                            source: wallpaper_source.clone(),
+                            outputs: vec![info.monitor.clone()],
+                            playing: Cell::new(false),
+                            fps_limit: Cell::new(None),
+                            volume_override: Cell::new(None),
+                            overrides: info.overrides,
+                            mirrors: Vec::new(),
+                        },
+                    );
                     Some(widget)
                 }
-                WindowRole::Clone { source } => primary_widgets.get(source).map(|primary| {
-                    primary
-                        .mirror(settings.enable_graphics_offload, settings.content_fit)
-                        .upcast()
+                WindowRole::Clone { source } => renderers.get_mut(source).map(|primary| {
+                    primary.outputs.push(info.monitor.clone());
+                    let content_fit = info.overrides.content_fit;
+                    let widget = primary.renderer.mirror(
+                        settings.enable_graphics_offload,
+                        content_fit.map_or(settings.content_fit, Into::into),
+                    );
+                    primary.mirrors.push(Mirror {
+                        monitor: info.monitor.clone(),
+                        widget: widget.clone(),
+                        content_fit,
+                    });
+                    widget.upcast()
                 }),
             };
 
-            if let Some(child) = child {
-                if let Some(viewport) = &info.viewport {
-                    window.set_child(Some(&wrap_with_viewport(
+            let source = match &info.role {
+                WindowRole::Primary { .. } => &info.monitor,
+                WindowRole::Clone { source } => source,
+            };
+            if let (Some(child), Some(primary)) = (child, renderers.get(source)) {
+                let content = match &info.viewport {
+                    Some(viewport) => wrap_with_viewport(
                         &child,
                         info.geometry.width,
                         info.geometry.height,
                         viewport,
-                    )));
-                } else {
-                    window.set_child(Some(&child));
-                }
+                    ),
+                    None => child,
+                };
+                let old = retired.remove(&info.monitor).map(|active| active.renderer);
+                set_content(&window, &content, &primary.renderer, old, settings);
+            }
+            if reused.is_none() {
+                window.present();
             }
-            window.present();
 
             if matches!(info.role, WindowRole::Primary { .. }) {
-                if let Some(renderer) = primary_widgets.get(&info.monitor) {
-                    renderer.play();
+                if let Some(primary) = renderers.get(&info.monitor) {
+                    primary.renderer.play();
+                    primary.playing.set(true);
                 }
             }
         }
+        // Monitors that got no content in the end.
+        for active in retired.into_values() {
+            active.renderer.stop();
+        }
+    }
 
-        // Store renderers in shared state
-        let mut shared = renderers.borrow_mut();
-        shared.clear();
-        shared.extend(primary_widgets.into_values());
+    /// The wallpaper window on `connector`, if one is open.
+    pub fn window_on(&self, connector: &str) -> Option<HotaruApplicationWindow> {
+        self.windows()
+            .into_iter()
+            .filter_map(|window| window.downcast::<HotaruApplicationWindow>().ok())
+            .find(|window| window.monitor_connector() == connector)
     }
 }
 
-/// Construct the renderer for a primary window's wallpaper source.
-fn build_renderer(
-    wallpaper_type: &WallpaperType,
-    wallpaper_source: &WallpaperSource,
-    settings: &RenderSettings,
-) -> Renderer {
-    if *wallpaper_type == WallpaperType::Wpe {
-        // WPE packages resolve their real renderer from project.json,
-        // so they take the whole source (filepath or workshop_id).
-        return Renderer::with_wpe(
-            wallpaper_source,
-            settings.video_renderer,
-            settings.enable_graphics_offload,
-        );
+/// The windows `config` needs on the monitors connected right now.
+pub fn current_layout(config: &WallpaperConfig) -> WindowLayout {
+    let monitor_map = MonitorWatcher::monitors().unwrap().monitor_map().unwrap();
+    info!("Monitor map: {:#?}", monitor_map);
+
+    let layout = WindowLayout::new(config, &monitor_map);
+    info!("Window layout: {:#?}", layout);
+    layout
+}
+
+/// Give `window` the geometry and title of `info` and, when it shows part
+/// of a canvas, point its `ClipBox` at the new viewport. Works on windows
+/// already on screen, whose child (and renderer) stays as it is.
+pub fn place_window(window: &HotaruApplicationWindow, info: &WindowInfo) {
+    // Before the position: in Hanabi mode a position change rewrites the
+    // title.
+    window.set_title(Some(&info.title));
+    window.set_position(Position {
+        x: info.geometry.x,
+        y: info.geometry.y,
+    });
+    window.set_size_request(info.geometry.width, info.geometry.height);
+    let content = window
+        .child()
+        .and_then(|transitions| transitions.first_child())
+        .and_downcast::<ClipBox>();
+    if let (Some(viewport), Some(clip_box)) = (&info.viewport, content) {
+        clip_box.set_viewport(info.geometry.width, info.geometry.height, viewport);
     }
-    match wallpaper_source {
-        WallpaperSource::Filepath { filepath } => Renderer::with_filepath(
-            filepath,
-            wallpaper_type,
-            settings.video_renderer,
-            settings.enable_graphics_offload,
-        ),
-        WallpaperSource::Uri { uri } => Renderer::with_uri(
-            uri,
-            wallpaper_type,
-            settings.video_renderer,
-            settings.enable_graphics_offload,
-        ),
-        WallpaperSource::WorkshopId { workshop_id } => {
-            warn!(
-                "workshop_id ({}) requires wallpaper_type: wpe; showing blank",
-                workshop_id
-            );
-            Renderer::with_uri(
-                "about:blank",
-                &WallpaperType::Web,
-                settings.video_renderer,
-                settings.enable_graphics_offload,
-            )
+}
+
+/// Show `content`, which draws `renderer`'s output, in `window`. Every
+/// window's child is a `TransitionBox`: what it showed before stays on top
+/// until `renderer` has a first frame (or fails, or times out), then
+/// transitions away, so changing the wallpaper never flashes black. `old`,
+/// the renderer it replaces, is stopped once gone from the screen.
+fn set_content(
+    window: &HotaruApplicationWindow,
+    content: &gtk::Widget,
+    renderer: &Renderer,
+    old: Option<Renderer>,
+    settings: &RenderSettings,
+) {
+    let transitions = window
+        .child()
+        .and_downcast::<TransitionBox>()
+        .unwrap_or_else(|| {
+            let transitions = TransitionBox::default();
+            window.set_child(Some(&transitions));
+            transitions
+        });
+    transitions.set_transition(settings.transition);
+    transitions.push(content, move || {
+        if let Some(old) = old {
+            old.stop();
         }
-    }
+    });
+    transitions.reveal_on_first_frame(renderer);
 }
 
 /// Wrap a widget so that only the portion visible through this monitor's
-/// viewport is shown. The child is allocated at full canvas size and
-/// translated by the viewport offset; rendering is clipped to the
-/// window dimensions so the oversized child does not inflate the window.
+/// viewport is shown. The child is allocated at full canvas size (times
+/// the viewport's render scale) and translated by the viewport offset;
+/// rendering is clipped to the window dimensions so the oversized child
+/// does not inflate the window.
 fn wrap_with_viewport(
     child: &gtk::Widget,
     window_width: i32,
@@ -184,25 +277,17 @@ fn wrap_with_viewport(
     viewport: &Viewport,
 ) -> gtk::Widget {
     debug!(
-        "Wrap with viewport: window size {}x{}, canvas size {}x{}, offset {}x{}",
+        "Wrap with viewport: window size {}x{}, canvas size {}x{}, offset {}x{}, render scale {}",
         window_width,
         window_height,
         viewport.canvas_width,
         viewport.canvas_height,
         viewport.offset_x,
-        viewport.offset_y
+        viewport.offset_y,
+        viewport.render_scale
     );
 
-    ClipBox::new(
-        child,
-        window_width,
-        window_height,
-        viewport.canvas_width,
-        viewport.canvas_height,
-        viewport.offset_x,
-        viewport.offset_y,
-    )
-    .upcast()
+    ClipBox::new(child, window_width, window_height, viewport).upcast()
 }
 
 fn display_backend_is(type_name: &str) -> bool {
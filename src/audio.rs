@@ -0,0 +1,252 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Audio spectrum capture for audio-reactive wallpapers.
+//!
+//! One GStreamer pipeline per process records what the desktop plays (the
+//! default sink's monitor, through PulseAudio or PipeWire's Pulse server)
+//! and runs it through the `spectrum` element, producing Wallpaper Engine's
+//! 64 left + 64 right band array about 30 times a second. It only runs
+//! while something is subscribed: a playing Wallpaper Engine web
+//! wallpaper. `HOTARU_AUDIO_SOURCE` replaces the source with another
+//! gst-launch description, e.g. `audiotestsrc wave=sine freq=1000` to
+//! check a wallpaper's reaction without playing anything.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gst::prelude::*;
+use gtk::glib;
+use tracing::{debug, info, warn};
+
+/// Frequency bands per channel.
+pub const SPECTRUM_BANDS: usize = 64;
+
+/// Band levels from 0 (at or below the threshold) to 1 (full scale): the
+/// left channel's bands, low to high, then the right's — the array
+/// `wallpaperRegisterAudioListener` callbacks receive.
+pub type Spectrum = [f32; 2 * SPECTRUM_BANDS];
+
+const SOURCE_ENV: &str = "HOTARU_AUDIO_SOURCE";
+/// What the default output device plays.
+const DEFAULT_SOURCE: &str = "pulsesrc device=@DEFAULT_MONITOR@";
+/// Time between spectrum updates, in nanoseconds (~30 Hz).
+const INTERVAL_NS: u64 = 33_333_333;
+/// Levels (dB) at or below this count as silence.
+const THRESHOLD_DB: i32 = -80;
+
+/// Keeps its callback receiving spectra; dropping it unsubscribes, and
+/// the last one to go stops the capture.
+#[must_use = "dropping the subscription unsubscribes at once"]
+pub struct AudioSubscription {
+    id: u64,
+}
+
+impl Drop for AudioSubscription {
+    fn drop(&mut self) {
+        CAPTURE.with_borrow_mut(|capture| {
+            capture.listeners.retain(|(id, _)| *id != self.id);
+            if capture.listeners.is_empty() {
+                capture.stop();
+            }
+        });
+    }
+}
+
+/// Call `f` on the main thread with every spectrum captured while the
+/// returned subscription lives. Capture failures are logged; the callback
+/// then just isn't called.
+pub fn subscribe(f: impl Fn(&Spectrum) + 'static) -> AudioSubscription {
+    CAPTURE.with_borrow_mut(|capture| {
+        capture.next_id += 1;
+        let id = capture.next_id;
+        capture.listeners.push((id, Rc::new(f)));
+        if capture.pipeline.is_none() {
+            capture.start();
+        }
+        AudioSubscription { id }
+    })
+}
+
+type Listener = Rc<dyn Fn(&Spectrum)>;
+
+thread_local! {
+    static CAPTURE: RefCell<Capture> = RefCell::default();
+}
+
+#[derive(Default)]
+struct Capture {
+    listeners: Vec<(u64, Listener)>,
+    next_id: u64,
+    pipeline: Option<(gst::Pipeline, gst::bus::BusWatchGuard)>,
+}
+
+impl Capture {
+    fn start(&mut self) {
+        let source = std::env::var(SOURCE_ENV).unwrap_or_else(|_| DEFAULT_SOURCE.to_string());
+        let pipeline = match pipeline(&source) {
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                warn!("Audio capture from '{}' unavailable: {}", source, e);
+                return;
+            }
+        };
+        let Some(bus) = pipeline.bus() else {
+            return;
+        };
+        let watch = bus.add_watch_local(|_bus, message| {
+            match message.view() {
+                gst::MessageView::Element(_) => {
+                    if let Some(spectrum) = spectrum_from_message(message) {
+                        // Listeners may subscribe or unsubscribe from
+                        // their callback; call them on a copy.
+                        let listeners: Vec<Listener> = CAPTURE.with_borrow(|capture| {
+                            capture.listeners.iter().map(|(_, f)| f.clone()).collect()
+                        });
+                        for listener in listeners {
+                            listener(&spectrum);
+                        }
+                    }
+                }
+                gst::MessageView::Error(error) => {
+                    warn!("Audio capture failed: {}", error.error());
+                    // Deferred: the watch is removed with the pipeline.
+                    glib::idle_add_local_once(|| CAPTURE.with_borrow_mut(Capture::stop));
+                }
+                _ => {}
+            }
+            glib::ControlFlow::Continue
+        });
+        let watch = match watch {
+            Ok(watch) => watch,
+            Err(e) => {
+                warn!("Audio capture has no bus watch: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = pipeline.set_state(gst::State::Playing) {
+            warn!("Audio capture from '{}' failed to start: {}", source, e);
+            let _ = pipeline.set_state(gst::State::Null);
+            return;
+        }
+        info!("Audio capture started from '{}'", source);
+        self.pipeline = Some((pipeline, watch));
+    }
+
+    fn stop(&mut self) {
+        if let Some((pipeline, _watch)) = self.pipeline.take() {
+            let _ = pipeline.set_state(gst::State::Null);
+            debug!("Audio capture stopped");
+        }
+    }
+}
+
+/// The capture pipeline on `source`, a gst-launch description.
+fn pipeline(source: &str) -> Result<gst::Pipeline, glib::Error> {
+    let description = format!(
+        "{source} ! audioconvert ! audio/x-raw,channels=2 \
+         ! spectrum bands={SPECTRUM_BANDS} interval={INTERVAL_NS} \
+           threshold={THRESHOLD_DB} multi-channel=true \
+         ! fakesink sync=true"
+    );
+    gst::parse::launch(&description)?
+        .downcast::<gst::Pipeline>()
+        .map_err(|_| glib::Error::new(gst::CoreError::Failed, "not a pipeline"))
+}
+
+/// The spectrum a `spectrum` element message carries, if it is one.
+fn spectrum_from_message(message: &gst::Message) -> Option<Spectrum> {
+    let structure = message.structure()?;
+    if structure.name() != "spectrum" {
+        return None;
+    }
+    // multi-channel=true: an array of channels, each an array of bands.
+    let channels = structure.get::<gst::Array>("magnitude").ok()?;
+    let channels: Vec<Vec<f32>> = channels
+        .iter()
+        .filter_map(|channel| channel.get::<gst::Array>().ok())
+        .map(|bands| bands.iter().filter_map(|db| db.get::<f32>().ok()).collect())
+        .collect();
+    Some(wpe_spectrum(&channels))
+}
+
+/// Spread per-channel band levels (dB) over a [`Spectrum`]. A mono
+/// capture fills both halves; missing bands are silent.
+fn wpe_spectrum(channels: &[Vec<f32>]) -> Spectrum {
+    let mut spectrum = [0.0; 2 * SPECTRUM_BANDS];
+    let Some(left) = channels.first() else {
+        return spectrum;
+    };
+    let right = channels.get(1).unwrap_or(left);
+    for (half, levels) in spectrum.chunks_mut(SPECTRUM_BANDS).zip([left, right]) {
+        for (band, db) in half.iter_mut().zip(levels) {
+            let threshold = THRESHOLD_DB as f32;
+            *band = ((db - threshold) / -threshold).clamp(0.0, 1.0);
+        }
+    }
+    spectrum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wpe_spectrum() {
+        assert_eq!(wpe_spectrum(&[]), [0.0; 2 * SPECTRUM_BANDS]);
+
+        let left = vec![0.0, -40.0, -80.0, -120.0];
+        let spectrum = wpe_spectrum(&[left.clone()]);
+        assert_eq!(spectrum[..4], [1.0, 0.5, 0.0, 0.0]);
+        assert_eq!(
+            spectrum[SPECTRUM_BANDS..SPECTRUM_BANDS + 4],
+            [1.0, 0.5, 0.0, 0.0]
+        );
+
+        let spectrum = wpe_spectrum(&[left, vec![-20.0]]);
+        assert_eq!(spectrum[1], 0.5);
+        assert_eq!(spectrum[SPECTRUM_BANDS], 0.75);
+        assert_eq!(spectrum[SPECTRUM_BANDS + 1], 0.0);
+    }
+
+    #[test]
+    fn test_spectrum_capture() {
+        // Needs gst-plugins-base and -good; skip where they are missing.
+        if gst::init().is_err() {
+            return;
+        }
+        // A sine at the center of band 5: 64 bands over 0-22050 Hz.
+        let Ok(pipeline) = pipeline("audiotestsrc wave=sine freq=1895 num-buffers=20") else {
+            return;
+        };
+        pipeline.set_state(gst::State::Playing).unwrap();
+        let message = pipeline.bus().unwrap().timed_pop_filtered(
+            gst::ClockTime::from_seconds(5),
+            &[gst::MessageType::Element],
+        );
+        pipeline.set_state(gst::State::Null).unwrap();
+
+        let spectrum = spectrum_from_message(&message.expect("no spectrum message")).unwrap();
+        let peak = |levels: &[f32]| {
+            (0..levels.len())
+                .max_by(|&a, &b| levels[a].total_cmp(&levels[b]))
+                .unwrap()
+        };
+        assert_eq!(peak(&spectrum[..SPECTRUM_BANDS]), 5);
+        assert_eq!(peak(&spectrum[SPECTRUM_BANDS..]), 5);
+    }
+}
@@ -17,12 +17,17 @@
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 use hotaru::prelude::*;
 
 #[derive(Debug, Parser)]
-#[command(author, version = crate::config::VERSION, about)]
+#[command(
+    author,
+    version = crate::config::VERSION,
+    about,
+    args_conflicts_with_subcommands = true
+)]
 pub struct Cli {
     #[arg(
         short = 'l',
@@ -41,6 +46,18 @@ pub struct Cli {
     )]
     pub config_file: Option<PathBuf>,
 
+    #[arg(
+        short = 'w',
+        long,
+        default_value_t = false,
+        requires = "config_file",
+        conflicts_with = "daemon",
+        help = "Re-apply the config file whenever it changes (standalone mode); \
+                a version that fails to parse or validate is logged and \
+                the current wallpaper kept"
+    )]
+    pub watch: bool,
+
     #[arg(
         short = 'd',
         long,
@@ -48,4 +65,50 @@ pub struct Cli {
         help = "Run as a D-Bus daemon, waiting for commands from the frontend"
     )]
     pub daemon: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Control a running daemon over D-Bus")]
+    Ctl {
+        #[command(subcommand)]
+        action: CtlAction,
+    },
+    #[command(about = "Check a wallpaper config file against the connected monitors")]
+    Validate {
+        #[arg(value_name = "FILE", help = "Path to the wallpaper config JSON file")]
+        config_file: PathBuf,
+    },
+    #[command(about = "Print the JSON Schema of the wallpaper config format")]
+    Schema,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CtlAction {
+    #[command(about = "Apply a wallpaper config file")]
+    Apply {
+        #[arg(value_name = "FILE", help = "Path to the wallpaper config JSON file")]
+        config_file: PathBuf,
+
+        #[arg(
+            short = 'l',
+            long = "launch-mode",
+            help = "Launch mode [default: the active wallpaper's, else \
+                    auto-detected]"
+        )]
+        launch_mode: Option<LaunchMode>,
+    },
+    #[command(about = "Pause playback")]
+    Pause,
+    #[command(about = "Resume playback")]
+    Resume,
+    #[command(about = "Disable the wallpaper")]
+    Disable,
+    #[command(about = "Quit the daemon")]
+    Quit,
+    #[command(about = "Print the playback state (idle, playing or paused)")]
+    State,
 }
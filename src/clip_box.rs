@@ -21,11 +21,17 @@
 //! viewport region appears at (0, 0). The widget itself reports only the
 //! viewport (window) size during measurement, preventing the oversized
 //! child from inflating the parent window.
+//!
+//! With a render scale above 1 the child is allocated that much larger and
+//! scaled back down, so it draws at a higher resolution than this monitor
+//! needs (for clones of it on denser monitors).
 
 use glib::subclass::types::ObjectSubclassIsExt as _;
 use glib::Object;
 use gtk::{glib, graphene, gsk, prelude::*};
 
+use crate::model::Viewport;
+
 glib::wrapper! {
     pub struct ClipBox(ObjectSubclass<imp::ClipBox>)
         @extends gtk::Widget,
@@ -37,28 +43,33 @@ impl ClipBox {
     ///
     /// * `child` – the widget to display (e.g. a renderer)
     /// * `window_width`, `window_height` – the size this widget reports
-    /// * `canvas_width`, `canvas_height` – the actual allocation for `child`
-    /// * `offset_x`, `offset_y` – translation applied to `child`
+    /// * `viewport` – the allocation for `child` (canvas size), the
+    ///   translation applied to it (offset) and its render scale
     pub fn new(
         child: &gtk::Widget,
         window_width: i32,
         window_height: i32,
-        canvas_width: i32,
-        canvas_height: i32,
-        offset_x: i32,
-        offset_y: i32,
+        viewport: &Viewport,
     ) -> Self {
         let obj: Self = Object::builder().build();
-        let imp = obj.imp();
-        imp.window_width.set(window_width);
-        imp.window_height.set(window_height);
-        imp.canvas_width.set(canvas_width);
-        imp.canvas_height.set(canvas_height);
-        imp.offset_x.set(offset_x);
-        imp.offset_y.set(offset_y);
+        obj.set_viewport(window_width, window_height, viewport);
         child.set_parent(&obj);
         obj
     }
+
+    /// Show another region of the canvas (the monitors moved), keeping the
+    /// child as it is.
+    pub fn set_viewport(&self, window_width: i32, window_height: i32, viewport: &Viewport) {
+        let imp = self.imp();
+        imp.window_width.set(window_width);
+        imp.window_height.set(window_height);
+        imp.canvas_width.set(viewport.canvas_width);
+        imp.canvas_height.set(viewport.canvas_height);
+        imp.offset_x.set(viewport.offset_x);
+        imp.offset_y.set(viewport.offset_y);
+        imp.render_scale.set(viewport.render_scale);
+        self.queue_resize();
+    }
 }
 
 mod imp {
@@ -74,6 +85,7 @@ mod imp {
         pub(super) canvas_height: Cell<i32>,
         pub(super) offset_x: Cell<i32>,
         pub(super) offset_y: Cell<i32>,
+        pub(super) render_scale: Cell<f64>,
     }
 
     #[glib::object_subclass]
@@ -104,13 +116,16 @@ mod imp {
 
         fn size_allocate(&self, _width: i32, _height: i32, _baseline: i32) {
             if let Some(child) = self.obj().first_child() {
-                let transform = gsk::Transform::new().translate(&graphene::Point::new(
-                    -self.offset_x.get() as f32,
-                    -self.offset_y.get() as f32,
-                ));
+                let scale = self.render_scale.get().max(1.0);
+                let transform = gsk::Transform::new()
+                    .translate(&graphene::Point::new(
+                        -self.offset_x.get() as f32,
+                        -self.offset_y.get() as f32,
+                    ))
+                    .scale(1.0 / scale as f32, 1.0 / scale as f32);
                 child.allocate(
-                    self.canvas_width.get(),
-                    self.canvas_height.get(),
+                    (self.canvas_width.get() as f64 * scale).round() as i32,
+                    (self.canvas_height.get() as f64 * scale).round() as i32,
                     -1,
                     Some(transform),
                 );
@@ -0,0 +1,151 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Config files on disk: loading them, and the standalone `--watch` hot
+//! reload, which re-reads the file whenever it changes.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use gtk::{gio, glib, prelude::*};
+use tracing::{debug, error, info};
+
+use crate::model::WallpaperConfig;
+
+/// An editor's save (truncate + write, or write-and-rename) arrives as a
+/// burst of events; reload once it has been quiet this long.
+const RELOAD_DELAY: Duration = Duration::from_millis(300);
+
+/// Read and parse a wallpaper config file, migrating older versions.
+pub fn load_config(path: &Path) -> Result<WallpaperConfig> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    WallpaperConfig::from_json(&json).with_context(|| format!("Invalid config {}", path.display()))
+}
+
+/// Watches a config file and hands every version that parses to
+/// `on_change`. A version that does not parse is logged and skipped, so
+/// the last good config stays up. Monitoring stops when the watcher is
+/// dropped.
+pub struct ConfigWatcher {
+    inner: Rc<WatcherInner>,
+}
+
+struct WatcherInner {
+    path: PathBuf,
+    on_change: Box<dyn Fn(WallpaperConfig)>,
+    monitor: RefCell<Option<gio::FileMonitor>>,
+    reload_source: RefCell<Option<glib::SourceId>>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: &Path, on_change: impl Fn(WallpaperConfig) + 'static) -> Result<Self> {
+        // Without WATCH_MOVES, a save that renames a temporary file over
+        // the config is reported as the config being created.
+        let monitor = gio::File::for_path(path)
+            .monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+            .with_context(|| format!("Failed to watch {}", path.display()))?;
+        let inner = Rc::new(WatcherInner {
+            path: path.to_path_buf(),
+            on_change: Box::new(on_change),
+            monitor: RefCell::new(None),
+            reload_source: RefCell::new(None),
+        });
+
+        let weak = Rc::downgrade(&inner);
+        monitor.connect_changed(move |_monitor, _file, _other, event| {
+            if !matches!(
+                event,
+                gio::FileMonitorEvent::Changed
+                    | gio::FileMonitorEvent::ChangesDoneHint
+                    | gio::FileMonitorEvent::Created
+            ) {
+                return;
+            }
+            debug!("Config file event {:?}", event);
+            if let Some(inner) = weak.upgrade() {
+                WatcherInner::schedule_reload(&inner);
+            }
+        });
+        inner.monitor.replace(Some(monitor));
+        info!("Watching {} for changes", path.display());
+        Ok(Self { inner })
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        if let Some(monitor) = self.inner.monitor.take() {
+            monitor.cancel();
+        }
+        if let Some(source) = self.inner.reload_source.take() {
+            source.remove();
+        }
+    }
+}
+
+impl WatcherInner {
+    fn schedule_reload(this: &Rc<Self>) {
+        if let Some(source) = this.reload_source.take() {
+            source.remove();
+        }
+        let weak = Rc::downgrade(this);
+        let source = glib::timeout_add_local_once(RELOAD_DELAY, move || {
+            let Some(this) = weak.upgrade() else {
+                return;
+            };
+            this.reload_source.take();
+            match load_config(&this.path) {
+                Ok(config) => (this.on_change)(config),
+                Err(e) => error!("{:#}; keeping the current wallpaper", e),
+            }
+        });
+        this.reload_source.replace(Some(source));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::WallpaperMode;
+
+    #[test]
+    fn test_load_config() {
+        let path =
+            std::env::temp_dir().join(format!("hotaru-config-test-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{ "mode": "clone_single_wallpaper", "monitors": [{ "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4" }] }"#,
+        )
+        .unwrap();
+        let config = load_config(&path).unwrap();
+        assert_eq!(config.mode, WallpaperMode::CloneSingleWallpaper);
+        assert_eq!(config.monitors.len(), 1);
+
+        std::fs::write(&path, r#"{ "mode": "clone_single_wallpaper" }"#).unwrap();
+        let error = format!("{:#}", load_config(&path).unwrap_err());
+        assert!(error.starts_with(&format!("Invalid config {}", path.display())));
+        assert!(error.contains("monitors"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(load_config(&path).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! `hotaru ctl`: drive a running daemon over D-Bus. Failures, including a
+//! command the daemon turned down (`Pause` while nothing plays), end up
+//! as errors, so `main` exits non-zero.
+
+use std::str::FromStr as _;
+
+use anyhow::{bail, Context, Result};
+
+use hotaru::config_watcher::load_config;
+use hotaru::dbus::HotaruRendererProxyBlocking;
+use hotaru::prelude::*;
+
+use crate::cli::CtlAction;
+
+pub fn run(action: CtlAction) -> Result<()> {
+    let conn =
+        zbus::blocking::Connection::session().context("Failed to connect to the session bus")?;
+    let daemon = HotaruRendererProxyBlocking::new(&conn)?;
+
+    match action {
+        CtlAction::Apply {
+            config_file,
+            launch_mode,
+        } => {
+            // Parse locally first, so a broken file is reported with its
+            // path rather than as a D-Bus error.
+            let config = load_config(&config_file)?;
+            let launch_mode = match launch_mode {
+                Some(launch_mode) => launch_mode,
+                None => active_launch_mode(&daemon)?.unwrap_or_else(LaunchMode::detect),
+            };
+            daemon
+                .apply_wallpaper(&serde_json::to_string(&config)?, &launch_mode.to_string())
+                .context("ApplyWallpaper failed")?;
+            println!("Applied {} ({})", config_file.display(), launch_mode);
+        }
+        CtlAction::Pause => {
+            if !daemon.pause()? {
+                bail!("Nothing is playing");
+            }
+        }
+        CtlAction::Resume => {
+            if !daemon.resume()? {
+                bail!("Playback is not paused");
+            }
+        }
+        CtlAction::Disable => {
+            if !daemon.disable_wallpaper()? {
+                bail!("Failed to disable the wallpaper");
+            }
+        }
+        CtlAction::Quit => daemon.quit()?,
+        CtlAction::State => println!("{}", daemon.state()?),
+    }
+    Ok(())
+}
+
+/// The launch mode of the wallpaper the daemon shows, if any: keeping it
+/// avoids an apply that needs the other GDK backend.
+fn active_launch_mode(daemon: &HotaruRendererProxyBlocking) -> Result<Option<LaunchMode>> {
+    let launch_mode = daemon.launch_mode()?;
+    if launch_mode.is_empty() {
+        return Ok(None);
+    }
+    LaunchMode::from_str(&launch_mode)
+        .map(Some)
+        .with_context(|| format!("Daemon reports unknown launch mode {}", launch_mode))
+}
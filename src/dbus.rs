@@ -15,16 +15,42 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use gtk::glib;
 use tracing::info;
+use zbus::object_server::SignalEmitter;
 
-use crate::state::RendererState;
+use crate::state::{RendererState, StateEvent};
 
 pub const DBUS_NAME: &str = "io.github.jeffshee.Hotaru";
 pub const DBUS_PATH: &str = "/io/github/jeffshee/Hotaru";
 
+/// Client side of the service, for `hotaru ctl`.
+#[zbus::proxy(
+    interface = "io.github.jeffshee.Hotaru.Renderer",
+    default_service = "io.github.jeffshee.Hotaru",
+    default_path = "/io/github/jeffshee/Hotaru"
+)]
+pub trait HotaruRenderer {
+    fn apply_wallpaper(&self, config_json: &str, launch_mode: &str) -> zbus::Result<bool>;
+
+    fn disable_wallpaper(&self) -> zbus::Result<bool>;
+
+    fn pause(&self) -> zbus::Result<bool>;
+
+    fn resume(&self) -> zbus::Result<bool>;
+
+    fn quit(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn launch_mode(&self) -> zbus::Result<String>;
+}
+
 // --- Commands sent from the D-Bus thread to the GLib main thread ---
 
 enum Command {
@@ -33,6 +59,10 @@ enum Command {
         launch_mode: String,
         reply: async_channel::Sender<Result<bool, String>>,
     },
+    ValidateConfig {
+        config_json: String,
+        reply: async_channel::Sender<Vec<(String, String, String)>>,
+    },
     DisableWallpaper {
         reply: async_channel::Sender<bool>,
     },
@@ -42,10 +72,69 @@ enum Command {
     Resume {
         reply: async_channel::Sender<bool>,
     },
+    PauseMonitor {
+        connector: String,
+        reply: async_channel::Sender<bool>,
+    },
+    ResumeMonitor {
+        connector: String,
+        reply: async_channel::Sender<bool>,
+    },
+    SetMonitorVolume {
+        connector: String,
+        volume: Option<i32>,
+        reply: async_channel::Sender<bool>,
+    },
+    SetWpeProperty {
+        connector: String,
+        name: String,
+        value: serde_json::Value,
+        reply: async_channel::Sender<Result<bool, String>>,
+    },
+    GetWpeProperties {
+        connector: String,
+        reply: async_channel::Sender<Result<String, String>>,
+    },
+    Next {
+        reply: async_channel::Sender<bool>,
+    },
+    Previous {
+        reply: async_channel::Sender<bool>,
+    },
     Quit,
     GetState {
         reply: async_channel::Sender<String>,
     },
+    GetPlaylistIndex {
+        reply: async_channel::Sender<i32>,
+    },
+    GetActiveScheduleSlot {
+        reply: async_channel::Sender<String>,
+    },
+    GetPauseReasons {
+        reply: async_channel::Sender<HashMap<String, String>>,
+    },
+    GetMonitorStates {
+        reply: async_channel::Sender<HashMap<String, (String, String, i32)>>,
+    },
+    GetCurrentConfig {
+        reply: async_channel::Sender<String>,
+    },
+    GetMonitors {
+        reply: async_channel::Sender<HashMap<String, (i32, i32, i32, i32)>>,
+    },
+    GetActiveRenderers {
+        reply: async_channel::Sender<HashMap<String, (String, String)>>,
+    },
+    GetLaunchMode {
+        reply: async_channel::Sender<String>,
+    },
+    GetCapabilities {
+        reply: async_channel::Sender<HashMap<String, bool>>,
+    },
+    GetLastErrors {
+        reply: async_channel::Sender<HashMap<String, (String, String)>>,
+    },
 }
 
 fn handle_command(state: &RendererState, cmd: Command) {
@@ -60,6 +149,17 @@ fn handle_command(state: &RendererState, cmd: Command) {
             let result = state.apply_wallpaper(&config_json, &launch_mode);
             let _ = reply.send_blocking(result);
         }
+        Command::ValidateConfig { config_json, reply } => {
+            let diagnostics = state
+                .validate_config(&config_json)
+                .into_iter()
+                .map(|diagnostic| {
+                    let severity = diagnostic.severity().to_string();
+                    (severity, diagnostic.path, diagnostic.kind.to_string())
+                })
+                .collect();
+            let _ = reply.send_blocking(diagnostics);
+        }
         Command::DisableWallpaper { reply } => {
             let _ = reply.send_blocking(state.disable_wallpaper());
         }
@@ -69,11 +169,106 @@ fn handle_command(state: &RendererState, cmd: Command) {
         Command::Resume { reply } => {
             let _ = reply.send_blocking(state.resume());
         }
+        Command::PauseMonitor { connector, reply } => {
+            let _ = reply.send_blocking(state.pause_monitor(&connector));
+        }
+        Command::ResumeMonitor { connector, reply } => {
+            let _ = reply.send_blocking(state.resume_monitor(&connector));
+        }
+        Command::SetMonitorVolume {
+            connector,
+            volume,
+            reply,
+        } => {
+            let _ = reply.send_blocking(state.set_monitor_volume(&connector, volume));
+        }
+        Command::SetWpeProperty {
+            connector,
+            name,
+            value,
+            reply,
+        } => {
+            let _ = reply.send_blocking(state.set_wpe_property(&connector, &name, value));
+        }
+        Command::GetWpeProperties { connector, reply } => {
+            let _ = reply.send_blocking(state.wpe_properties(&connector));
+        }
+        Command::Next { reply } => {
+            let _ = reply.send_blocking(state.step_playlist(true));
+        }
+        Command::Previous { reply } => {
+            let _ = reply.send_blocking(state.step_playlist(false));
+        }
         Command::Quit => {
             state.quit();
         }
         Command::GetState { reply } => {
-            let _ = reply.send_blocking(state.playback_state.borrow().to_string());
+            let _ = reply.send_blocking(state.effective_state().to_string());
+        }
+        Command::GetPlaylistIndex { reply } => {
+            let _ = reply.send_blocking(state.playlist_index());
+        }
+        Command::GetActiveScheduleSlot { reply } => {
+            let _ = reply.send_blocking(state.active_schedule_slot());
+        }
+        Command::GetPauseReasons { reply } => {
+            let reasons = state
+                .pause_reasons()
+                .into_iter()
+                .map(|(connector, reason)| (connector, reason.to_string()))
+                .collect();
+            let _ = reply.send_blocking(reasons);
+        }
+        Command::GetMonitorStates { reply } => {
+            let states = state
+                .monitor_states()
+                .into_iter()
+                .map(|(connector, monitor)| {
+                    let reason = monitor
+                        .pause_reason
+                        .map(|reason| reason.to_string())
+                        .unwrap_or_default();
+                    (
+                        connector,
+                        (monitor.state.to_string(), reason, monitor.volume),
+                    )
+                })
+                .collect();
+            let _ = reply.send_blocking(states);
+        }
+        Command::GetCurrentConfig { reply } => {
+            let _ = reply.send_blocking(state.current_config_json());
+        }
+        Command::GetMonitors { reply } => {
+            let monitors = state
+                .monitors()
+                .into_iter()
+                .map(|(connector, m)| (connector, (m.x, m.y, m.width, m.height)))
+                .collect();
+            let _ = reply.send_blocking(monitors);
+        }
+        Command::GetActiveRenderers { reply } => {
+            let renderers = state
+                .active_renderers()
+                .into_iter()
+                .map(|(connector, info)| (connector, (info.kind.to_string(), info.source)))
+                .collect();
+            let _ = reply.send_blocking(renderers);
+        }
+        Command::GetLaunchMode { reply } => {
+            let _ = reply.send_blocking(state.active_launch_mode());
+        }
+        Command::GetCapabilities { reply } => {
+            let capabilities = crate::renderer::capabilities().into_iter().collect();
+            let _ = reply.send_blocking(capabilities);
+        }
+        Command::GetLastErrors { reply } => {
+            let errors = state
+                .last_errors()
+                .into_iter()
+                .map(|(connector, error)| (connector, (error.kind.to_string(), error.message)))
+                .collect();
+            let _ = reply.send_blocking(errors);
         }
     }
 }
@@ -123,14 +318,29 @@ impl RendererService {
             .map_err(zbus::fdo::Error::Failed)?;
 
         self.emit_state_changed().await;
+        self.emit_event(StateEvent::PlaylistIndexChanged).await;
+        self.emit_event(StateEvent::ScheduleSlotChanged).await;
         Ok(result)
     }
 
+    /// Check a config against the connected monitors without applying it:
+    /// one `(severity, path, message)` per problem, severity `warning` or
+    /// `error`. Empty when the config is fine.
+    async fn validate_config(
+        &self,
+        config_json: &str,
+    ) -> zbus::fdo::Result<Vec<(String, String, String)>> {
+        let config_json = config_json.to_string();
+        self.request(|reply| Command::ValidateConfig { config_json, reply })
+            .await
+    }
+
     async fn disable_wallpaper(&self) -> zbus::fdo::Result<bool> {
         let result = self
             .request(|reply| Command::DisableWallpaper { reply })
             .await?;
         self.emit_state_changed().await;
+        self.emit_event(StateEvent::ScheduleSlotChanged).await;
         Ok(result)
     }
 
@@ -146,6 +356,96 @@ impl RendererService {
         Ok(result)
     }
 
+    /// Pause the wallpaper on one monitor (and any monitor mirroring the
+    /// same renderer). False when nothing shows there or it already is.
+    async fn pause_monitor(&self, connector: &str) -> zbus::fdo::Result<bool> {
+        let connector = connector.to_string();
+        let result = self
+            .request(|reply| Command::PauseMonitor { connector, reply })
+            .await?;
+        self.emit_state_changed().await;
+        Ok(result)
+    }
+
+    /// Undo `PauseMonitor`. False when the monitor wasn't paused that way.
+    async fn resume_monitor(&self, connector: &str) -> zbus::fdo::Result<bool> {
+        let connector = connector.to_string();
+        let result = self
+            .request(|reply| Command::ResumeMonitor { connector, reply })
+            .await?;
+        self.emit_state_changed().await;
+        Ok(result)
+    }
+
+    /// Set one monitor's volume (0-100); -1 returns it to the config's
+    /// `volume`, else the setting. False when nothing shows on the monitor.
+    async fn set_monitor_volume(&self, connector: &str, volume: i32) -> zbus::fdo::Result<bool> {
+        let volume = match volume {
+            -1 => None,
+            0..=100 => Some(volume),
+            _ => {
+                return Err(zbus::fdo::Error::InvalidArgs(format!(
+                    "Volume {} is outside 0-100 (or -1 to reset)",
+                    volume
+                )))
+            }
+        };
+        let connector = connector.to_string();
+        self.request(|reply| Command::SetMonitorVolume {
+            connector,
+            volume,
+            reply,
+        })
+        .await
+    }
+
+    /// Set a user property of the Wallpaper Engine web wallpaper on one
+    /// monitor (a playlist's current entry) to `json_value`, and hand it to
+    /// the running wallpaper. Lasts until the wallpaper is rebuilt; put it
+    /// in the entry's `wpe_properties` to keep it. False when nothing
+    /// shows on the monitor.
+    async fn set_wpe_property(
+        &self,
+        connector: &str,
+        name: &str,
+        json_value: &str,
+    ) -> zbus::fdo::Result<bool> {
+        let value = serde_json::from_str(json_value)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Value is not JSON: {}", e)))?;
+        let connector = connector.to_string();
+        let name = name.to_string();
+        self.request(|reply| Command::SetWpeProperty {
+            connector,
+            name,
+            value,
+            reply,
+        })
+        .await?
+        .map_err(zbus::fdo::Error::Failed)
+    }
+
+    /// The user properties of the Wallpaper Engine package (web or scene)
+    /// on one monitor, as a JSON array in display order. Each has `name`,
+    /// `type`, `label`, `value` (current), `default` and `order`, plus
+    /// `options`, `min`, `max`, `step` and `condition` where the package
+    /// declares them.
+    async fn get_wpe_properties(&self, connector: &str) -> zbus::fdo::Result<String> {
+        let connector = connector.to_string();
+        self.request(|reply| Command::GetWpeProperties { connector, reply })
+            .await?
+            .map_err(zbus::fdo::Error::Failed)
+    }
+
+    /// Advance every active playlist to its next entry.
+    async fn next(&self) -> zbus::fdo::Result<bool> {
+        self.request(|reply| Command::Next { reply }).await
+    }
+
+    /// Step every active playlist back to its previous entry.
+    async fn previous(&self) -> zbus::fdo::Result<bool> {
+        self.request(|reply| Command::Previous { reply }).await
+    }
+
     async fn quit(&self) -> zbus::fdo::Result<()> {
         self.cmd_tx
             .send(Command::Quit)
@@ -153,10 +453,102 @@ impl RendererService {
             .map_err(|e| zbus::fdo::Error::Failed(format!("Channel send error: {}", e)))
     }
 
+    /// `paused` also while the auto-pause policy holds every monitor; see
+    /// `PauseReasons` for why.
     #[zbus(property)]
     async fn state(&self) -> zbus::fdo::Result<String> {
         self.request(|reply| Command::GetState { reply }).await
     }
+
+    /// Entry index of the active playlist; -1 when none is showing.
+    #[zbus(property)]
+    async fn playlist_index(&self) -> zbus::fdo::Result<i32> {
+        self.request(|reply| Command::GetPlaylistIndex { reply })
+            .await
+    }
+
+    /// Name of the active schedule slot; empty outside every slot or when
+    /// the config has no schedule.
+    #[zbus(property)]
+    async fn active_schedule_slot(&self) -> zbus::fdo::Result<String> {
+        self.request(|reply| Command::GetActiveScheduleSlot { reply })
+            .await
+    }
+
+    /// Paused monitors (by connector) and why: `user`, `covered`,
+    /// `locked`, `idle` or `power`.
+    #[zbus(property)]
+    async fn pause_reasons(&self) -> zbus::fdo::Result<HashMap<String, String>> {
+        self.request(|reply| Command::GetPauseReasons { reply })
+            .await
+    }
+
+    /// Every monitor showing the wallpaper, by connector: (`playing` or
+    /// `paused`, pause reason or `""`, volume).
+    #[zbus(property)]
+    async fn monitor_states(&self) -> zbus::fdo::Result<HashMap<String, (String, String, i32)>> {
+        self.request(|reply| Command::GetMonitorStates { reply })
+            .await
+    }
+
+    /// The applied `WallpaperConfig` as JSON; empty while idle.
+    #[zbus(property)]
+    async fn current_config(&self) -> zbus::fdo::Result<String> {
+        self.request(|reply| Command::GetCurrentConfig { reply })
+            .await
+    }
+
+    /// Connected monitors by connector: (x, y, width, height) in logical
+    /// pixels.
+    #[zbus(property)]
+    async fn monitors(&self) -> zbus::fdo::Result<HashMap<String, (i32, i32, i32, i32)>> {
+        self.request(|reply| Command::GetMonitors { reply }).await
+    }
+
+    /// Every monitor showing the wallpaper, by connector: (renderer kind —
+    /// `mpv`, `gst-gtk4`, `image`, `web`, `scene`, `shader` or `playlist` —
+    /// and the configured source as JSON). Clones report their primary's
+    /// renderer.
+    #[zbus(property)]
+    async fn active_renderers(&self) -> zbus::fdo::Result<HashMap<String, (String, String)>> {
+        self.request(|reply| Command::GetActiveRenderers { reply })
+            .await
+    }
+
+    /// Launch mode of the active wallpaper; empty while idle.
+    #[zbus(property)]
+    async fn launch_mode(&self) -> zbus::fdo::Result<String> {
+        self.request(|reply| Command::GetLaunchMode { reply }).await
+    }
+
+    /// Optional renderer support: `mpv`, `wpe` and `shader` (compiled in)
+    /// and `wpe-library` (the scene engine library loads).
+    #[zbus(property)]
+    async fn capabilities(&self) -> zbus::fdo::Result<HashMap<String, bool>> {
+        self.request(|reply| Command::GetCapabilities { reply })
+            .await
+    }
+
+    /// The latest renderer failure of each monitor, by connector: (kind,
+    /// message) as in `RendererError`. Cleared when the wallpaper is
+    /// rebuilt.
+    #[zbus(property)]
+    async fn last_errors(&self) -> zbus::fdo::Result<HashMap<String, (String, String)>> {
+        self.request(|reply| Command::GetLastErrors { reply }).await
+    }
+
+    /// The renderer showing `connector` failed. `kind` is `load` (the
+    /// wallpaper could not be loaded or played), `package` (an invalid
+    /// Wallpaper Engine package), `unsupported` (this build lacks a
+    /// feature it needs) or `backend` (GL, libmpv, WebKit or the scene
+    /// engine failed). A blank wallpaper or a frozen frame stays up.
+    #[zbus(signal)]
+    async fn renderer_error(
+        emitter: &SignalEmitter<'_>,
+        connector: &str,
+        kind: &str,
+        message: &str,
+    ) -> zbus::Result<()>;
 }
 
 impl RendererService {
@@ -174,6 +566,49 @@ impl RendererService {
             }
         }
     }
+
+    /// Emit the PropertiesChanged signal a main-thread event stands for.
+    async fn emit_event(&self, event: StateEvent) {
+        let conn = self.conn.lock().unwrap().clone();
+        if let Some(conn) = conn {
+            if let Ok(iface_ref) = conn
+                .object_server()
+                .interface::<_, RendererService>(DBUS_PATH)
+                .await
+            {
+                let ctx = iface_ref.signal_emitter();
+                let _ = match event {
+                    StateEvent::PlaylistIndexChanged => self.playlist_index_changed(ctx).await,
+                    StateEvent::ScheduleSlotChanged => self.active_schedule_slot_changed(ctx).await,
+                    StateEvent::PlaybackChanged => {
+                        let _ = self.state_changed(ctx).await;
+                        let _ = self.pause_reasons_changed(ctx).await;
+                        self.monitor_states_changed(ctx).await
+                    }
+                    StateEvent::MonitorStatesChanged => self.monitor_states_changed(ctx).await,
+                    StateEvent::Rebuilt => {
+                        let _ = self.current_config_changed(ctx).await;
+                        let _ = self.monitors_changed(ctx).await;
+                        let _ = self.active_renderers_changed(ctx).await;
+                        let _ = self.launch_mode_changed(ctx).await;
+                        let _ = self.capabilities_changed(ctx).await;
+                        let _ = self.last_errors_changed(ctx).await;
+                        self.monitor_states_changed(ctx).await
+                    }
+                    StateEvent::RendererError { connector, error } => {
+                        let _ = Self::renderer_error(
+                            ctx,
+                            &connector,
+                            &error.kind.to_string(),
+                            &error.message,
+                        )
+                        .await;
+                        self.last_errors_changed(ctx).await
+                    }
+                };
+            }
+        }
+    }
 }
 
 // --- Registration ---
@@ -183,6 +618,10 @@ impl RendererService {
 /// the GLib main loop processes them on the main thread.
 pub fn register_dbus_service(state: Rc<RendererState>) {
     let (cmd_tx, cmd_rx) = async_channel::bounded::<Command>(32);
+    // Events flow the other way: main thread → D-Bus thread. Unbounded so
+    // the main thread never waits on the bus.
+    let (event_tx, event_rx) = async_channel::unbounded::<StateEvent>();
+    state.set_event_sender(event_tx);
 
     // Process commands on the GLib main thread as they arrive (the
     // channel integrates with the main loop; no polling).
@@ -222,18 +661,23 @@ pub fn register_dbus_service(state: Rc<RendererState>) {
             };
 
             // Store the connection reference in the service so it can emit signals.
-            if let Ok(iface_ref) = connection
+            let Ok(iface_ref) = connection
                 .object_server()
                 .interface::<_, RendererService>(DBUS_PATH)
                 .await
-            {
-                let iface = iface_ref.get().await;
-                *iface.conn.lock().unwrap() = Some(connection.clone());
-            }
+            else {
+                tracing::error!("D-Bus interface missing at {}", DBUS_PATH);
+                return;
+            };
+            *iface_ref.get().await.conn.lock().unwrap() = Some(connection.clone());
 
             info!("D-Bus service registered: {} at {}", DBUS_NAME, DBUS_PATH);
 
-            // Keep the connection alive
+            // Forward main-thread events for as long as the process lives
+            // (this also keeps the connection alive).
+            while let Ok(event) = event_rx.recv().await {
+                iface_ref.get().await.emit_event(event).await;
+            }
             std::future::pending::<()>().await;
         });
     });
@@ -0,0 +1,341 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Directory wallpaper sources.
+//!
+//! A `directory` source is a playlist whose entries come from the
+//! filesystem: this module enumerates a folder, classifies what it finds
+//! (video files, images, shaders, web pages, Wallpaper Engine package
+//! folders), and watches the folder so the playlist follows files being
+//! added or removed.
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk::{gio, glib, prelude::*};
+use tracing::{debug, warn};
+
+use crate::model::{PlaylistEntry, WallpaperSource, WallpaperType};
+use crate::wpe::WpePackage;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mkv", "webm", "mov", "avi", "ogv"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "avif", "apng", "bmp"];
+const SHADER_EXTENSIONS: &[&str] = &["frag", "glsl"];
+const WEB_EXTENSIONS: &[&str] = &["html", "htm"];
+/// Pseudo-extension selecting Wallpaper Engine package folders.
+const WPE_EXTENSION: &str = "wpe";
+
+/// Bursts of file events (a copy in progress, a folder being unpacked)
+/// settle into one rescan after this long without further events.
+const RESCAN_DELAY: Duration = Duration::from_secs(2);
+
+/// What to pick up from a directory, as configured on the source.
+#[derive(Debug, Clone)]
+pub struct DirectoryScan {
+    pub directory: PathBuf,
+    pub recursive: bool,
+    pub extensions: Vec<String>,
+    /// Seconds each entry is shown.
+    pub duration: u32,
+}
+
+impl DirectoryScan {
+    fn accepts(&self, extension: &str) -> bool {
+        self.extensions.is_empty()
+            || self.extensions.iter().any(|allowed| {
+                allowed
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(extension)
+            })
+    }
+
+    /// Enumerate the directory into playlist entries, sorted by path so a
+    /// sequential playlist has a stable order. Unreadable folders and
+    /// unrecognized files are skipped.
+    pub fn entries(&self) -> Vec<PlaylistEntry> {
+        let mut found = Vec::new();
+        self.scan_dir(&self.directory, &mut found);
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found
+            .into_iter()
+            .map(|(path, wallpaper_type)| PlaylistEntry {
+                wallpaper_type: Some(wallpaper_type),
+                wallpaper_source: WallpaperSource::Filepath {
+                    filepath: path.to_string_lossy().into_owned(),
+                },
+                duration: self.duration,
+                wpe_properties: Default::default(),
+            })
+            .collect()
+    }
+
+    /// The directories to monitor: the root, plus every non-package
+    /// subdirectory when recursive.
+    fn watched_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.directory.clone()];
+        if self.recursive {
+            let mut i = 0;
+            while i < dirs.len() {
+                for path in read_dir_sorted(&dirs[i]) {
+                    if path.is_dir() && !is_wpe_package(&path) {
+                        dirs.push(path);
+                    }
+                }
+                i += 1;
+            }
+        }
+        dirs
+    }
+
+    fn scan_dir(&self, dir: &Path, found: &mut Vec<(PathBuf, WallpaperType)>) {
+        for path in read_dir_sorted(dir) {
+            if path.is_dir() {
+                if is_wpe_package(&path) {
+                    if self.accepts(WPE_EXTENSION) {
+                        match WpePackage::from_dir(path.clone()) {
+                            Ok(_) => found.push((path, WallpaperType::Wpe)),
+                            Err(e) => warn!("Skipping Wallpaper Engine package: {:#}", e),
+                        }
+                    }
+                } else if self.recursive {
+                    self.scan_dir(&path, found);
+                }
+                continue;
+            }
+            let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+                continue;
+            };
+            let extension = extension.to_ascii_lowercase();
+            if !self.accepts(&extension) {
+                continue;
+            }
+            if let Some(wallpaper_type) = classify_extension(&extension) {
+                found.push((path, wallpaper_type));
+            }
+        }
+    }
+}
+
+fn classify_extension(extension: &str) -> Option<WallpaperType> {
+    if VIDEO_EXTENSIONS.contains(&extension) {
+        Some(WallpaperType::Video)
+    } else if IMAGE_EXTENSIONS.contains(&extension) {
+        Some(WallpaperType::Image)
+    } else if SHADER_EXTENSIONS.contains(&extension) {
+        Some(WallpaperType::Shader)
+    } else if WEB_EXTENSIONS.contains(&extension) {
+        Some(WallpaperType::Web)
+    } else {
+        None
+    }
+}
+
+fn is_wpe_package(dir: &Path) -> bool {
+    dir.join("project.json").is_file()
+}
+
+/// Directory children, skipping hidden files (editors' swap files, partial
+/// downloads) and anything unreadable.
+fn read_dir_sorted(dir: &Path) -> Vec<PathBuf> {
+    let read = match fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) => {
+            warn!(
+                "Failed to read wallpaper directory {}: {}",
+                dir.display(),
+                e
+            );
+            return Vec::new();
+        }
+    };
+    let mut paths: Vec<PathBuf> = read
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Watches a directory source and reports its rescanned entries whenever
+/// its contents change. Monitoring stops when the watcher is dropped.
+pub struct DirectoryWatcher {
+    inner: Rc<WatcherInner>,
+}
+
+struct WatcherInner {
+    scan: DirectoryScan,
+    on_change: Box<dyn Fn(Vec<PlaylistEntry>)>,
+    monitors: RefCell<Vec<gio::FileMonitor>>,
+    rescan_source: RefCell<Option<glib::SourceId>>,
+}
+
+impl DirectoryWatcher {
+    pub fn new(scan: DirectoryScan, on_change: impl Fn(Vec<PlaylistEntry>) + 'static) -> Self {
+        let inner = Rc::new(WatcherInner {
+            scan,
+            on_change: Box::new(on_change),
+            monitors: RefCell::new(Vec::new()),
+            rescan_source: RefCell::new(None),
+        });
+        WatcherInner::watch(&inner);
+        Self { inner }
+    }
+}
+
+impl Drop for DirectoryWatcher {
+    fn drop(&mut self) {
+        for monitor in self.inner.monitors.take() {
+            monitor.cancel();
+        }
+        if let Some(source) = self.inner.rescan_source.take() {
+            source.remove();
+        }
+    }
+}
+
+impl WatcherInner {
+    /// (Re)create the monitors. Subdirectories come and go in recursive
+    /// mode, so the set is rebuilt on every rescan.
+    fn watch(this: &Rc<Self>) {
+        for monitor in this.monitors.take() {
+            monitor.cancel();
+        }
+        let mut monitors = Vec::new();
+        for dir in this.scan.watched_dirs() {
+            let monitor = match gio::File::for_path(&dir)
+                .monitor_directory(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+            {
+                Ok(monitor) => monitor,
+                Err(e) => {
+                    warn!("Failed to watch {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+            let weak = Rc::downgrade(this);
+            monitor.connect_changed(move |_monitor, file, _other, event| {
+                if event == gio::FileMonitorEvent::Changed {
+                    // Content writes; the entry set only changes on
+                    // create/delete/move (and the done-hint after a copy).
+                    return;
+                }
+                debug!("Wallpaper directory event {:?}: {:?}", event, file.path());
+                if let Some(this) = weak.upgrade() {
+                    Self::schedule_rescan(&this);
+                }
+            });
+            monitors.push(monitor);
+        }
+        this.monitors.replace(monitors);
+    }
+
+    fn schedule_rescan(this: &Rc<Self>) {
+        if let Some(source) = this.rescan_source.take() {
+            source.remove();
+        }
+        let weak = Rc::downgrade(this);
+        let source = glib::timeout_add_local_once(RESCAN_DELAY, move || {
+            let Some(this) = weak.upgrade() else {
+                return;
+            };
+            this.rescan_source.take();
+            Self::watch(&this);
+            (this.on_change)(this.scan.entries());
+        });
+        this.rescan_source.replace(Some(source));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn filepaths(entries: &[PlaylistEntry]) -> Vec<(String, WallpaperType)> {
+        entries
+            .iter()
+            .map(|entry| {
+                let WallpaperSource::Filepath { filepath } = &entry.wallpaper_source else {
+                    panic!("Expected filepath entries");
+                };
+                let name = Path::new(filepath).file_name().unwrap().to_string_lossy();
+                (name.into_owned(), entry.wallpaper_type.unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_directory_scan() {
+        let root = std::env::temp_dir().join(format!("hotaru-dir-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        touch(&root.join("b.webm"), "");
+        touch(&root.join("a.MP4"), "");
+        touch(&root.join("page.html"), "");
+        touch(&root.join("still.JPG"), "");
+        touch(&root.join("plasma.frag"), "");
+        touch(&root.join("notes.txt"), "");
+        touch(&root.join(".partial.mp4"), "");
+        touch(
+            &root.join("package/project.json"),
+            r#"{ "type": "video", "file": "clip.mp4" }"#,
+        );
+        touch(&root.join("package/clip.mp4"), "");
+        touch(&root.join("nested/c.mkv"), "");
+
+        let mut scan = DirectoryScan {
+            directory: root.clone(),
+            recursive: false,
+            extensions: vec![],
+            duration: 60,
+        };
+        assert_eq!(
+            filepaths(&scan.entries()),
+            vec![
+                ("a.MP4".into(), WallpaperType::Video),
+                ("b.webm".into(), WallpaperType::Video),
+                ("package".into(), WallpaperType::Wpe),
+                ("page.html".into(), WallpaperType::Web),
+                ("plasma.frag".into(), WallpaperType::Shader),
+                ("still.JPG".into(), WallpaperType::Image),
+            ]
+        );
+        assert!(scan.entries().iter().all(|entry| entry.duration == 60));
+
+        scan.recursive = true;
+        scan.extensions = vec!["mkv".into(), ".webm".into()];
+        assert_eq!(
+            filepaths(&scan.entries()),
+            vec![
+                ("b.webm".into(), WallpaperType::Video),
+                ("c.mkv".into(), WallpaperType::Video),
+            ]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
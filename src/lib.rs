@@ -16,14 +16,23 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 pub mod application;
+pub mod audio;
 pub mod clip_box;
+pub mod config_watcher;
 pub mod constants;
 pub mod dbus;
+pub mod directory;
 pub mod model;
 pub mod monitor_watcher;
+pub mod mpris;
+pub mod policy;
 pub mod renderer;
+pub mod scheduler;
 pub mod settings_watcher;
 pub mod state;
+#[cfg(test)]
+mod test_support;
+pub mod transition_box;
 pub mod window;
 pub mod wpe;
 
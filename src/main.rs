@@ -17,6 +17,8 @@
 
 mod cli;
 mod config;
+mod ctl;
+mod validate;
 
 use clap::Parser as _;
 use gtk::{
@@ -27,15 +29,31 @@ use gtk::{
 use tracing::info;
 use tracing_subscriber::{fmt, layer::SubscriberExt as _, util::SubscriberInitExt as _, EnvFilter};
 
+use hotaru::config_watcher::{load_config, ConfigWatcher};
 use hotaru::dbus::register_dbus_service;
+use hotaru::model::Severity;
 use hotaru::prelude::*;
 use hotaru::state::RendererState;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, Command};
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    // Subcommands either talk to a running daemon (ctl) or only deal with
+    // config files (validate, schema); none of the setup below applies to
+    // them.
+    match cli.command {
+        Some(Command::Ctl { action }) => return ctl::run(action),
+        Some(Command::Validate { config_file }) => return validate::run(&config_file),
+        Some(Command::Schema) => {
+            let schema = WallpaperConfig::json_schema();
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            return Ok(());
+        }
+        None => {}
+    }
+
     tracing_subscriber::registry()
         .with(fmt::layer())
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("hotaru=info")))
@@ -149,8 +167,7 @@ fn main() -> anyhow::Result<()> {
         let config_file = cli
             .config_file
             .ok_or_else(|| anyhow::anyhow!("--config is required unless --daemon"))?;
-        let json = std::fs::read_to_string(&config_file)?;
-        let config: WallpaperConfig = serde_json::from_str(&json)?;
+        let config = load_config(&config_file)?;
         info!("Wallpaper config loaded: {:#?}", config);
 
         let launch_mode = cli.launch_mode.unwrap_or_else(LaunchMode::detect);
@@ -166,6 +183,32 @@ fn main() -> anyhow::Result<()> {
                 tracing::error!("Failed to apply wallpaper: {}", e);
             }
         });
+
+        // --watch: every version of the file that parses and validates
+        // without errors replaces the wallpaper; the watcher lives as long
+        // as the app runs.
+        let _config_watcher = if cli.watch {
+            let state = state.clone();
+            Some(ConfigWatcher::new(&config_file, move |config| {
+                if state.config.borrow().as_ref() == Some(&config) {
+                    return;
+                }
+                let diagnostics = config.validate(&state.monitors());
+                for diagnostic in &diagnostics {
+                    tracing::warn!("{}", diagnostic);
+                }
+                if diagnostics.iter().any(|d| d.severity() == Severity::Error) {
+                    tracing::error!("Config has errors; keeping the current wallpaper");
+                    return;
+                }
+                info!("Config file changed, applying: {:#?}", config);
+                if let Err(e) = state.apply(&config, launch_mode) {
+                    tracing::error!("Failed to apply wallpaper: {}", e);
+                }
+            })?)
+        } else {
+            None
+        };
         app.run();
     }
 
@@ -17,16 +17,43 @@
 
 mod hanabi_params;
 mod launch_mode;
+mod media;
+mod migration;
 mod monitor;
+mod monitor_match;
+mod placement;
+mod power_policy;
+mod render_overrides;
+mod schedule;
+mod shader_source;
+mod transition;
+mod validation;
 mod video_renderer;
 mod wallpaper_config;
 mod window_layout;
+mod wpe_property;
 
 pub use hanabi_params::HanabiParams;
 pub use launch_mode::LaunchMode;
+pub use media::{MediaEventKind, NowPlaying, PlaybackState, Thumbnail};
+pub use migration::{migrate, ConfigError, CONFIG_VERSION};
 pub use monitor::{MonitorError, MonitorInfo, MonitorListModelExt, MonitorMap};
+pub use monitor_match::{find_monitor, ANY_MONITOR};
+pub use placement::{Bezel, BezelUnit, Placement};
+pub use power_policy::{PowerAction, PowerPolicy, PowerState};
+pub use render_overrides::{ContentFit, RenderOverrides};
+pub use schedule::{
+    sun_times, LocalTime, Location, Schedule, ScheduleSlot, ScheduleTime, SunTimes,
+};
+pub use shader_source::{ShaderSource, SHADER_CHANNELS};
+pub use transition::{dissolve_threshold, Transition, TransitionKind};
+pub use validation::{Diagnostic, DiagnosticKind, Severity};
 pub use video_renderer::VideoRenderer;
 pub use wallpaper_config::{
-    MonitorConfig, WallpaperConfig, WallpaperMode, WallpaperSource, WallpaperType,
+    MonitorConfig, PlaylistEntry, PlaylistOrder, SpanGroup, WallpaperConfig, WallpaperMode,
+    WallpaperSource, WallpaperType,
+};
+pub use window_layout::{
+    LayoutDiff, Viewport, WindowGeometry, WindowInfo, WindowLayout, WindowRole,
 };
-pub use window_layout::{Viewport, WindowGeometry, WindowInfo, WindowLayout, WindowRole};
+pub use wpe_property::{engine_assignment, user_property, WpeProperty, WpePropertyOption};
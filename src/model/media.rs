@@ -0,0 +1,302 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+/// An MPRIS player's `PlaybackStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display)]
+pub enum PlaybackState {
+    #[default]
+    Stopped,
+    Playing,
+    Paused,
+}
+
+impl PlaybackState {
+    /// The `window.wallpaperMediaIntegration.PLAYBACK_*` value.
+    fn wpe_value(self) -> u8 {
+        match self {
+            Self::Stopped => 0,
+            Self::Playing => 1,
+            Self::Paused => 2,
+        }
+    }
+}
+
+/// Which Wallpaper Engine media listener an event is for; the string form
+/// is the key the web wallpaper API stub files listeners under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum MediaEventKind {
+    /// `wallpaperRegisterMediaStatusListener`
+    Status,
+    /// `wallpaperRegisterMediaPropertiesListener`
+    Properties,
+    /// `wallpaperRegisterMediaThumbnailListener`
+    Thumbnail,
+    /// `wallpaperRegisterMediaPlaybackListener`
+    Playback,
+    /// `wallpaperRegisterMediaTimelineListener`
+    Timeline,
+}
+
+/// Album art, ready for a thumbnail event.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Thumbnail {
+    /// A `data:` URL, or the player's own art URL when it isn't local.
+    pub url: String,
+    /// Average colour of the whole image.
+    pub primary: [u8; 3],
+    /// Average colour of its darker half.
+    pub secondary: [u8; 3],
+    /// Average colour of its lighter half.
+    pub tertiary: [u8; 3],
+}
+
+impl Thumbnail {
+    /// A thumbnail for `url` coloured after its `pixels`.
+    pub fn new(url: String, pixels: &[[u8; 3]]) -> Self {
+        let mut pixels = pixels.to_vec();
+        pixels.sort_by_key(|&pixel| luminance(pixel));
+        let (darker, lighter) = pixels.split_at(pixels.len() / 2);
+        Self {
+            url,
+            primary: average(&pixels),
+            secondary: average(darker),
+            tertiary: average(lighter),
+        }
+    }
+}
+
+/// What a player is playing, as last read.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NowPlaying {
+    pub title: String,
+    pub artist: String,
+    pub album_title: String,
+    pub album_artist: String,
+    pub genres: Vec<String>,
+    /// `mpris:artUrl`, empty if the player has none.
+    pub art_url: String,
+    pub thumbnail: Option<Thumbnail>,
+    pub state: PlaybackState,
+    /// Position when read.
+    pub position: Duration,
+    /// Track length, if the player knows it.
+    pub length: Option<Duration>,
+    /// Playback speed, 1.0 being normal.
+    pub rate: f64,
+}
+
+impl NowPlaying {
+    /// Where playback is `elapsed` after the position was read.
+    pub fn position_after(&self, elapsed: Duration) -> Duration {
+        let mut position = self.position;
+        if self.state == PlaybackState::Playing {
+            position += elapsed.mul_f64(self.rate.max(0.0));
+        }
+        match self.length {
+            Some(length) => position.min(length),
+            None => position,
+        }
+    }
+
+    /// The event for `kind`, `elapsed` after the player was read.
+    pub fn event(&self, kind: MediaEventKind, elapsed: Duration) -> Value {
+        match kind {
+            MediaEventKind::Status => json!({ "enabled": true }),
+            MediaEventKind::Properties => json!({
+                "title": self.title,
+                "artist": self.artist,
+                "subTitle": "",
+                "albumTitle": self.album_title,
+                "albumArtist": self.album_artist,
+                "genres": self.genres.join(", "),
+                "contentType": "music",
+            }),
+            MediaEventKind::Thumbnail => {
+                let thumbnail = self.thumbnail.clone().unwrap_or_default();
+                json!({
+                    "thumbnail": thumbnail.url,
+                    "primaryColor": hex(thumbnail.primary),
+                    "secondaryColor": hex(thumbnail.secondary),
+                    "tertiaryColor": hex(thumbnail.tertiary),
+                    "textColor": hex(contrasting(thumbnail.primary)),
+                    "highContrastColor": hex(contrasting(thumbnail.secondary)),
+                })
+            }
+            MediaEventKind::Playback => json!({ "state": self.state.wpe_value() }),
+            MediaEventKind::Timeline => json!({
+                "position": self.position_after(elapsed).as_secs_f64(),
+                "duration": self.length.unwrap_or_default().as_secs_f64(),
+            }),
+        }
+    }
+
+    /// The events a listener must see to catch up from `old` to `self`.
+    pub fn changes_from(&self, old: &NowPlaying) -> Vec<MediaEventKind> {
+        let mut kinds = Vec::new();
+        if (
+            &self.title,
+            &self.artist,
+            &self.album_title,
+            &self.album_artist,
+            &self.genres,
+        ) != (
+            &old.title,
+            &old.artist,
+            &old.album_title,
+            &old.album_artist,
+            &old.genres,
+        ) {
+            kinds.push(MediaEventKind::Properties);
+        }
+        if self.thumbnail != old.thumbnail {
+            kinds.push(MediaEventKind::Thumbnail);
+        }
+        if self.state != old.state {
+            kinds.push(MediaEventKind::Playback);
+        }
+        kinds.push(MediaEventKind::Timeline);
+        kinds
+    }
+}
+
+/// Rec. 601 luma, 0-255.
+fn luminance([r, g, b]: [u8; 3]) -> u8 {
+    ((299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)) / 1000) as u8
+}
+
+fn average(pixels: &[[u8; 3]]) -> [u8; 3] {
+    if pixels.is_empty() {
+        return [0; 3];
+    }
+    let mut sum = [0u64; 3];
+    for pixel in pixels {
+        for (sum, channel) in sum.iter_mut().zip(pixel) {
+            *sum += u64::from(*channel);
+        }
+    }
+    sum.map(|sum| (sum / pixels.len() as u64) as u8)
+}
+
+/// Black or white, whichever reads better on `color`.
+fn contrasting(color: [u8; 3]) -> [u8; 3] {
+    if luminance(color) > 127 {
+        [0; 3]
+    } else {
+        [255; 3]
+    }
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playback_state() {
+        assert_eq!("Playing".parse(), Ok(PlaybackState::Playing));
+        assert_eq!("Paused".parse(), Ok(PlaybackState::Paused));
+        assert!("playing".parse::<PlaybackState>().is_err());
+        let now_playing = NowPlaying {
+            state: PlaybackState::Paused,
+            ..Default::default()
+        };
+        assert_eq!(
+            now_playing.event(MediaEventKind::Playback, Duration::ZERO),
+            json!({ "state": 2 })
+        );
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut now_playing = NowPlaying {
+            state: PlaybackState::Playing,
+            position: Duration::from_secs(10),
+            length: Some(Duration::from_secs(12)),
+            rate: 1.0,
+            ..Default::default()
+        };
+        let elapsed = Duration::from_secs(1);
+        assert_eq!(
+            now_playing.event(MediaEventKind::Timeline, elapsed),
+            json!({ "position": 11.0, "duration": 12.0 })
+        );
+        // Clamped to the track.
+        assert_eq!(
+            now_playing.position_after(elapsed * 5),
+            Duration::from_secs(12)
+        );
+
+        now_playing.state = PlaybackState::Paused;
+        assert_eq!(now_playing.position_after(elapsed), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_properties_and_changes() {
+        let old = NowPlaying::default();
+        let new = NowPlaying {
+            title: "Song".into(),
+            artist: "Band".into(),
+            genres: vec!["Rock".into(), "Pop".into()],
+            state: PlaybackState::Playing,
+            ..Default::default()
+        };
+        let event = new.event(MediaEventKind::Properties, Duration::ZERO);
+        assert_eq!(event["title"], "Song");
+        assert_eq!(event["artist"], "Band");
+        assert_eq!(event["genres"], "Rock, Pop");
+        assert_eq!(
+            new.changes_from(&old),
+            [
+                MediaEventKind::Properties,
+                MediaEventKind::Playback,
+                MediaEventKind::Timeline
+            ]
+        );
+        assert_eq!(new.changes_from(&new), [MediaEventKind::Timeline]);
+        assert_eq!(<&str>::from(MediaEventKind::Timeline), "timeline");
+    }
+
+    #[test]
+    fn test_thumbnail_colors() {
+        let thumbnail = Thumbnail::new(
+            "file:///art.png".into(),
+            &[[0, 0, 0], [255, 255, 255], [20, 20, 20], [250, 250, 250]],
+        );
+        assert_eq!(thumbnail.primary, [131, 131, 131]);
+        assert_eq!(thumbnail.secondary, [10, 10, 10]);
+        assert_eq!(thumbnail.tertiary, [252, 252, 252]);
+
+        let now_playing = NowPlaying {
+            thumbnail: Some(thumbnail),
+            ..Default::default()
+        };
+        let event = now_playing.event(MediaEventKind::Thumbnail, Duration::ZERO);
+        assert_eq!(event["thumbnail"], "file:///art.png");
+        assert_eq!(event["primaryColor"], "#838383");
+        assert_eq!(event["textColor"], "#000000");
+        assert_eq!(event["highContrastColor"], "#ffffff");
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// The config format version this build reads and writes.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Step `i` upgrades a version-`i` config object to version `i + 1`. A
+/// format change bumps `CONFIG_VERSION` and appends its step here, so
+/// every older config keeps loading.
+const MIGRATIONS: [fn(&mut Map<String, Value>); CONFIG_VERSION as usize] = [
+    // 0 → 1: configs from before versioning have no `version` key and
+    // otherwise the same shape.
+    |_| {},
+];
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// Malformed JSON, or a problem with the top-level object itself.
+    #[error("{0}")]
+    Json(serde_json::Error),
+    #[error("a wallpaper config must be a JSON object")]
+    NotAnObject,
+    #[error("version must be a non-negative integer")]
+    InvalidVersion,
+    #[error("config version {0} is newer than this hotaru supports ({CONFIG_VERSION})")]
+    UnsupportedVersion(u64),
+    /// The JSON does not match the current format; `path` is where, as in
+    /// `monitors[1].playlist[0]`.
+    #[error("{path}: {source}")]
+    Invalid {
+        path: String,
+        source: serde_json::Error,
+    },
+}
+
+impl ConfigError {
+    /// Where in the config the error is, if it concerns one place.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Invalid { path, .. } => path,
+            _ => "",
+        }
+    }
+}
+
+/// Upgrade a parsed config in place to `CONFIG_VERSION`, returning the
+/// version it had.
+pub fn migrate(value: &mut Value) -> Result<u32, ConfigError> {
+    let config = value.as_object_mut().ok_or(ConfigError::NotAnObject)?;
+    let version = match config.get("version") {
+        None => 0,
+        Some(version) => version.as_u64().ok_or(ConfigError::InvalidVersion)?,
+    };
+    if version > CONFIG_VERSION as u64 {
+        return Err(ConfigError::UnsupportedVersion(version));
+    }
+    for step in &MIGRATIONS[version as usize..] {
+        step(config);
+    }
+    config.insert("version".into(), CONFIG_VERSION.into());
+    Ok(version as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_versions() {
+        let mut unversioned = json!({ "mode": "wallpaper_per_monitor", "monitors": [] });
+        assert_eq!(migrate(&mut unversioned).unwrap(), 0);
+        assert_eq!(unversioned["version"], CONFIG_VERSION);
+        assert_eq!(unversioned["mode"], "wallpaper_per_monitor");
+
+        let mut current = json!({ "version": CONFIG_VERSION, "monitors": [] });
+        assert_eq!(migrate(&mut current).unwrap(), CONFIG_VERSION);
+
+        let mut newer = json!({ "version": CONFIG_VERSION + 1 });
+        assert!(matches!(
+            migrate(&mut newer),
+            Err(ConfigError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            migrate(&mut json!({ "version": "1" })),
+            Err(ConfigError::InvalidVersion)
+        ));
+        assert!(matches!(
+            migrate(&mut json!([])),
+            Err(ConfigError::NotAnObject)
+        ));
+    }
+}
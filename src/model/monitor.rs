@@ -28,12 +28,39 @@ pub enum MonitorError {
     MonitorListModel(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MonitorInfo {
     pub x: i32,
     pub y: i32,
     pub width: i32,
     pub height: i32,
+    /// Physical size in millimeters, 0 if the monitor does not report it.
+    pub width_mm: i32,
+    pub height_mm: i32,
+    /// Device pixels per logical pixel, fractional scaling included.
+    pub scale: f64,
+    /// What the monitor reports about itself (from its EDID), which stays
+    /// the same on another port; configs can name it by these.
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Default for MonitorInfo {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            width_mm: 0,
+            height_mm: 0,
+            scale: 1.0,
+            manufacturer: None,
+            model: None,
+            description: None,
+        }
+    }
 }
 
 impl From<&Monitor> for MonitorInfo {
@@ -43,6 +70,12 @@ impl From<&Monitor> for MonitorInfo {
             y: monitor.geometry().y(),
             height: monitor.geometry().height(),
             width: monitor.geometry().width(),
+            width_mm: monitor.width_mm(),
+            height_mm: monitor.height_mm(),
+            scale: monitor.scale(),
+            manufacturer: monitor.manufacturer().map(Into::into),
+            model: monitor.model().map(Into::into),
+            description: monitor.description().map(Into::into),
         }
     }
 }
@@ -50,6 +83,9 @@ impl From<&Monitor> for MonitorInfo {
 pub type MonitorMap = HashMap<String, MonitorInfo>;
 pub trait MonitorListModelExt {
     fn monitor_vec(&self) -> Result<Vec<Monitor>, MonitorError>;
+    /// Monitors with the key `monitor_map` gives them: the connector, or
+    /// `Unknown-<n>` for the n-th monitor without one.
+    fn keyed_monitors(&self) -> Result<Vec<(String, Monitor)>, MonitorError>;
     fn monitor_map(&self) -> Result<MonitorMap, MonitorError>;
 }
 
@@ -69,18 +105,31 @@ impl MonitorListModelExt for ListModel {
             .collect()
     }
 
-    fn monitor_map(&self) -> Result<MonitorMap, MonitorError> {
+    fn keyed_monitors(&self) -> Result<Vec<(String, Monitor)>, MonitorError> {
+        let mut unknown = 0;
         Ok(self
             .monitor_vec()?
-            .iter()
+            .into_iter()
             .map(|m| {
+                // Each monitor without a connector keeps an entry of its own;
+                // configs can still reach them by model.
                 let connector = match m.connector() {
                     Some(connector) => connector.to_string(),
-                    None => "Unknown".to_string(),
+                    None => {
+                        unknown += 1;
+                        format!("Unknown-{unknown}")
+                    }
                 };
-
-                (connector, m.into())
+                (connector, m)
             })
             .collect())
     }
+
+    fn monitor_map(&self) -> Result<MonitorMap, MonitorError> {
+        Ok(self
+            .keyed_monitors()?
+            .iter()
+            .map(|(connector, m)| (connector.clone(), m.into()))
+            .collect())
+    }
 }
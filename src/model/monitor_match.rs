@@ -0,0 +1,293 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::{HashMap, HashSet};
+
+use crate::model::{MonitorConfig, MonitorInfo, MonitorMap, WallpaperConfig, WallpaperMode};
+
+/// The `monitor` name of an entry (or group member) for every monitor no
+/// other name matches.
+pub const ANY_MONITOR: &str = "*";
+
+impl MonitorInfo {
+    /// Whether `name` is what this monitor reports about itself: its
+    /// description, its model, or its manufacturer and model
+    /// (`"Dell Inc. DELL U2720Q"`).
+    pub fn is_named(&self, name: &str) -> bool {
+        let model = self.model.as_deref();
+        self.description.as_deref() == Some(name)
+            || model == Some(name)
+            || matches!(
+                (&self.manufacturer, model),
+                (Some(manufacturer), Some(model)) if format!("{manufacturer} {model}") == name
+            )
+    }
+}
+
+/// The connected monitor a config name stands for: the one with that
+/// connector, else the first (by connector) whose identity it is.
+pub fn find_monitor<'a>(
+    name: &str,
+    monitors: &'a MonitorMap,
+) -> Option<(&'a String, &'a MonitorInfo)> {
+    monitors.get_key_value(name).or_else(|| {
+        let mut named: Vec<_> = monitors
+            .iter()
+            .filter(|(_, info)| info.is_named(name))
+            .collect();
+        named.sort_by_key(|(connector, _)| *connector);
+        named.into_iter().next()
+    })
+}
+
+/// Hands connected monitors out to the names a config uses for them, each
+/// monitor once.
+struct Matcher<'a> {
+    /// Sorted by connector, so identical monitors go out in port order.
+    monitors: Vec<(&'a String, &'a MonitorInfo)>,
+    claimed: HashSet<&'a str>,
+    /// The connector each identity name first went to, for `clone_source`.
+    resolved: HashMap<String, String>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(monitors: &'a MonitorMap) -> Self {
+        let mut monitors: Vec<_> = monitors.iter().collect();
+        monitors.sort_by_key(|(connector, _)| *connector);
+        Self {
+            monitors,
+            claimed: HashSet::new(),
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Claim the monitor `name` is the connector of, if any.
+    fn claim_connector(&mut self, name: &str) -> bool {
+        match self
+            .monitors
+            .iter()
+            .find(|(connector, _)| *connector == name)
+        {
+            Some((connector, _)) => {
+                self.claimed.insert(connector.as_str());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Point `name` at the first unclaimed monitor it is the identity of,
+    /// claiming it. Leaves names that match nothing alone.
+    fn claim_identity(&mut self, name: &mut String) {
+        let monitor = self.monitors.iter().find(|(connector, info)| {
+            !self.claimed.contains(connector.as_str()) && info.is_named(name)
+        });
+        if let Some((connector, _)) = monitor {
+            self.claimed.insert(connector.as_str());
+            self.resolved
+                .entry(name.clone())
+                .or_insert_with(|| connector.to_string());
+            *name = connector.to_string();
+        }
+    }
+
+    /// Claim every monitor left.
+    fn claim_rest(&mut self) -> Vec<String> {
+        let rest: Vec<_> = self
+            .monitors
+            .iter()
+            .map(|(connector, _)| connector.as_str())
+            .filter(|connector| !self.claimed.contains(connector))
+            .collect();
+        self.claimed.extend(&rest);
+        rest.into_iter().map(String::from).collect()
+    }
+}
+
+impl WallpaperConfig {
+    /// The config with every monitor name replaced by the connector it
+    /// matches on `monitors`. Connector names go first; a monitor's
+    /// identity then takes the first monitor with it that no other name
+    /// took, so two entries naming the same model get one monitor each;
+    /// `*` last expands to every monitor left. Names that match nothing
+    /// stay as they are.
+    pub fn resolve_monitors(&self, monitors: &MonitorMap) -> WallpaperConfig {
+        let mut config = self.clone();
+        let mut matcher = Matcher::new(monitors);
+
+        // Stretch mode spans every monitor whatever the entries say; in
+        // span_groups mode an entry may name a group instead.
+        let entries: Vec<&mut String> = match config.mode {
+            WallpaperMode::StretchSingleWallpaper => Vec::new(),
+            _ => config
+                .monitors
+                .iter_mut()
+                .map(MonitorConfig::monitor_mut)
+                .filter(|name| !self.groups.iter().any(|group| group.name == **name))
+                .collect(),
+        };
+        let members = config
+            .groups
+            .iter_mut()
+            .filter(|_| self.mode == WallpaperMode::SpanGroups)
+            .flat_map(|group| group.monitors.iter_mut());
+        let mut identities = Vec::new();
+        for name in entries.into_iter().chain(members) {
+            if *name != ANY_MONITOR && !matcher.claim_connector(name) {
+                identities.push(name);
+            }
+        }
+        for name in identities {
+            matcher.claim_identity(name);
+        }
+
+        if self.mode != WallpaperMode::StretchSingleWallpaper {
+            config.monitors = config
+                .monitors
+                .into_iter()
+                .flat_map(|entry| match entry.monitor() {
+                    ANY_MONITOR => matcher
+                        .claim_rest()
+                        .into_iter()
+                        .map(|connector| {
+                            let mut entry = entry.clone();
+                            *entry.monitor_mut() = connector;
+                            entry
+                        })
+                        .collect(),
+                    _ => vec![entry],
+                })
+                .collect();
+        }
+        if self.mode == WallpaperMode::SpanGroups {
+            for group in &mut config.groups {
+                group.monitors = std::mem::take(&mut group.monitors)
+                    .into_iter()
+                    .flat_map(|name| match name.as_str() {
+                        ANY_MONITOR => matcher.claim_rest(),
+                        _ => vec![name],
+                    })
+                    .collect();
+            }
+        }
+
+        for entry in &mut config.monitors {
+            if let MonitorConfig::Clone {
+                clone_source: Some(source),
+                ..
+            } = entry
+            {
+                if let Some(connector) = matcher.resolved.get(source) {
+                    *source = connector.clone();
+                }
+            }
+        }
+        config.placements = std::mem::take(&mut config.placements)
+            .into_iter()
+            .map(|(name, placement)| match find_monitor(&name, monitors) {
+                Some((connector, _)) => (connector.clone(), placement),
+                None => (name, placement),
+            })
+            .collect();
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitors() -> MonitorMap {
+        let monitor = |manufacturer: Option<&str>, model: Option<&str>| MonitorInfo {
+            manufacturer: manufacturer.map(Into::into),
+            model: model.map(Into::into),
+            ..Default::default()
+        };
+        MonitorMap::from([
+            (
+                "DP-1".into(),
+                monitor(Some("Dell Inc."), Some("DELL U2720Q")),
+            ),
+            (
+                "DP-2".into(),
+                monitor(Some("Dell Inc."), Some("DELL U2720Q")),
+            ),
+            ("HDMI-1".into(), monitor(Some("LG"), Some("27UK850"))),
+            ("eDP-1".into(), monitor(None, None)),
+        ])
+    }
+
+    fn resolve(json: &str) -> WallpaperConfig {
+        WallpaperConfig::from_json(json)
+            .unwrap()
+            .resolve_monitors(&monitors())
+    }
+
+    fn names(config: &WallpaperConfig) -> Vec<&str> {
+        config.monitors.iter().map(MonitorConfig::monitor).collect()
+    }
+
+    #[test]
+    fn test_resolve_monitors() {
+        // Connector names go first, so the model takes the other DELL.
+        let per_monitor = resolve(
+            r#"{
+                "mode": "wallpaper_per_monitor",
+                "monitors": [
+                    { "monitor": "Dell Inc. DELL U2720Q", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                    { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/b.mp4" },
+                    { "monitor": "*", "wallpaper_type": "video", "filepath": "/c.mp4" },
+                    { "monitor": "HP Z27", "wallpaper_type": "video", "filepath": "/d.mp4" }
+                ]
+            }"#,
+        );
+        assert_eq!(
+            names(&per_monitor),
+            vec!["DP-2", "DP-1", "HDMI-1", "eDP-1", "HP Z27"]
+        );
+
+        let clone = resolve(
+            r#"{
+                "mode": "clone_single_wallpaper",
+                "monitors": [
+                    { "monitor": "27UK850", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                    { "monitor": "*", "clone_source": "27UK850" }
+                ],
+                "placements": { "27UK850": { "offset_x": 10 } }
+            }"#,
+        );
+        assert_eq!(names(&clone), vec!["HDMI-1", "DP-1", "DP-2", "eDP-1"]);
+        assert!(clone.monitors[1..].iter().all(|entry| matches!(
+            entry,
+            MonitorConfig::Clone { clone_source: Some(source), .. } if source == "HDMI-1"
+        )));
+        assert!(clone.placements.contains_key("HDMI-1"));
+
+        let span = resolve(
+            r#"{
+                "mode": "span_groups",
+                "groups": [{ "name": "wall", "monitors": ["DELL U2720Q", "DELL U2720Q"] }],
+                "monitors": [
+                    { "monitor": "wall", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                    { "monitor": "*", "wallpaper_type": "video", "filepath": "/b.mp4" }
+                ]
+            }"#,
+        );
+        assert_eq!(span.groups[0].monitors, vec!["DP-1", "DP-2"]);
+        assert_eq!(names(&span), vec!["wall", "HDMI-1", "eDP-1"]);
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::model::MonitorInfo;
+
+/// How one monitor's slice of a shared canvas (stretch mode, span groups)
+/// departs from its logical geometry: bezels hide part of the canvas
+/// between monitors, and the offset and scale correct by hand what the
+/// geometry gets wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Placement {
+    #[serde(default)]
+    pub bezel: Bezel,
+    /// Canvas pixels to move the monitor's slice by, after bezels. Other
+    /// monitors stay where they are.
+    #[serde(default)]
+    pub offset_x: i32,
+    #[serde(default)]
+    pub offset_y: i32,
+    /// Canvas pixels per logical pixel of the monitor; below 1 on a monitor
+    /// whose pixels are physically smaller than its neighbors'.
+    #[serde(default = "Placement::default_scale")]
+    pub scale: f64,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            bezel: Bezel::default(),
+            offset_x: 0,
+            offset_y: 0,
+            scale: Self::default_scale(),
+        }
+    }
+}
+
+impl Placement {
+    fn default_scale() -> f64 {
+        1.0
+    }
+}
+
+/// The frame around a monitor's panel, on each side. The canvas behind it
+/// is skipped, so a line crossing two monitors stays straight.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Bezel {
+    #[serde(default)]
+    pub left: f64,
+    #[serde(default)]
+    pub right: f64,
+    #[serde(default)]
+    pub top: f64,
+    #[serde(default)]
+    pub bottom: f64,
+    #[serde(default)]
+    pub unit: BezelUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BezelUnit {
+    /// Logical pixels of the monitor.
+    #[default]
+    Px,
+    /// Millimeters, converted with the monitor's reported physical size.
+    Mm,
+}
+
+impl Bezel {
+    /// Left, right, top and bottom sizes in the monitor's logical pixels.
+    /// Millimeters count as 0 on a monitor that reports no physical size.
+    pub fn to_px(&self, info: &MonitorInfo) -> [i32; 4] {
+        let (x_per_unit, y_per_unit) = match self.unit {
+            BezelUnit::Px => (1.0, 1.0),
+            BezelUnit::Mm if info.width_mm > 0 && info.height_mm > 0 => (
+                info.width as f64 / info.width_mm as f64,
+                info.height as f64 / info.height_mm as f64,
+            ),
+            BezelUnit::Mm => (0.0, 0.0),
+        };
+        [
+            self.left * x_per_unit,
+            self.right * x_per_unit,
+            self.top * y_per_unit,
+            self.bottom * y_per_unit,
+        ]
+        .map(|size| size.round() as i32)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        [self.left, self.right, self.top, self.bottom]
+            .iter()
+            .any(|size| *size < 0.0)
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use strum_macros::{Display, EnumString};
+
+/// What to do with the wallpaper under a power condition.
+///
+/// The string representation matches the `on-battery-action` and
+/// `power-saver-action` GSettings keys. Variants are ordered from least to
+/// most restrictive, so when several conditions hold the largest wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, EnumString, Display)]
+#[strum(serialize_all = "kebab_case")]
+pub enum PowerAction {
+    #[default]
+    None,
+    /// Cap every renderer at the `reduced-fps` setting.
+    ReduceFps,
+    /// Hold each renderer on its current frame. Playlists still rotate,
+    /// showing every entry as a still.
+    Static,
+    /// Pause like `Pause()` does; reported as the `power` pause reason.
+    Pause,
+}
+
+/// The power conditions the policy reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PowerState {
+    /// UPower `OnBattery`.
+    pub on_battery: bool,
+    /// power-profiles-daemon's `ActiveProfile` is `power-saver`.
+    pub power_saver: bool,
+}
+
+/// The policy table: an action per condition, plus the cap `ReduceFps`
+/// applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PowerPolicy {
+    pub on_battery: PowerAction,
+    pub power_saver: PowerAction,
+    pub reduced_fps: u32,
+}
+
+impl PowerPolicy {
+    /// The most restrictive action among the conditions that hold.
+    pub fn action(&self, state: PowerState) -> PowerAction {
+        let on_battery = state.on_battery.then_some(self.on_battery);
+        let power_saver = state.power_saver.then_some(self.power_saver);
+        on_battery
+            .into_iter()
+            .chain(power_saver)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The frame-rate cap renderers get for `action`: `Some(0)` holds the
+    /// current frame, `None` lifts the cap. `Pause` leaves the rate alone;
+    /// the renderer is paused instead.
+    pub fn fps_limit(&self, action: PowerAction) -> Option<u32> {
+        match action {
+            PowerAction::None | PowerAction::Pause => None,
+            PowerAction::ReduceFps => Some(self.reduced_fps.max(1)),
+            PowerAction::Static => Some(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr as _;
+
+    #[test]
+    fn test_power_action_strings() {
+        assert_eq!(
+            PowerAction::from_str("reduce-fps").unwrap(),
+            PowerAction::ReduceFps
+        );
+        assert_eq!(PowerAction::from_str("pause").unwrap(), PowerAction::Pause);
+        assert!(PowerAction::from_str("hibernate").is_err());
+        assert_eq!(PowerAction::Static.to_string(), "static");
+        assert_eq!(PowerAction::None.to_string(), "none");
+    }
+
+    #[test]
+    fn test_power_policy_most_restrictive_wins() {
+        let policy = PowerPolicy {
+            on_battery: PowerAction::ReduceFps,
+            power_saver: PowerAction::Static,
+            reduced_fps: 15,
+        };
+        let state = |on_battery, power_saver| PowerState {
+            on_battery,
+            power_saver,
+        };
+
+        assert_eq!(policy.action(state(false, false)), PowerAction::None);
+        assert_eq!(policy.action(state(true, false)), PowerAction::ReduceFps);
+        assert_eq!(policy.action(state(false, true)), PowerAction::Static);
+        assert_eq!(policy.action(state(true, true)), PowerAction::Static);
+
+        assert_eq!(policy.fps_limit(PowerAction::ReduceFps), Some(15));
+        assert_eq!(policy.fps_limit(PowerAction::Static), Some(0));
+        assert_eq!(policy.fps_limit(PowerAction::Pause), None);
+    }
+}
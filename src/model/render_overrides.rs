@@ -0,0 +1,67 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::model::VideoRenderer;
+
+/// How a wallpaper is scaled into its window, as the `content-fit`
+/// GSettings key selects it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentFit {
+    Fill,
+    Contain,
+    Cover,
+}
+
+/// Render settings of one monitor entry that replace the global GSettings
+/// values. Unset fields follow the settings, also when they change at
+/// runtime. Clone entries only use `content_fit`; the rest belongs to the
+/// renderer they mirror.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RenderOverrides {
+    /// Audio volume (0-100).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 0, max = 100))]
+    pub volume: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mute: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_fit: Option<ContentFit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_renderer: Option<VideoRenderer>,
+}
+
+impl RenderOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The keys set here that a clone entry cannot use.
+    pub fn clone_ignored(&self) -> Vec<&'static str> {
+        [
+            ("volume", self.volume.is_some()),
+            ("mute", self.mute.is_some()),
+            ("video_renderer", self.video_renderer.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, set)| set.then_some(key))
+        .collect()
+    }
+}
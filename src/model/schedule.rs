@@ -0,0 +1,322 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::model::MonitorConfig;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Time-of-day wallpaper switching: each slot replaces the config's
+/// `monitors` while it is active. Outside every slot the top-level
+/// `monitors` apply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Schedule {
+    /// Where the sun is observed, for `sunrise`/`sunset` times.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    pub slots: Vec<ScheduleSlot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Location {
+    /// Degrees, north positive.
+    pub latitude: f64,
+    /// Degrees, east positive.
+    pub longitude: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduleSlot {
+    /// Reported over D-Bus as the active slot.
+    pub name: String,
+    pub start: ScheduleTime,
+    /// Exclusive. An end at or before the start wraps past midnight.
+    pub end: ScheduleTime,
+    pub monitors: Vec<MonitorConfig>,
+}
+
+/// A point in the local day: `"HH:MM"`, or `"sunrise"` / `"sunset"` with an
+/// optional offset in minutes (`"sunset-30"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub enum ScheduleTime {
+    /// Minutes since local midnight.
+    Clock(u32),
+    Sunrise(i32),
+    Sunset(i32),
+}
+
+/// Local sunrise and sunset, in minutes since local midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunTimes {
+    pub sunrise: u32,
+    pub sunset: u32,
+}
+
+/// The local date and time a schedule is evaluated at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    /// 1-based, as `GDateTime` reports it.
+    pub day_of_year: u32,
+    /// Minutes since local midnight.
+    pub minute_of_day: u32,
+    /// Offset of local time from UTC, in minutes.
+    pub utc_offset: i32,
+}
+
+impl ScheduleTime {
+    /// Minutes since midnight, or `None` for a sun-relative time when the
+    /// sun doesn't rise or set today (or no location is configured).
+    fn resolve(&self, sun: Option<SunTimes>) -> Option<u32> {
+        let (base, offset) = match *self {
+            Self::Clock(minutes) => return Some(minutes),
+            Self::Sunrise(offset) => (sun?.sunrise, offset),
+            Self::Sunset(offset) => (sun?.sunset, offset),
+        };
+        Some((base as i32 + offset).rem_euclid(MINUTES_PER_DAY as i32) as u32)
+    }
+}
+
+impl TryFrom<String> for ScheduleTime {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        for (name, make) in [
+            ("sunrise", Self::Sunrise as fn(i32) -> Self),
+            ("sunset", Self::Sunset as fn(i32) -> Self),
+        ] {
+            if let Some(rest) = value.strip_prefix(name) {
+                let offset = match rest {
+                    "" => 0,
+                    _ => rest
+                        .trim_start_matches('+')
+                        .parse()
+                        .map_err(|_| format!("invalid {name} offset in {value:?}"))?,
+                };
+                return Ok(make(offset));
+            }
+        }
+        let invalid = || format!("invalid time {value:?}, expected HH:MM, sunrise or sunset");
+        let (hour, minute) = value.split_once(':').ok_or_else(invalid)?;
+        let hour: u32 = hour.parse().map_err(|_| invalid())?;
+        let minute: u32 = minute.parse().map_err(|_| invalid())?;
+        // 24:00 is accepted as the end of the day.
+        if minute > 59 || hour * 60 + minute > MINUTES_PER_DAY {
+            return Err(invalid());
+        }
+        Ok(Self::Clock((hour * 60 + minute) % MINUTES_PER_DAY))
+    }
+}
+
+impl fmt::Display for ScheduleTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, offset) = match *self {
+            Self::Clock(minutes) => return write!(f, "{:02}:{:02}", minutes / 60, minutes % 60),
+            Self::Sunrise(offset) => ("sunrise", offset),
+            Self::Sunset(offset) => ("sunset", offset),
+        };
+        match offset {
+            0 => write!(f, "{name}"),
+            _ => write!(f, "{name}{offset:+}"),
+        }
+    }
+}
+
+impl From<ScheduleTime> for String {
+    fn from(time: ScheduleTime) -> Self {
+        time.to_string()
+    }
+}
+
+impl Schedule {
+    fn sun_times(&self, now: LocalTime) -> Option<SunTimes> {
+        let location = self.location?;
+        sun_times(
+            now.day_of_year,
+            location.latitude,
+            location.longitude,
+            now.utc_offset,
+        )
+    }
+
+    /// Index of the slot active at `now` (the first that matches), if any.
+    pub fn active_slot(&self, now: LocalTime) -> Option<usize> {
+        let sun = self.sun_times(now);
+        let minute = now.minute_of_day;
+        self.slots.iter().position(|slot| {
+            let (Some(start), Some(end)) = (slot.start.resolve(sun), slot.end.resolve(sun)) else {
+                return false;
+            };
+            if start < end {
+                (start..end).contains(&minute)
+            } else {
+                // Wraps past midnight (start == end: the whole day).
+                minute >= start || minute < end
+            }
+        })
+    }
+
+    /// Minutes from `now` until the next slot boundary. Local midnight
+    /// always counts as one, since sunrise and sunset move day to day.
+    pub fn minutes_until_boundary(&self, now: LocalTime) -> u32 {
+        let sun = self.sun_times(now);
+        self.slots
+            .iter()
+            .flat_map(|slot| [slot.start.resolve(sun), slot.end.resolve(sun)])
+            .flatten()
+            .chain([0])
+            .map(|boundary| {
+                match (boundary + MINUTES_PER_DAY - now.minute_of_day) % MINUTES_PER_DAY {
+                    0 => MINUTES_PER_DAY,
+                    until => until,
+                }
+            })
+            .min()
+            .unwrap_or(MINUTES_PER_DAY)
+    }
+}
+
+/// Sunrise and sunset for a day of the year at a location, after NOAA's
+/// general solar position equations (accurate to a few minutes, which is
+/// plenty for switching wallpapers). `None` during polar day or night.
+pub fn sun_times(
+    day_of_year: u32,
+    latitude: f64,
+    longitude: f64,
+    utc_offset: i32,
+) -> Option<SunTimes> {
+    // Fractional year, in radians, at local noon.
+    let gamma = 2.0 * PI / 365.0 * (day_of_year as f64 - 1.0);
+    // Equation of time, in minutes.
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    // Solar declination, in radians.
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    // Hour angle of the sun at the horizon (90.833° zenith accounts for
+    // refraction and the solar disk).
+    let lat = latitude.to_radians();
+    let cos_ha = 90.833f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if !(-1.0..=1.0).contains(&cos_ha) {
+        return None;
+    }
+    let ha = cos_ha.acos().to_degrees();
+
+    let to_local = |utc_minutes: f64| {
+        (utc_minutes.round() as i32 + utc_offset).rem_euclid(MINUTES_PER_DAY as i32) as u32
+    };
+    Some(SunTimes {
+        sunrise: to_local(720.0 - 4.0 * (longitude + ha) - eqtime),
+        sunset: to_local(720.0 - 4.0 * (longitude - ha) - eqtime),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(name: &str, start: &str, end: &str) -> ScheduleSlot {
+        ScheduleSlot {
+            name: name.into(),
+            start: start.to_string().try_into().unwrap(),
+            end: end.to_string().try_into().unwrap(),
+            monitors: vec![],
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> LocalTime {
+        LocalTime {
+            day_of_year: 172,
+            minute_of_day: hour * 60 + minute,
+            utc_offset: 9 * 60,
+        }
+    }
+
+    #[test]
+    fn test_schedule_time_parse() {
+        let parse = |s: &str| ScheduleTime::try_from(s.to_string());
+        assert_eq!(parse("07:30"), Ok(ScheduleTime::Clock(450)));
+        assert_eq!(parse("24:00"), Ok(ScheduleTime::Clock(0)));
+        assert_eq!(parse("sunrise"), Ok(ScheduleTime::Sunrise(0)));
+        assert_eq!(parse("sunset-30"), Ok(ScheduleTime::Sunset(-30)));
+        assert_eq!(parse("sunrise+15"), Ok(ScheduleTime::Sunrise(15)));
+        assert!(parse("25:00").is_err());
+        assert!(parse("noon").is_err());
+        assert_eq!(ScheduleTime::Sunset(-30).to_string(), "sunset-30");
+        assert_eq!(ScheduleTime::Clock(450).to_string(), "07:30");
+    }
+
+    #[test]
+    fn test_sun_times() {
+        // Tokyo at the June solstice: sunrise ~04:25, sunset ~19:00 JST.
+        let sun = sun_times(172, 35.68, 139.69, 9 * 60).unwrap();
+        assert!((4 * 60 + 20..4 * 60 + 30).contains(&sun.sunrise), "{sun:?}");
+        assert!((18 * 60 + 55..19 * 60 + 5).contains(&sun.sunset), "{sun:?}");
+
+        // Midnight sun in Svalbard.
+        assert_eq!(sun_times(172, 78.2, 15.6, 2 * 60), None);
+    }
+
+    #[test]
+    fn test_active_slot_and_boundaries() {
+        let schedule = Schedule {
+            location: None,
+            slots: vec![
+                slot("work", "09:00", "17:00"),
+                slot("night", "22:00", "06:00"),
+            ],
+        };
+
+        assert_eq!(schedule.active_slot(at(12, 0)), Some(0));
+        assert_eq!(schedule.active_slot(at(17, 0)), None);
+        assert_eq!(schedule.active_slot(at(23, 30)), Some(1));
+        assert_eq!(schedule.active_slot(at(3, 0)), Some(1));
+
+        assert_eq!(schedule.minutes_until_boundary(at(12, 0)), 5 * 60);
+        assert_eq!(schedule.minutes_until_boundary(at(23, 30)), 30);
+        assert_eq!(schedule.minutes_until_boundary(at(17, 0)), 5 * 60);
+    }
+
+    #[test]
+    fn test_sun_slots_need_location() {
+        let mut schedule = Schedule {
+            location: None,
+            slots: vec![slot("day", "sunrise", "sunset")],
+        };
+        assert_eq!(schedule.active_slot(at(12, 0)), None);
+
+        schedule.location = Some(Location {
+            latitude: 35.68,
+            longitude: 139.69,
+        });
+        assert_eq!(schedule.active_slot(at(12, 0)), Some(0));
+        assert_eq!(schedule.active_slot(at(21, 0)), None);
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::path::{Path, PathBuf};
+
+/// Texture channels a shader can sample (`iChannel0`–`iChannel3`).
+pub const SHADER_CHANNELS: usize = 4;
+
+/// Declarations a Shadertoy `mainImage` expects to find, and the `main`
+/// that calls it. The `#line` directive keeps compile errors pointing at
+/// the wallpaper's own lines.
+const SHADERTOY_PRELUDE: &str = "#version 330 core
+uniform vec3 iResolution;
+uniform float iTime;
+uniform float iTimeDelta;
+uniform int iFrame;
+uniform vec4 iMouse;
+uniform vec4 iDate;
+uniform vec3 iChannelResolution[4];
+uniform sampler2D iChannel0;
+uniform sampler2D iChannel1;
+uniform sampler2D iChannel2;
+uniform sampler2D iChannel3;
+out vec4 hotaruFragColor;
+#line 1
+";
+
+const SHADERTOY_MAIN: &str = "
+void main() {
+    mainImage(hotaruFragColor, gl_FragCoord.xy);
+}
+";
+
+/// A shader wallpaper file, ready to compile.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShaderSource {
+    /// The complete fragment shader.
+    pub fragment: String,
+    /// The image file bound to each channel, from `// iChannelN: <file>`
+    /// comments; relative paths are relative to the shader's directory.
+    pub channels: [Option<PathBuf>; SHADER_CHANNELS],
+}
+
+impl ShaderSource {
+    /// Read the code of a shader file in `base_dir`. Shadertoy-style code
+    /// (a `mainImage` function) gets the Shadertoy uniforms and a `main`
+    /// added around it; code starting with its own `#version` is taken as
+    /// a complete fragment shader and declares what it uses itself.
+    pub fn parse(code: &str, base_dir: &Path) -> Self {
+        let complete = code
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .is_some_and(|line| line.starts_with("#version"));
+        let fragment = if complete {
+            code.to_string()
+        } else {
+            format!("{SHADERTOY_PRELUDE}{code}{SHADERTOY_MAIN}")
+        };
+
+        let mut channels: [Option<PathBuf>; SHADER_CHANNELS] = Default::default();
+        for line in code.lines() {
+            if let Some((index, file)) = channel_directive(line) {
+                channels[index] = Some(base_dir.join(file));
+            }
+        }
+        ShaderSource { fragment, channels }
+    }
+}
+
+/// The channel index and file of a `// iChannelN: <file>` line.
+fn channel_directive(line: &str) -> Option<(usize, &str)> {
+    let rest = line.trim().strip_prefix("//")?.trim_start();
+    let rest = rest.strip_prefix("iChannel")?;
+    let (index, file) = rest.split_once(':')?;
+    let index: usize = index.trim().parse().ok()?;
+    let file = file.trim();
+    (index < SHADER_CHANNELS && !file.is_empty()).then_some((index, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadertoy_source() {
+        let code = "// iChannel0: noise.png
+//iChannel2 : /textures/abstract.jpg
+// iChannel7: ignored.png
+// iChannel1:
+void mainImage(out vec4 fragColor, in vec2 fragCoord) {
+    fragColor = vec4(fragCoord / iResolution.xy, 0.5 + 0.5 * sin(iTime), 1.0);
+}
+";
+        let source = ShaderSource::parse(code, Path::new("/shaders"));
+        assert!(source.fragment.starts_with("#version 330 core\n"));
+        assert!(source.fragment.contains("uniform float iTime;"));
+        assert!(source.fragment.contains(&format!("#line 1\n{code}")));
+        assert!(source
+            .fragment
+            .contains("mainImage(hotaruFragColor, gl_FragCoord.xy);"));
+        assert_eq!(
+            source.channels,
+            [
+                Some(PathBuf::from("/shaders/noise.png")),
+                None,
+                Some(PathBuf::from("/textures/abstract.jpg")),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complete_shader_source() {
+        let code = "\n#version 300 es\nprecision mediump float;\nout vec4 color;\nvoid main() { color = vec4(1.0); }\n";
+        let source = ShaderSource::parse(code, Path::new("/shaders"));
+        assert_eq!(source.fragment, code);
+        assert_eq!(source.channels, ShaderSource::default().channels);
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use strum_macros::{Display, EnumString};
+
+/// How a window (or a playlist) moves from one wallpaper to the next.
+///
+/// The string representation matches the `transition` GSettings key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display)]
+#[strum(serialize_all = "kebab_case")]
+pub enum TransitionKind {
+    /// Cut straight to the new wallpaper.
+    None,
+    /// Fade the old wallpaper out over the new one.
+    #[default]
+    Crossfade,
+    /// Push the old wallpaper out to the left as the new one follows.
+    Slide,
+    /// Drop the old wallpaper in random blocks.
+    Dissolve,
+}
+
+/// A transition as the settings configure it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    /// Length in milliseconds.
+    pub duration_ms: u32,
+}
+
+impl Transition {
+    /// Whether the new wallpaper simply replaces the old one.
+    pub fn is_instant(&self) -> bool {
+        self.kind == TransitionKind::None || self.duration_ms == 0
+    }
+
+    /// How far along (0 to 1, eased in and out) the transition is after
+    /// `elapsed_us` microseconds.
+    pub fn progress(&self, elapsed_us: i64) -> f64 {
+        if self.is_instant() {
+            return 1.0;
+        }
+        let t = (elapsed_us as f64 / (f64::from(self.duration_ms) * 1000.0)).clamp(0.0, 1.0);
+        // Cubic ease-in-out.
+        if t < 0.5 {
+            4.0 * t * t * t
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        }
+    }
+}
+
+/// The progress (0 to 1) at which a dissolve drops block `index` of the old
+/// wallpaper. Fixed per block and spread evenly, so every block goes once
+/// and the old wallpaper thins out steadily.
+pub fn dissolve_threshold(index: u32) -> f64 {
+    // A 32-bit integer hash (lowbias32) as a cheap, stable shuffle.
+    let mut x = index;
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    f64::from(x) / (f64::from(u32::MAX) + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr as _;
+
+    #[test]
+    fn test_transition_kind_strings() {
+        assert_eq!(
+            TransitionKind::from_str("crossfade").unwrap(),
+            TransitionKind::Crossfade
+        );
+        assert_eq!(
+            TransitionKind::from_str("dissolve").unwrap(),
+            TransitionKind::Dissolve
+        );
+        assert!(TransitionKind::from_str("wipe").is_err());
+        assert_eq!(TransitionKind::Slide.to_string(), "slide");
+        assert_eq!(TransitionKind::None.to_string(), "none");
+    }
+
+    #[test]
+    fn test_transition_progress() {
+        let fade = Transition {
+            kind: TransitionKind::Crossfade,
+            duration_ms: 500,
+        };
+        assert_eq!(fade.progress(0), 0.0);
+        assert_eq!(fade.progress(250_000), 0.5);
+        assert_eq!(fade.progress(500_000), 1.0);
+        assert_eq!(fade.progress(900_000), 1.0);
+        assert!(fade.progress(100_000) < 0.2);
+        assert!(fade.progress(400_000) > 0.8);
+
+        let cut = Transition {
+            kind: TransitionKind::None,
+            duration_ms: 500,
+        };
+        assert!(cut.is_instant());
+        assert_eq!(cut.progress(0), 1.0);
+        assert!(Transition {
+            duration_ms: 0,
+            ..fade
+        }
+        .is_instant());
+    }
+
+    #[test]
+    fn test_dissolve_thresholds_spread() {
+        let thresholds: Vec<f64> = (0..1000).map(dissolve_threshold).collect();
+        assert!(thresholds.iter().all(|t| (0.0..1.0).contains(t)));
+        // Roughly a tenth of the blocks in each tenth of the transition.
+        for decile in 0..10 {
+            let low = f64::from(decile) / 10.0;
+            let count = thresholds
+                .iter()
+                .filter(|&&t| t >= low && t < low + 0.1)
+                .count();
+            assert!((60..140).contains(&count), "{decile}: {count}");
+        }
+    }
+}
@@ -0,0 +1,672 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use strum_macros::Display;
+use thiserror::Error;
+
+use crate::model::{
+    find_monitor, BezelUnit, ConfigError, MonitorConfig, MonitorMap, PlaylistEntry, ScheduleTime,
+    SpanGroup, WallpaperConfig, WallpaperMode, WallpaperSource, WallpaperType, ANY_MONITOR,
+};
+
+/// How much a diagnostic matters. The lowercase string form (strum) is
+/// what D-Bus `ValidateConfig` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// Part of the config is ignored or may not show.
+    Warning,
+    /// The config cannot show what it describes: a blank wallpaper, or no
+    /// window at all.
+    Error,
+}
+
+/// A problem `WallpaperConfig::validate` found.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DiagnosticKind {
+    #[error("{0}")]
+    Parse(String),
+    #[error("no monitors are configured")]
+    NoMonitors,
+    #[error("{0} mode needs a monitor entry with a wallpaper")]
+    NoPrimary(&'static str),
+    #[error("stretch mode takes its wallpaper from the first entry, which has none")]
+    StretchFirstNotPrimary,
+    #[error("monitor {0} is not connected")]
+    UnknownMonitor(String),
+    #[error("monitor {0} is configured more than once")]
+    DuplicateMonitor(String),
+    #[error("only the first wallpaper is shown in {0} mode; this one is ignored")]
+    ExtraPrimary(&'static str),
+    #[error("clone entries are ignored in {0} mode")]
+    CloneIgnored(&'static str),
+    #[error("clone_source {0} is not the primary monitor; the clone is dropped")]
+    CloneSourceNotPrimary(String),
+    #[error("{0} has no effect on a clone entry; only content_fit applies")]
+    CloneOverrideIgnored(&'static str),
+    #[error("workshop_id requires wallpaper_type: wpe")]
+    WorkshopIdWithoutWpe,
+    #[error("a wpe wallpaper cannot be a uri; use filepath or workshop_id")]
+    UriWithWpe,
+    #[error("wpe_properties only apply to a single wpe wallpaper; they are ignored here")]
+    WpePropertiesIgnored,
+    #[error("the playlist is empty")]
+    EmptyPlaylist,
+    #[error("duration 0 is shown as 1 second")]
+    ZeroDuration,
+    #[error("sunrise/sunset times need schedule.location; the slot never activates")]
+    SunTimeWithoutLocation,
+    #[error("groups only apply in span_groups mode")]
+    GroupsIgnored,
+    #[error("group {0} has no monitors")]
+    EmptyGroup(String),
+    #[error("group {0} is defined more than once; the first definition is used")]
+    DuplicateGroup(String),
+    #[error("placements only apply in stretch_single_wallpaper and span_groups mode")]
+    PlacementsIgnored,
+    #[error("scale must be positive; the placement is ignored")]
+    NonPositiveScale,
+    #[error("bezel sizes cannot be negative")]
+    NegativeBezel,
+    #[error("monitor {0} reports no physical size; its mm bezel counts as 0")]
+    NoPhysicalSize(String),
+}
+
+impl DiagnosticKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnknownMonitor(_)
+            | Self::DuplicateMonitor(_)
+            | Self::ExtraPrimary(_)
+            | Self::CloneIgnored(_)
+            | Self::CloneSourceNotPrimary(_)
+            | Self::CloneOverrideIgnored(_)
+            | Self::WpePropertiesIgnored
+            | Self::ZeroDuration
+            | Self::SunTimeWithoutLocation
+            | Self::GroupsIgnored
+            | Self::DuplicateGroup(_)
+            | Self::PlacementsIgnored
+            | Self::NoPhysicalSize(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// A diagnostic and where it applies, as a path into the config JSON
+/// (`monitors[1]`, `schedule.slots[0].monitors[2].playlist[3]`; empty for
+/// the whole config).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub path: String,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    /// The diagnostic for a config that does not even parse.
+    pub fn parse_error(error: &ConfigError) -> Self {
+        let message = match error {
+            ConfigError::Invalid { source, .. } => source.to_string(),
+            _ => error.to_string(),
+        };
+        Self {
+            path: error.path().to_string(),
+            kind: DiagnosticKind::Parse(message),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.as_str() {
+            "" => write!(f, "{}: {}", self.severity(), self.kind),
+            path => write!(f, "{}: {}: {}", self.severity(), path, self.kind),
+        }
+    }
+}
+
+impl WallpaperConfig {
+    /// Everything that would make this config degrade silently when built
+    /// on `monitors`: entries the layout drops, sources the renderers turn
+    /// into a blank wallpaper, schedule slots that never activate.
+    pub fn validate(&self, monitors: &MonitorMap) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut push = |path: String, kind| diagnostics.push(Diagnostic { path, kind });
+
+        check_groups(self.mode, &self.groups, monitors, &mut push);
+        check_placements(self, monitors, &mut push);
+        check_monitors(
+            self.mode,
+            &self.groups,
+            &self.monitors,
+            "monitors",
+            monitors,
+            &mut push,
+        );
+        if let Some(schedule) = &self.schedule {
+            for (i, slot) in schedule.slots.iter().enumerate() {
+                let path = format!("schedule.slots[{i}]");
+                let sun_relative = [slot.start, slot.end]
+                    .iter()
+                    .any(|time| !matches!(time, ScheduleTime::Clock(_)));
+                if sun_relative && schedule.location.is_none() {
+                    push(path.clone(), DiagnosticKind::SunTimeWithoutLocation);
+                }
+                check_monitors(
+                    self.mode,
+                    &self.groups,
+                    &slot.monitors,
+                    &format!("{path}.monitors"),
+                    monitors,
+                    &mut push,
+                );
+            }
+        }
+        diagnostics
+    }
+}
+
+fn check_groups(
+    mode: WallpaperMode,
+    groups: &[SpanGroup],
+    connected: &MonitorMap,
+    push: &mut impl FnMut(String, DiagnosticKind),
+) {
+    if mode != WallpaperMode::SpanGroups {
+        if !groups.is_empty() {
+            push("groups".into(), DiagnosticKind::GroupsIgnored);
+        }
+        return;
+    }
+    let mut names = HashSet::new();
+    for (i, group) in groups.iter().enumerate() {
+        let path = format!("groups[{i}]");
+        if !names.insert(group.name.as_str()) {
+            push(
+                path.clone(),
+                DiagnosticKind::DuplicateGroup(group.name.clone()),
+            );
+        }
+        if group.monitors.is_empty() {
+            push(path.clone(), DiagnosticKind::EmptyGroup(group.name.clone()));
+        }
+        for (j, monitor) in group.monitors.iter().enumerate() {
+            if monitor != ANY_MONITOR && find_monitor(monitor, connected).is_none() {
+                push(
+                    format!("{path}.monitors[{j}]"),
+                    DiagnosticKind::UnknownMonitor(monitor.clone()),
+                );
+            }
+        }
+    }
+}
+
+fn check_placements(
+    config: &WallpaperConfig,
+    connected: &MonitorMap,
+    push: &mut impl FnMut(String, DiagnosticKind),
+) {
+    if let WallpaperMode::WallpaperPerMonitor | WallpaperMode::CloneSingleWallpaper = config.mode {
+        if !config.placements.is_empty() {
+            push("placements".into(), DiagnosticKind::PlacementsIgnored);
+        }
+        return;
+    }
+    for (monitor, placement) in &config.placements {
+        let path = format!("placements.{monitor}");
+        if placement.scale <= 0.0 {
+            push(path.clone(), DiagnosticKind::NonPositiveScale);
+        }
+        if placement.bezel.is_negative() {
+            push(path.clone(), DiagnosticKind::NegativeBezel);
+        }
+        match find_monitor(monitor, connected) {
+            None => push(path, DiagnosticKind::UnknownMonitor(monitor.clone())),
+            Some((_, info)) => {
+                let sized = info.width_mm > 0 && info.height_mm > 0;
+                if placement.bezel.unit == BezelUnit::Mm && !sized {
+                    push(path, DiagnosticKind::NoPhysicalSize(monitor.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// Check one monitor list (the top-level one or a schedule slot's) the way
+/// `WindowLayout` will read it in `mode`.
+fn check_monitors(
+    mode: WallpaperMode,
+    groups: &[SpanGroup],
+    entries: &[MonitorConfig],
+    path: &str,
+    connected: &MonitorMap,
+    push: &mut impl FnMut(String, DiagnosticKind),
+) {
+    if entries.is_empty() {
+        push(path.to_string(), DiagnosticKind::NoMonitors);
+        return;
+    }
+    let mode_name = match mode {
+        WallpaperMode::WallpaperPerMonitor => "wallpaper_per_monitor",
+        WallpaperMode::CloneSingleWallpaper => "clone_single_wallpaper",
+        WallpaperMode::StretchSingleWallpaper => "stretch_single_wallpaper",
+        WallpaperMode::SpanGroups => "span_groups",
+    };
+    let primary = entries
+        .iter()
+        .position(|entry| matches!(entry, MonitorConfig::Primary { .. }));
+    let Some(primary) = primary else {
+        push(path.to_string(), DiagnosticKind::NoPrimary(mode_name));
+        return;
+    };
+    if mode == WallpaperMode::StretchSingleWallpaper && primary != 0 {
+        push(format!("{path}[0]"), DiagnosticKind::StretchFirstNotPrimary);
+        return;
+    }
+    let primary_monitor = entries[primary].monitor();
+
+    let mut seen = HashSet::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let path = format!("{path}[{i}]");
+        let monitor = entry.monitor();
+        // Stretch mode spans every connected monitor; the names are unused.
+        // In span_groups mode a group entry covers its group's monitors,
+        // each only once.
+        let group = groups
+            .iter()
+            .find(|group| mode == WallpaperMode::SpanGroups && group.name == monitor);
+        if let Some(group) = group {
+            if matches!(entry, MonitorConfig::Primary { .. }) {
+                for member in &group.monitors {
+                    if !seen.insert(member.as_str()) {
+                        push(
+                            path.clone(),
+                            DiagnosticKind::DuplicateMonitor(member.clone()),
+                        );
+                    }
+                }
+            }
+        } else if mode != WallpaperMode::StretchSingleWallpaper {
+            let known = monitor == ANY_MONITOR || find_monitor(monitor, connected).is_some();
+            // Entries naming a model get one monitor with it each.
+            let by_identity = known && monitor != ANY_MONITOR && !connected.contains_key(monitor);
+            if !by_identity && !seen.insert(monitor) {
+                push(
+                    path.clone(),
+                    DiagnosticKind::DuplicateMonitor(monitor.into()),
+                );
+            } else if !known {
+                push(path.clone(), DiagnosticKind::UnknownMonitor(monitor.into()));
+            }
+        }
+
+        match entry {
+            MonitorConfig::Primary {
+                wallpaper_type,
+                wallpaper_source,
+                wpe_properties,
+                ..
+            } => {
+                let per_monitor = matches!(
+                    mode,
+                    WallpaperMode::WallpaperPerMonitor | WallpaperMode::SpanGroups
+                );
+                if i != primary && !per_monitor {
+                    push(path.clone(), DiagnosticKind::ExtraPrimary(mode_name));
+                    continue;
+                }
+                check_source(*wallpaper_type, wallpaper_source, &path, push);
+                check_wpe_properties(
+                    *wallpaper_type,
+                    wallpaper_source,
+                    wpe_properties,
+                    &path,
+                    push,
+                );
+            }
+            MonitorConfig::Clone {
+                clone_source,
+                overrides,
+                ..
+            } => match mode {
+                WallpaperMode::WallpaperPerMonitor | WallpaperMode::SpanGroups => {
+                    push(path, DiagnosticKind::CloneIgnored(mode_name));
+                }
+                WallpaperMode::CloneSingleWallpaper => {
+                    if let Some(source) = clone_source.as_ref().filter(|s| *s != primary_monitor) {
+                        push(
+                            path.clone(),
+                            DiagnosticKind::CloneSourceNotPrimary(source.clone()),
+                        );
+                    }
+                    for key in overrides.clone_ignored() {
+                        push(path.clone(), DiagnosticKind::CloneOverrideIgnored(key));
+                    }
+                }
+                WallpaperMode::StretchSingleWallpaper => {}
+            },
+        }
+    }
+}
+
+fn check_source(
+    wallpaper_type: WallpaperType,
+    source: &WallpaperSource,
+    path: &str,
+    push: &mut impl FnMut(String, DiagnosticKind),
+) {
+    match source {
+        WallpaperSource::WorkshopId { .. } if wallpaper_type != WallpaperType::Wpe => {
+            push(path.to_string(), DiagnosticKind::WorkshopIdWithoutWpe);
+        }
+        WallpaperSource::Uri { .. } if wallpaper_type == WallpaperType::Wpe => {
+            push(path.to_string(), DiagnosticKind::UriWithWpe);
+        }
+        WallpaperSource::Playlist { playlist, .. } => {
+            if playlist.is_empty() {
+                push(path.to_string(), DiagnosticKind::EmptyPlaylist);
+            }
+            for (i, entry) in playlist.iter().enumerate() {
+                check_entry(
+                    wallpaper_type,
+                    entry,
+                    &format!("{path}.playlist[{i}]"),
+                    push,
+                );
+            }
+        }
+        WallpaperSource::Directory { duration: 0, .. } => {
+            push(path.to_string(), DiagnosticKind::ZeroDuration);
+        }
+        _ => {}
+    }
+}
+
+fn check_entry(
+    default_type: WallpaperType,
+    entry: &PlaylistEntry,
+    path: &str,
+    push: &mut impl FnMut(String, DiagnosticKind),
+) {
+    if entry.duration == 0 {
+        push(path.to_string(), DiagnosticKind::ZeroDuration);
+    }
+    let wallpaper_type = entry.wallpaper_type.unwrap_or(default_type);
+    check_source(wallpaper_type, &entry.wallpaper_source, path, push);
+    check_wpe_properties(
+        wallpaper_type,
+        &entry.wallpaper_source,
+        &entry.wpe_properties,
+        path,
+        push,
+    );
+}
+
+/// Property values reach one package only: a playlist's entries carry
+/// their own.
+fn check_wpe_properties(
+    wallpaper_type: WallpaperType,
+    source: &WallpaperSource,
+    properties: &BTreeMap<String, serde_json::Value>,
+    path: &str,
+    push: &mut impl FnMut(String, DiagnosticKind),
+) {
+    let single = !matches!(
+        source,
+        WallpaperSource::Playlist { .. } | WallpaperSource::Directory { .. }
+    );
+    if !properties.is_empty() && (wallpaper_type != WallpaperType::Wpe || !single) {
+        push(path.to_string(), DiagnosticKind::WpePropertiesIgnored);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::MonitorInfo;
+
+    fn monitors(names: &[&str]) -> MonitorMap {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let info = MonitorInfo {
+                    x: i as i32 * 1920,
+                    y: 0,
+                    width: 1920,
+                    height: 1080,
+                    ..Default::default()
+                };
+                (name.to_string(), info)
+            })
+            .collect()
+    }
+
+    fn validate(json: &str, connected: &[&str]) -> Vec<(String, DiagnosticKind)> {
+        let config: WallpaperConfig = serde_json::from_str(json).unwrap();
+        config
+            .validate(&monitors(connected))
+            .into_iter()
+            .map(|diagnostic| (diagnostic.path, diagnostic.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_sources() {
+        let json = r#"{
+            "mode": "wallpaper_per_monitor",
+            "monitors": [
+                { "monitor": "DP-1", "wallpaper_type": "video", "workshop_id": "123" },
+                { "monitor": "DP-2", "wallpaper_type": "wpe", "uri": "https://example.com" },
+                { "monitor": "DP-3", "wallpaper_type": "web", "playlist": [
+                    { "filepath": "/a.html", "duration": 0, "wpe_properties": { "speed": 8 } },
+                    { "wallpaper_type": "wpe", "workshop_id": "456", "wpe_properties": { "speed": 8 } }
+                ] },
+                { "monitor": "DP-1" }
+            ]
+        }"#;
+        assert_eq!(
+            validate(json, &["DP-1", "DP-2"]),
+            vec![
+                ("monitors[0]".into(), DiagnosticKind::WorkshopIdWithoutWpe),
+                ("monitors[1]".into(), DiagnosticKind::UriWithWpe),
+                (
+                    "monitors[2]".into(),
+                    DiagnosticKind::UnknownMonitor("DP-3".into())
+                ),
+                (
+                    "monitors[2].playlist[0]".into(),
+                    DiagnosticKind::ZeroDuration
+                ),
+                (
+                    "monitors[2].playlist[0]".into(),
+                    DiagnosticKind::WpePropertiesIgnored
+                ),
+                (
+                    "monitors[3]".into(),
+                    DiagnosticKind::DuplicateMonitor("DP-1".into())
+                ),
+                (
+                    "monitors[3]".into(),
+                    DiagnosticKind::CloneIgnored("wallpaper_per_monitor")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_layout_modes() {
+        let clone = r#"{
+            "mode": "clone_single_wallpaper",
+            "monitors": [
+                { "monitor": "DP-1" },
+                { "monitor": "DP-2", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                { "monitor": "DP-3", "wallpaper_type": "video", "filepath": "/b.mp4" },
+                { "monitor": "DP-4", "clone_source": "DP-3", "volume": 20 }
+            ]
+        }"#;
+        assert_eq!(
+            validate(clone, &["DP-1", "DP-2", "DP-3", "DP-4"]),
+            vec![
+                (
+                    "monitors[2]".into(),
+                    DiagnosticKind::ExtraPrimary("clone_single_wallpaper")
+                ),
+                (
+                    "monitors[3]".into(),
+                    DiagnosticKind::CloneSourceNotPrimary("DP-3".into())
+                ),
+                (
+                    "monitors[3]".into(),
+                    DiagnosticKind::CloneOverrideIgnored("volume")
+                ),
+            ]
+        );
+
+        let stretch = r#"{
+            "mode": "stretch_single_wallpaper",
+            "monitors": [
+                { "monitor": "DP-9" },
+                { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4" }
+            ]
+        }"#;
+        assert_eq!(
+            validate(stretch, &["DP-1"]),
+            vec![("monitors[0]".into(), DiagnosticKind::StretchFirstNotPrimary)]
+        );
+
+        let span = r#"{
+            "mode": "span_groups",
+            "groups": [
+                { "name": "wall", "monitors": ["DP-1", "DP-2", "DP-3"] },
+                { "name": "wall", "monitors": [] }
+            ],
+            "monitors": [
+                { "monitor": "DP-2", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                { "monitor": "wall", "wallpaper_type": "video", "filepath": "/b.mp4" },
+                { "monitor": "eDP-1" }
+            ]
+        }"#;
+        assert_eq!(
+            validate(span, &["DP-1", "DP-2", "eDP-1"]),
+            vec![
+                (
+                    "groups[0].monitors[2]".into(),
+                    DiagnosticKind::UnknownMonitor("DP-3".into())
+                ),
+                (
+                    "groups[1]".into(),
+                    DiagnosticKind::DuplicateGroup("wall".into())
+                ),
+                (
+                    "groups[1]".into(),
+                    DiagnosticKind::EmptyGroup("wall".into())
+                ),
+                (
+                    "monitors[1]".into(),
+                    DiagnosticKind::DuplicateMonitor("DP-2".into())
+                ),
+                (
+                    "monitors[2]".into(),
+                    DiagnosticKind::CloneIgnored("span_groups")
+                ),
+            ]
+        );
+
+        let placements = r#"{
+            "mode": "stretch_single_wallpaper",
+            "monitors": [{ "monitor": "STRETCH", "wallpaper_type": "video", "filepath": "/a.mp4" }],
+            "placements": {
+                "DP-1": { "bezel": { "left": -2, "unit": "mm" }, "scale": 0 },
+                "DP-9": { "offset_x": 40 }
+            }
+        }"#;
+        assert_eq!(
+            validate(placements, &["DP-1"]),
+            vec![
+                ("placements.DP-1".into(), DiagnosticKind::NonPositiveScale),
+                ("placements.DP-1".into(), DiagnosticKind::NegativeBezel),
+                (
+                    "placements.DP-1".into(),
+                    DiagnosticKind::NoPhysicalSize("DP-1".into())
+                ),
+                (
+                    "placements.DP-9".into(),
+                    DiagnosticKind::UnknownMonitor("DP-9".into())
+                ),
+            ]
+        );
+
+        let no_primary =
+            r#"{ "mode": "clone_single_wallpaper", "monitors": [{ "monitor": "DP-1" }] }"#;
+        assert_eq!(
+            validate(no_primary, &["DP-1"]),
+            vec![(
+                "monitors".into(),
+                DiagnosticKind::NoPrimary("clone_single_wallpaper")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        let json = r#"{
+            "mode": "wallpaper_per_monitor",
+            "monitors": [
+                { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/day.mp4" }
+            ],
+            "schedule": {
+                "slots": [
+                    { "name": "night", "start": "sunset", "end": "06:00", "monitors": [] }
+                ]
+            }
+        }"#;
+        let diagnostics = validate(json, &["DP-1"]);
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    "schedule.slots[0]".into(),
+                    DiagnosticKind::SunTimeWithoutLocation
+                ),
+                (
+                    "schedule.slots[0].monitors".into(),
+                    DiagnosticKind::NoMonitors
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[0].1.severity(), Severity::Warning);
+        assert_eq!(diagnostics[1].1.severity(), Severity::Error);
+
+        let diagnostic = Diagnostic {
+            path: "monitors[0]".into(),
+            kind: DiagnosticKind::UriWithWpe,
+        };
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: monitors[0]: a wpe wallpaper cannot be a uri; use filepath or workshop_id"
+        );
+    }
+}
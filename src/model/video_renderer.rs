@@ -15,15 +15,31 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
 /// Backend used to render video wallpapers.
 ///
 /// The string representation matches the `video-renderer` GSettings key
-/// ("mpv", "gst-gtk4"). mpv is the default for its performance (notably
-/// working hardware decoding); GstGtk4 is the fallback.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display)]
+/// ("mpv", "gst-gtk4"). Configs use the same strings. mpv is the default
+/// for its performance (notably working hardware decoding); GstGtk4 is the
+/// fallback.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    EnumString,
+    Display,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
 #[strum(serialize_all = "kebab_case")]
+#[serde(rename_all = "kebab-case")]
 pub enum VideoRenderer {
     #[default]
     Mpv,
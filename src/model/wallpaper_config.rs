@@ -15,23 +15,104 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use crate::model::{
+    migrate, ConfigError, ContentFit, Placement, RenderOverrides, Schedule, VideoRenderer,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct WallpaperConfig {
+    /// Format version. Configs written before versioning have none and
+    /// read as version 0.
+    #[serde(default)]
+    pub version: u32,
     pub mode: WallpaperMode,
     pub monitors: Vec<MonitorConfig>,
+    /// Named sets of monitors that share one stretched canvas
+    /// (`span_groups` mode). An entry whose `monitor` is a group name spans
+    /// the group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<SpanGroup>,
+    /// Bezel compensation and manual corrections of where monitors
+    /// (connector names) sit on a stretched canvas.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub placements: BTreeMap<String, Placement>,
+    /// Time-of-day slots whose monitor lists take over from `monitors`
+    /// while they are active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+}
+
+impl WallpaperConfig {
+    /// Parse a config of any supported version, migrating it to the
+    /// current format first. Errors name the offending key's path.
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let mut value: serde_json::Value = serde_json::from_str(json).map_err(ConfigError::Json)?;
+        migrate(&mut value)?;
+        serde_path_to_error::deserialize(value).map_err(|e| match e.path().iter().next() {
+            Some(_) => ConfigError::Invalid {
+                path: e.path().to_string(),
+                source: e.into_inner(),
+            },
+            None => ConfigError::Json(e.into_inner()),
+        })
+    }
+
+    /// The JSON Schema of the current format, for editors.
+    pub fn json_schema() -> schemars::Schema {
+        schemars::schema_for!(WallpaperConfig)
+    }
+
+    /// The config as it applies while schedule slot `slot` is active (or
+    /// outside every slot, for `None`): the slot's monitors replace the
+    /// top-level ones.
+    pub fn effective(&self, slot: Option<usize>) -> WallpaperConfig {
+        let monitors = slot
+            .and_then(|slot| self.schedule.as_ref()?.slots.get(slot))
+            .map_or(&self.monitors, |slot| &slot.monitors);
+        WallpaperConfig {
+            version: self.version,
+            mode: self.mode,
+            monitors: monitors.clone(),
+            groups: self.groups.clone(),
+            placements: self.placements.clone(),
+            schedule: None,
+        }
+    }
+
+    /// The group named `name`; the first one if several share it.
+    pub fn group(&self, name: &str) -> Option<&SpanGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum WallpaperMode {
     WallpaperPerMonitor,
     CloneSingleWallpaper,
     StretchSingleWallpaper,
+    /// Per-monitor wallpapers, except that an entry naming a group stretches
+    /// its wallpaper across the group's monitors.
+    SpanGroups,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Monitors (connector names) that show one wallpaper as a single canvas,
+/// spanning their own bounding box.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SpanGroup {
+    pub name: String,
+    pub monitors: Vec<String>,
+}
+
+/// An entry with a `wallpaper_type` and a source shows that wallpaper; one
+/// without either mirrors another monitor. Deserialized through
+/// `RawEntry`, so a malformed entry says what is wrong with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum MonitorConfig {
     Primary {
@@ -39,15 +120,40 @@ pub enum MonitorConfig {
         wallpaper_type: WallpaperType,
         #[serde(flatten)]
         wallpaper_source: WallpaperSource,
+        #[serde(flatten)]
+        overrides: RenderOverrides,
+        /// Wallpaper Engine user property values by property name, in
+        /// place of the package's defaults.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        wpe_properties: BTreeMap<String, serde_json::Value>,
     },
     Clone {
         monitor: String,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         clone_source: Option<String>,
+        #[serde(flatten)]
+        overrides: RenderOverrides,
     },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl MonitorConfig {
+    /// The monitor this entry is for, as the config names it.
+    pub fn monitor(&self) -> &str {
+        match self {
+            Self::Primary { monitor, .. } | Self::Clone { monitor, .. } => monitor,
+        }
+    }
+
+    pub(crate) fn monitor_mut(&mut self) -> &mut String {
+        match self {
+            Self::Primary { monitor, .. } | Self::Clone { monitor, .. } => monitor,
+        }
+    }
+}
+
+/// Exactly one of `filepath`, `uri`, `workshop_id`, `playlist` and
+/// `directory`, picked by which key is present.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
 #[serde(untagged)]
 #[serde(rename_all = "snake_case")]
 pub enum WallpaperSource {
@@ -62,15 +168,541 @@ pub enum WallpaperSource {
     WorkshopId {
         workshop_id: String,
     },
+    /// A rotation of wallpapers. The renderer layer swaps entries in place
+    /// (see `PlaylistWidget`), so advancing never rebuilds the windows.
+    Playlist {
+        playlist: Vec<PlaylistEntry>,
+        #[serde(default)]
+        order: PlaylistOrder,
+    },
+    /// Every wallpaper found in a directory, rotated like a playlist and
+    /// kept in sync as files are added or removed (see `directory.rs`).
+    Directory {
+        directory: String,
+        #[serde(default)]
+        recursive: bool,
+        /// File extensions to pick up (case-insensitive, no dot); `wpe`
+        /// selects Wallpaper Engine package folders. Empty means every
+        /// supported kind.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        extensions: Vec<String>,
+        /// Seconds each wallpaper is shown.
+        #[serde(default = "PlaylistEntry::default_duration")]
+        duration: u32,
+        #[serde(default)]
+        order: PlaylistOrder,
+    },
+}
+
+/// One item of a playlist source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct PlaylistEntry {
+    /// Defaults to the enclosing monitor's `wallpaper_type`, so a playlist
+    /// of one kind only states it once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallpaper_type: Option<WallpaperType>,
+    #[serde(flatten)]
+    pub wallpaper_source: WallpaperSource,
+    /// How long the entry is shown before advancing, in seconds.
+    #[serde(default = "PlaylistEntry::default_duration")]
+    pub duration: u32,
+    /// Like a monitor entry's `wpe_properties`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub wpe_properties: BTreeMap<String, serde_json::Value>,
+}
+
+impl PlaylistEntry {
+    pub const DEFAULT_DURATION: u32 = 300;
+
+    pub(crate) fn default_duration() -> u32 {
+        Self::DEFAULT_DURATION
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistOrder {
+    #[default]
+    Sequential,
+    /// A fresh random permutation every cycle through the list.
+    Shuffle,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum WallpaperType {
     Video,
+    /// A still or animated (GIF, WebP, APNG where gdk-pixbuf can decode
+    /// it) image.
+    Image,
+    /// A GLSL fragment shader file, Shadertoy-style (`mainImage`) or
+    /// complete (with its own `#version`).
+    Shader,
     Web,
     /// A Wallpaper Engine package (workshop item). Its `project.json` `type`
     /// selects the actual renderer: scene → linux-wallpaperengine, video →
     /// the video renderer, web → the web renderer.
     Wpe,
 }
+
+/// Every key a monitor entry, playlist entry or source may have. Untagged
+/// enums can only report that nothing matched; reading the keys first and
+/// then sorting them into a variant (`into_*`) names the actual problem,
+/// and keeps nested errors at their own path (`flatten` would lose it).
+#[derive(Deserialize)]
+struct RawEntry {
+    monitor: Option<String>,
+    wallpaper_type: Option<WallpaperType>,
+    clone_source: Option<String>,
+    filepath: Option<String>,
+    uri: Option<String>,
+    workshop_id: Option<String>,
+    playlist: Option<Vec<PlaylistEntry>>,
+    directory: Option<String>,
+    recursive: Option<bool>,
+    extensions: Option<Vec<String>>,
+    duration: Option<u32>,
+    order: Option<PlaylistOrder>,
+    volume: Option<i32>,
+    mute: Option<bool>,
+    content_fit: Option<ContentFit>,
+    video_renderer: Option<VideoRenderer>,
+    wpe_properties: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+const SOURCE_KEYS: &str = "filepath, uri, workshop_id, playlist or directory";
+
+impl RawEntry {
+    fn into_monitor(mut self) -> Result<MonitorConfig, String> {
+        let monitor = self.monitor.take().ok_or("missing field `monitor`")?;
+        let source = self.take_source()?;
+        let overrides = self.take_overrides()?;
+        let wpe_properties = self.wpe_properties.take();
+        match (self.wallpaper_type, source) {
+            (Some(wallpaper_type), Some(wallpaper_source)) => {
+                if self.clone_source.is_some() {
+                    return Err(
+                        "clone_source only applies to clone entries, which have no wallpaper_type"
+                            .into(),
+                    );
+                }
+                Ok(MonitorConfig::Primary {
+                    monitor,
+                    wallpaper_type,
+                    wallpaper_source,
+                    overrides,
+                    wpe_properties: wpe_properties.unwrap_or_default(),
+                })
+            }
+            (Some(wallpaper_type), None) => Err(format!(
+                "wallpaper_type {} needs a source: {SOURCE_KEYS}",
+                serde_json::to_string(&wallpaper_type).unwrap_or_default()
+            )),
+            (None, Some(_)) => Err("missing field `wallpaper_type`".into()),
+            (None, None) if wpe_properties.is_some() => {
+                Err("wpe_properties does not apply to clone entries".into())
+            }
+            (None, None) => Ok(MonitorConfig::Clone {
+                monitor,
+                clone_source: self.clone_source,
+                overrides,
+            }),
+        }
+    }
+
+    fn into_playlist_entry(mut self) -> Result<PlaylistEntry, String> {
+        if self.monitor.is_some() || self.clone_source.is_some() {
+            return Err("monitor and clone_source do not apply to playlist entries".into());
+        }
+        if !self.take_overrides()?.is_empty() {
+            return Err(
+                "volume, mute, content_fit and video_renderer only apply to monitor entries".into(),
+            );
+        }
+        // The entry's own duration, not its directory's.
+        let duration = self.duration.take();
+        let wallpaper_source = self
+            .take_source()?
+            .ok_or_else(|| format!("a playlist entry needs a source: {SOURCE_KEYS}"))?;
+        Ok(PlaylistEntry {
+            wallpaper_type: self.wallpaper_type,
+            wallpaper_source,
+            duration: duration.unwrap_or(PlaylistEntry::DEFAULT_DURATION),
+            wpe_properties: self.wpe_properties.unwrap_or_default(),
+        })
+    }
+
+    fn into_source(mut self) -> Result<WallpaperSource, String> {
+        self.take_source()?
+            .ok_or_else(|| format!("expected a source: {SOURCE_KEYS}"))
+    }
+
+    /// The source the keys describe, `None` without any. Fails on two
+    /// sources, or on a source option the source does not take.
+    fn take_source(&mut self) -> Result<Option<WallpaperSource>, String> {
+        let mut sources = Vec::new();
+        if let Some(filepath) = self.filepath.take() {
+            sources.push(("filepath", WallpaperSource::Filepath { filepath }));
+        }
+        if let Some(uri) = self.uri.take() {
+            sources.push(("uri", WallpaperSource::Uri { uri }));
+        }
+        if let Some(workshop_id) = self.workshop_id.take() {
+            sources.push(("workshop_id", WallpaperSource::WorkshopId { workshop_id }));
+        }
+        if let Some(playlist) = self.playlist.take() {
+            let order = self.order.take().unwrap_or_default();
+            sources.push(("playlist", WallpaperSource::Playlist { playlist, order }));
+        }
+        if let Some(directory) = self.directory.take() {
+            let source = WallpaperSource::Directory {
+                directory,
+                recursive: self.recursive.take().unwrap_or_default(),
+                extensions: self.extensions.take().unwrap_or_default(),
+                duration: self
+                    .duration
+                    .take()
+                    .unwrap_or(PlaylistEntry::DEFAULT_DURATION),
+                order: self.order.take().unwrap_or_default(),
+            };
+            sources.push(("directory", source));
+        }
+        if let [(first, _), (second, _), ..] = sources.as_slice() {
+            return Err(format!(
+                "{first} and {second} are exclusive; give one source"
+            ));
+        }
+
+        // Options the source did not take are misplaced.
+        for (key, left) in [
+            ("recursive", self.recursive.is_some()),
+            ("extensions", self.extensions.is_some()),
+            ("duration", self.duration.is_some()),
+        ] {
+            if left {
+                return Err(format!("{key} only applies to directory sources"));
+            }
+        }
+        if self.order.is_some() {
+            return Err("order only applies to playlist and directory sources".into());
+        }
+        Ok(sources.pop().map(|(_, source)| source))
+    }
+
+    fn take_overrides(&mut self) -> Result<RenderOverrides, String> {
+        if let Some(volume) = self.volume.filter(|volume| !(0..=100).contains(volume)) {
+            return Err(format!("volume {volume} is outside 0-100"));
+        }
+        Ok(RenderOverrides {
+            volume: self.volume.take(),
+            mute: self.mute.take(),
+            content_fit: self.content_fit.take(),
+            video_renderer: self.video_renderer.take(),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for MonitorConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawEntry::deserialize(deserializer)?
+            .into_monitor()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for PlaylistEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawEntry::deserialize(deserializer)?
+            .into_playlist_entry()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for WallpaperSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawEntry::deserialize(deserializer)?
+            .into_source()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::CONFIG_VERSION;
+
+    #[test]
+    fn test_playlist_source_from_json() {
+        let json = r#"{
+            "monitor": "DP-1",
+            "wallpaper_type": "video",
+            "playlist": [
+                { "filepath": "/videos/a.mp4", "duration": 60 },
+                { "wallpaper_type": "web", "uri": "https://example.com" }
+            ],
+            "order": "shuffle"
+        }"#;
+        let config: MonitorConfig = serde_json::from_str(json).unwrap();
+
+        let MonitorConfig::Primary {
+            wallpaper_source: WallpaperSource::Playlist { playlist, order },
+            ..
+        } = config
+        else {
+            panic!("Expected a primary monitor with a playlist source");
+        };
+        assert_eq!(order, PlaylistOrder::Shuffle);
+        assert_eq!(
+            playlist,
+            vec![
+                PlaylistEntry {
+                    wallpaper_type: None,
+                    wallpaper_source: WallpaperSource::Filepath {
+                        filepath: "/videos/a.mp4".into(),
+                    },
+                    duration: 60,
+                    wpe_properties: BTreeMap::new(),
+                },
+                PlaylistEntry {
+                    wallpaper_type: Some(WallpaperType::Web),
+                    wallpaper_source: WallpaperSource::Uri {
+                        uri: "https://example.com".into(),
+                    },
+                    duration: PlaylistEntry::DEFAULT_DURATION,
+                    wpe_properties: BTreeMap::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directory_source_defaults() {
+        let json = r#"{ "directory": "/wallpapers" }"#;
+        let source: WallpaperSource = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            source,
+            WallpaperSource::Directory {
+                directory: "/wallpapers".into(),
+                recursive: false,
+                extensions: vec![],
+                duration: PlaylistEntry::DEFAULT_DURATION,
+                order: PlaylistOrder::Sequential,
+            }
+        );
+    }
+
+    #[test]
+    fn test_schedule_effective_config() {
+        let json = r#"{
+            "mode": "wallpaper_per_monitor",
+            "monitors": [
+                { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/videos/day.mp4" }
+            ],
+            "schedule": {
+                "location": { "latitude": 35.68, "longitude": 139.69 },
+                "slots": [
+                    {
+                        "name": "night",
+                        "start": "sunset",
+                        "end": "sunrise",
+                        "monitors": [
+                            { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/videos/night.mp4" }
+                        ]
+                    }
+                ]
+            }
+        }"#;
+        let config: WallpaperConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.schedule.as_ref().unwrap().slots.len(), 1);
+
+        let filepath = |config: &WallpaperConfig| match &config.monitors[0] {
+            MonitorConfig::Primary {
+                wallpaper_source: WallpaperSource::Filepath { filepath },
+                ..
+            } => filepath.clone(),
+            _ => panic!("Expected a filepath primary"),
+        };
+        assert_eq!(filepath(&config.effective(None)), "/videos/day.mp4");
+        assert_eq!(filepath(&config.effective(Some(0))), "/videos/night.mp4");
+        assert_eq!(config.effective(Some(0)).schedule, None);
+    }
+
+    #[test]
+    fn test_from_json_migrates_and_reports_paths() {
+        let config = WallpaperConfig::from_json(
+            r#"{ "mode": "clone_single_wallpaper", "monitors": [{ "monitor": "DP-1" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(
+            config.monitors,
+            vec![MonitorConfig::Clone {
+                monitor: "DP-1".into(),
+                clone_source: None,
+                overrides: RenderOverrides::default(),
+            }]
+        );
+
+        let error = |json: &str| {
+            let error = WallpaperConfig::from_json(json).unwrap_err();
+            (error.path().to_string(), error.to_string())
+        };
+        let monitors = |entry: &str| {
+            format!(r#"{{ "mode": "wallpaper_per_monitor", "monitors": [{entry}] }}"#)
+        };
+        assert_eq!(
+            error(&monitors(r#"{ "monitor": "DP-1", "wallpaper_type": "video" }"#)).1,
+            "monitors[0]: wallpaper_type \"video\" needs a source: filepath, uri, workshop_id, playlist or directory"
+        );
+        assert_eq!(
+            error(&monitors(r#"{ "monitor": "DP-1", "filepath": "/a.mp4" }"#)).1,
+            "monitors[0]: missing field `wallpaper_type`"
+        );
+        assert_eq!(
+            error(&monitors(
+                r#"{ "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4", "uri": "https://example.com" }"#
+            ))
+            .1,
+            "monitors[0]: filepath and uri are exclusive; give one source"
+        );
+        assert_eq!(
+            error(&monitors(
+                r#"{ "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4", "order": "shuffle" }"#
+            ))
+            .1,
+            "monitors[0]: order only applies to playlist and directory sources"
+        );
+        assert_eq!(
+            error(&monitors(
+                r#"{ "monitor": "DP-1", "wallpaper_type": "video", "playlist": [{ "filepath": 1 }] }"#
+            ))
+            .0,
+            "monitors[0].playlist[0].filepath"
+        );
+        assert!(matches!(
+            WallpaperConfig::from_json(
+                r#"{ "version": 99, "mode": "wallpaper_per_monitor", "monitors": [] }"#
+            ),
+            Err(ConfigError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn test_round_trip_is_current_version() {
+        let json = r#"{
+            "mode": "wallpaper_per_monitor",
+            "monitors": [
+                { "monitor": "DP-1", "wallpaper_type": "video", "directory": "/videos", "duration": 60 },
+                { "monitor": "DP-2", "clone_source": "DP-1" }
+            ]
+        }"#;
+        let config = WallpaperConfig::from_json(json).unwrap();
+        let saved = serde_json::to_string(&config).unwrap();
+        assert!(saved.starts_with(&format!(r#"{{"version":{CONFIG_VERSION},"#)));
+        assert_eq!(WallpaperConfig::from_json(&saved).unwrap(), config);
+
+        let schema = serde_json::to_value(WallpaperConfig::json_schema()).unwrap();
+        assert_eq!(schema["required"], serde_json::json!(["mode", "monitors"]));
+        assert!(schema["$defs"]["MonitorConfig"].is_object());
+    }
+
+    #[test]
+    fn test_render_overrides() {
+        let json = r#"{
+            "mode": "clone_single_wallpaper",
+            "monitors": [
+                { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4", "volume": 40, "video_renderer": "gst-gtk4" },
+                { "monitor": "DP-2", "mute": true, "content_fit": "contain" }
+            ]
+        }"#;
+        let config = WallpaperConfig::from_json(json).unwrap();
+        let MonitorConfig::Primary { overrides, .. } = &config.monitors[0] else {
+            panic!("Expected a primary");
+        };
+        assert_eq!(
+            *overrides,
+            RenderOverrides {
+                volume: Some(40),
+                video_renderer: Some(VideoRenderer::GstGtk4),
+                ..Default::default()
+            }
+        );
+        let MonitorConfig::Clone { overrides, .. } = &config.monitors[1] else {
+            panic!("Expected a clone");
+        };
+        assert_eq!(overrides.content_fit, Some(ContentFit::Contain));
+        assert_eq!(overrides.clone_ignored(), vec!["mute"]);
+
+        let saved = serde_json::to_value(&config).unwrap();
+        assert_eq!(saved["monitors"][0]["video_renderer"], "gst-gtk4");
+        assert_eq!(saved["monitors"][1].get("volume"), None);
+
+        let error = |entry: &str| {
+            let json = format!(r#"{{ "mode": "wallpaper_per_monitor", "monitors": [{entry}] }}"#);
+            WallpaperConfig::from_json(&json).unwrap_err().to_string()
+        };
+        assert_eq!(
+            error(
+                r#"{ "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4", "volume": 101 }"#
+            ),
+            "monitors[0]: volume 101 is outside 0-100"
+        );
+        assert_eq!(
+            error(r#"{ "monitor": "DP-1", "wallpaper_type": "video", "playlist": [{ "filepath": "/a.mp4", "mute": true }] }"#),
+            "monitors[0].playlist[0]: volume, mute, content_fit and video_renderer only apply to monitor entries"
+        );
+    }
+
+    #[test]
+    fn test_wpe_properties() {
+        let json = r#"{
+            "mode": "wallpaper_per_monitor",
+            "monitors": [
+                {
+                    "monitor": "DP-1",
+                    "wallpaper_type": "wpe",
+                    "workshop_id": "123",
+                    "wpe_properties": { "speed": 8, "schemecolor": "1 0 0" }
+                },
+                {
+                    "monitor": "DP-2",
+                    "wallpaper_type": "wpe",
+                    "playlist": [{ "workshop_id": "456", "wpe_properties": { "mode": "2" } }]
+                }
+            ]
+        }"#;
+        let config = WallpaperConfig::from_json(json).unwrap();
+        let MonitorConfig::Primary { wpe_properties, .. } = &config.monitors[0] else {
+            panic!("Expected a primary");
+        };
+        assert_eq!(wpe_properties["speed"], serde_json::json!(8));
+        assert_eq!(wpe_properties["schemecolor"], "1 0 0");
+        let MonitorConfig::Primary {
+            wallpaper_source: WallpaperSource::Playlist { playlist, .. },
+            wpe_properties,
+            ..
+        } = &config.monitors[1]
+        else {
+            panic!("Expected a playlist primary");
+        };
+        assert!(wpe_properties.is_empty());
+        assert_eq!(playlist[0].wpe_properties["mode"], "2");
+
+        let saved = serde_json::to_value(&config).unwrap();
+        assert_eq!(saved["monitors"][0]["wpe_properties"]["speed"], 8);
+        assert_eq!(saved["monitors"][1].get("wpe_properties"), None);
+
+        let error = WallpaperConfig::from_json(
+            r#"{ "mode": "clone_single_wallpaper", "monitors": [
+                { "monitor": "DP-1", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                { "monitor": "DP-2", "wpe_properties": { "speed": 8 } }
+            ] }"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "monitors[1]: wpe_properties does not apply to clone entries"
+        );
+    }
+}
@@ -15,15 +15,17 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::{BTreeMap, HashSet};
+
 use crate::model::{
-    MonitorConfig, MonitorInfo, MonitorMap, WallpaperConfig, WallpaperMode, WallpaperSource,
-    WallpaperType,
+    MonitorConfig, MonitorInfo, MonitorMap, Placement, RenderOverrides, WallpaperConfig,
+    WallpaperMode, WallpaperSource, WallpaperType,
 };
 
 /// The set of windows to create for a wallpaper config on the current
 /// monitors. Windows are ordered primaries-first, so a consumer building
 /// them in order always has a clone's source renderer available.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WindowLayout {
     pub windows: Vec<WindowInfo>,
 }
@@ -37,8 +39,8 @@ pub struct WindowGeometry {
     pub height: i32,
 }
 
-impl From<MonitorInfo> for WindowGeometry {
-    fn from(info: MonitorInfo) -> Self {
+impl From<&MonitorInfo> for WindowGeometry {
+    fn from(info: &MonitorInfo) -> Self {
         Self {
             x: info.x,
             y: info.y,
@@ -48,17 +50,23 @@ impl From<MonitorInfo> for WindowGeometry {
     }
 }
 
-/// The visible region of an oversized canvas (stretch mode): the child is
-/// allocated at canvas size and shifted by the offset, clipped to the window.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The visible region of an oversized canvas (stretch mode, span groups):
+/// the child is allocated at canvas size and shifted by the offset, clipped
+/// to the window.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Viewport {
     pub offset_x: i32,
     pub offset_y: i32,
     pub canvas_width: i32,
     pub canvas_height: i32,
+    /// How much larger than the canvas to allocate the child, then scale it
+    /// back down: the highest monitor scale on the canvas over this
+    /// monitor's. A renderer on a low-DPI monitor then draws frames sharp
+    /// enough for the clones on denser ones.
+    pub render_scale: f64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WindowInfo {
     /// Connector name of the monitor this window covers (e.g. "DP-1").
     pub monitor: String,
@@ -66,6 +74,9 @@ pub struct WindowInfo {
     pub title: String,
     pub viewport: Option<Viewport>,
     pub role: WindowRole,
+    /// The monitor entry's render overrides. Stretch and span clones, which
+    /// have no entry of their own, get none.
+    pub overrides: RenderOverrides,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -74,6 +85,8 @@ pub enum WindowRole {
     Primary {
         wallpaper_type: WallpaperType,
         wallpaper_source: WallpaperSource,
+        /// The entry's Wallpaper Engine user property values.
+        wpe_properties: BTreeMap<String, serde_json::Value>,
     },
     /// Mirrors the primary renderer of another monitor.
     Clone {
@@ -82,14 +95,40 @@ pub enum WindowRole {
     },
 }
 
+/// What changes between two layouts of the same wallpaper, typically
+/// before and after a monitor hotplug. Windows are matched by monitor; one
+/// keeps its renderer (or mirror) when its role, overrides and whether it
+/// shows a viewport are unchanged, and a clone only when its source does.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LayoutDiff {
+    /// Monitors whose window stays exactly as it is.
+    pub kept: Vec<String>,
+    /// Windows that keep their renderer but get a new geometry, title or
+    /// viewport.
+    pub moved: Vec<WindowInfo>,
+    /// Monitors whose window (and renderer, for a primary) goes away.
+    pub removed: Vec<String>,
+    /// Windows to build, primaries first.
+    pub added: Vec<WindowInfo>,
+}
+
+impl LayoutDiff {
+    /// Whether the new layout's windows are the old ones.
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty() && self.removed.is_empty() && self.added.is_empty()
+    }
+}
+
 impl WindowLayout {
     pub fn new(config: &WallpaperConfig, monitor_map: &MonitorMap) -> Self {
+        let config = &config.resolve_monitors(monitor_map);
         match config.mode {
             WallpaperMode::WallpaperPerMonitor => Self::layout_per_monitor(config, monitor_map),
             WallpaperMode::CloneSingleWallpaper => Self::layout_clone_single(config, monitor_map),
             WallpaperMode::StretchSingleWallpaper => {
                 Self::layout_stretch_single(config, monitor_map)
             }
+            WallpaperMode::SpanGroups => Self::layout_span_groups(config, monitor_map),
         }
     }
 
@@ -101,18 +140,22 @@ impl WindowLayout {
                 monitor,
                 wallpaper_type,
                 wallpaper_source,
+                overrides,
+                wpe_properties,
             } = monitor_config
             {
                 if let Some(info) = monitor_map.get(monitor) {
                     windows.push(WindowInfo {
                         monitor: monitor.clone(),
-                        geometry: (*info).into(),
+                        geometry: info.into(),
                         title: format!("Live Wallpaper - {monitor}"),
                         viewport: None,
                         role: WindowRole::Primary {
                             wallpaper_type: *wallpaper_type,
                             wallpaper_source: wallpaper_source.clone(),
+                            wpe_properties: wpe_properties.clone(),
                         },
+                        overrides: *overrides,
                     })
                 }
             }
@@ -130,6 +173,8 @@ impl WindowLayout {
             monitor,
             wallpaper_type,
             wallpaper_source,
+            overrides,
+            wpe_properties,
         }) = config
             .monitors
             .iter()
@@ -140,13 +185,15 @@ impl WindowLayout {
 
                 windows.push(WindowInfo {
                     monitor: monitor.clone(),
-                    geometry: (*info).into(),
+                    geometry: info.into(),
                     title: format!("Live Wallpaper - {monitor}"),
                     viewport: None,
                     role: WindowRole::Primary {
                         wallpaper_type: *wallpaper_type,
                         wallpaper_source: wallpaper_source.clone(),
+                        wpe_properties: wpe_properties.clone(),
                     },
+                    overrides: *overrides,
                 })
             }
         }
@@ -154,11 +201,14 @@ impl WindowLayout {
         // Add the clones
         if let Some(primary_monitor) = primary {
             for monitor_config in &config.monitors {
-                if let MonitorConfig::Clone { monitor, .. } = monitor_config {
+                if let MonitorConfig::Clone {
+                    monitor, overrides, ..
+                } = monitor_config
+                {
                     if let Some(info) = monitor_map.get(monitor) {
                         windows.push(WindowInfo {
                             monitor: monitor.clone(),
-                            geometry: (*info).into(),
+                            geometry: info.into(),
                             title: format!(
                                 "Live Wallpaper - {monitor} (Clone of {primary_monitor})"
                             ),
@@ -166,6 +216,7 @@ impl WindowLayout {
                             role: WindowRole::Clone {
                                 source: primary_monitor.clone(),
                             },
+                            overrides: *overrides,
                         })
                     }
                 }
@@ -178,67 +229,310 @@ impl WindowLayout {
     fn layout_stretch_single(config: &WallpaperConfig, monitor_map: &MonitorMap) -> Self {
         let mut windows = Vec::new();
 
-        // Calculate bounding box of all monitors
-        let (box_width, box_height) = monitor_map.values().fold(
-            (0, 0),
-            |acc,
-             MonitorInfo {
-                 x,
-                 y,
-                 width,
-                 height,
-             }| { (acc.0.max(*x + *width), acc.1.max(*y + *height)) },
-        );
-
-        let (wallpaper_type, wallpaper_source) = match config.monitors.first() {
-            Some(MonitorConfig::Primary {
-                wallpaper_type,
-                wallpaper_source,
-                ..
-            }) => (*wallpaper_type, wallpaper_source.clone()),
-            _ => return Self { windows },
-        };
+        let (wallpaper_type, wallpaper_source, overrides, wpe_properties) =
+            match config.monitors.first() {
+                Some(MonitorConfig::Primary {
+                    wallpaper_type,
+                    wallpaper_source,
+                    overrides,
+                    wpe_properties,
+                    ..
+                }) => (
+                    *wallpaper_type,
+                    wallpaper_source.clone(),
+                    *overrides,
+                    wpe_properties.clone(),
+                ),
+                _ => return Self { windows },
+            };
 
         // Create one window per monitor. The first becomes Primary (renders
         // the video), the rest become Clones (mirror the paintable). Each
         // window carries a Viewport describing its offset within the canvas.
         let mut monitors: Vec<_> = monitor_map.iter().collect();
         monitors.sort_by_key(|(name, _)| (*name).clone());
+        let viewports = canvas_viewports(&monitors, &config.placements);
 
         let mut primary_name = None;
 
-        for (monitor_name, info) in monitors {
-            let viewport = Some(Viewport {
-                offset_x: info.x,
-                offset_y: info.y,
-                canvas_width: box_width,
-                canvas_height: box_height,
-            });
-
-            let role = match &primary_name {
+        for ((monitor_name, info), viewport) in monitors.into_iter().zip(viewports) {
+            let (role, overrides) = match &primary_name {
                 None => {
                     primary_name = Some(monitor_name.clone());
-                    WindowRole::Primary {
+                    let role = WindowRole::Primary {
                         wallpaper_type,
                         wallpaper_source: wallpaper_source.clone(),
-                    }
+                        wpe_properties: wpe_properties.clone(),
+                    };
+                    (role, overrides)
+                }
+                Some(primary) => {
+                    let role = WindowRole::Clone {
+                        source: primary.clone(),
+                    };
+                    (role, RenderOverrides::default())
                 }
-                Some(primary) => WindowRole::Clone {
-                    source: primary.clone(),
-                },
             };
 
             windows.push(WindowInfo {
                 monitor: monitor_name.clone(),
-                geometry: (*info).into(),
+                geometry: info.into(),
                 title: format!("Live Wallpaper - {} (Stretch)", monitor_name),
-                viewport,
+                viewport: Some(viewport),
                 role,
+                overrides,
             });
         }
 
         Self { windows }
     }
+
+    fn layout_span_groups(config: &WallpaperConfig, monitor_map: &MonitorMap) -> Self {
+        let mut primaries = Vec::new();
+        let mut clones = Vec::new();
+        // A monitor shows the first entry that covers it, alone or as part
+        // of a group.
+        let mut covered = HashSet::new();
+
+        for monitor_config in &config.monitors {
+            let MonitorConfig::Primary {
+                monitor,
+                wallpaper_type,
+                wallpaper_source,
+                overrides,
+                wpe_properties,
+            } = monitor_config
+            else {
+                continue;
+            };
+            let role = WindowRole::Primary {
+                wallpaper_type: *wallpaper_type,
+                wallpaper_source: wallpaper_source.clone(),
+                wpe_properties: wpe_properties.clone(),
+            };
+
+            let Some(group) = config.group(monitor) else {
+                if let Some(info) = monitor_map.get(monitor) {
+                    if covered.insert(monitor.as_str()) {
+                        primaries.push(WindowInfo {
+                            monitor: monitor.clone(),
+                            geometry: info.into(),
+                            title: format!("Live Wallpaper - {monitor}"),
+                            viewport: None,
+                            role,
+                            overrides: *overrides,
+                        });
+                    }
+                }
+                continue;
+            };
+
+            let members: Vec<_> = group
+                .monitors
+                .iter()
+                .filter_map(|name| Some((name, monitor_map.get(name)?)))
+                .filter(|(name, _)| covered.insert(name.as_str()))
+                .collect();
+            let Some(((primary_name, _), _)) = members.split_first() else {
+                continue;
+            };
+
+            let viewports = canvas_viewports(&members, &config.placements);
+            for ((name, info), viewport) in members.iter().zip(viewports) {
+                let viewport = Some(viewport);
+                let title = format!("Live Wallpaper - {name} (Span {monitor})");
+                if name == primary_name {
+                    primaries.push(WindowInfo {
+                        monitor: (*name).clone(),
+                        geometry: (*info).into(),
+                        title,
+                        viewport,
+                        role: role.clone(),
+                        overrides: *overrides,
+                    });
+                } else {
+                    clones.push(WindowInfo {
+                        monitor: (*name).clone(),
+                        geometry: (*info).into(),
+                        title,
+                        viewport,
+                        role: WindowRole::Clone {
+                            source: (*primary_name).clone(),
+                        },
+                        overrides: RenderOverrides::default(),
+                    });
+                }
+            }
+        }
+
+        primaries.extend(clones);
+        Self { windows: primaries }
+    }
+
+    /// How to turn the windows of `self` into those of `new`.
+    pub fn diff(&self, new: &WindowLayout) -> LayoutDiff {
+        let old: BTreeMap<&str, &WindowInfo> = self
+            .windows
+            .iter()
+            .map(|window| (window.monitor.as_str(), window))
+            .collect();
+        // Primaries come first, so a clone's source is decided by the time
+        // the clone is reached.
+        let mut reused = HashSet::new();
+        let mut diff = LayoutDiff::default();
+        for window in &new.windows {
+            let reusable = old.get(window.monitor.as_str()).is_some_and(|old| {
+                old.role == window.role
+                    && old.overrides == window.overrides
+                    && old.viewport.is_some() == window.viewport.is_some()
+                    && match &window.role {
+                        WindowRole::Primary { .. } => true,
+                        WindowRole::Clone { source } => reused.contains(source.as_str()),
+                    }
+            });
+            if !reusable {
+                diff.added.push(window.clone());
+            } else if old[window.monitor.as_str()] == window {
+                diff.kept.push(window.monitor.clone());
+            } else {
+                diff.moved.push(window.clone());
+            }
+            if reusable {
+                reused.insert(window.monitor.as_str());
+            }
+        }
+        diff.removed = self
+            .windows
+            .iter()
+            .filter(|window| !reused.contains(window.monitor.as_str()))
+            .map(|window| window.monitor.clone())
+            .collect();
+        diff
+    }
+
+    /// Turn the windows of `self` into those of `new` giving every window
+    /// new content, for when a setting all renderers are built with
+    /// changes. Windows on monitors in both layouts are refilled in place.
+    pub fn replace_all(&self, new: &WindowLayout) -> LayoutDiff {
+        LayoutDiff {
+            removed: self.windows.iter().map(|w| w.monitor.clone()).collect(),
+            added: new.windows.clone(),
+            ..LayoutDiff::default()
+        }
+    }
+}
+
+/// Each monitor's view of one canvas shared by `members`, spanning their
+/// bounding box. Without placements every monitor shows the canvas under
+/// its logical geometry; bezels push the monitors after them along, and a
+/// placement's offset and scale move and resize the one monitor's slice.
+fn canvas_viewports(
+    members: &[(&String, &MonitorInfo)],
+    placements: &BTreeMap<String, Placement>,
+) -> Vec<Viewport> {
+    // Monitors left of or above the primary monitor have negative
+    // coordinates; the canvas starts at the top-left corner of them all.
+    let origin = (
+        members.iter().map(|(_, info)| info.x).min().unwrap_or(0),
+        members.iter().map(|(_, info)| info.y).min().unwrap_or(0),
+    );
+    let max_scale = members
+        .iter()
+        .map(|(_, info)| info.scale)
+        .fold(1.0, f64::max);
+
+    // Validation reports a scale that is not positive; it would divide by 0.
+    let placements: Vec<_> = members
+        .iter()
+        .map(|(name, _)| placements.get(*name).copied())
+        .map(|placement| placement.filter(|placement| placement.scale > 0.0))
+        .map(Option::unwrap_or_default)
+        .collect();
+    let mut horizontal = Vec::new();
+    let mut vertical = Vec::new();
+    for ((_, info), placement) in members.iter().zip(&placements) {
+        let [left, right, top, bottom] = placement.bezel.to_px(info);
+        let scaled = |length: i32| (length as f64 * placement.scale).round() as i32;
+        horizontal.push(AxisSpan {
+            start: info.x - origin.0,
+            length: info.width,
+            scaled: scaled(info.width),
+            before: left,
+            after: right,
+        });
+        vertical.push(AxisSpan {
+            start: info.y - origin.1,
+            length: info.height,
+            scaled: scaled(info.height),
+            before: top,
+            after: bottom,
+        });
+    }
+    let xs = spread(&horizontal);
+    let ys = spread(&vertical);
+
+    // The canvas holds every monitor's slice and bezels.
+    let mut canvas_width = 0;
+    let mut canvas_height = 0;
+    for i in 0..members.len() {
+        let (x, y) = (
+            xs[i] + placements[i].offset_x,
+            ys[i] + placements[i].offset_y,
+        );
+        canvas_width = canvas_width
+            .max(xs[i] + horizontal[i].scaled + horizontal[i].after)
+            .max(x + horizontal[i].scaled);
+        canvas_height = canvas_height
+            .max(ys[i] + vertical[i].scaled + vertical[i].after)
+            .max(y + vertical[i].scaled);
+    }
+
+    // A window shows `scale` canvas pixels per pixel, so its viewport is the
+    // canvas shrunk by the scale.
+    (0..members.len())
+        .map(|i| {
+            let unscale = |length: i32| (length as f64 / placements[i].scale).round() as i32;
+            let scale = members[i].1.scale;
+            Viewport {
+                offset_x: unscale(xs[i] + placements[i].offset_x),
+                offset_y: unscale(ys[i] + placements[i].offset_y),
+                canvas_width: unscale(canvas_width),
+                canvas_height: unscale(canvas_height),
+                render_scale: if scale > 0.0 { max_scale / scale } else { 1.0 },
+            }
+        })
+        .collect()
+}
+
+/// A monitor along one axis of the canvas, in its logical pixels (`scaled`
+/// in canvas pixels).
+struct AxisSpan {
+    start: i32,
+    length: i32,
+    scaled: i32,
+    before: i32,
+    after: i32,
+}
+
+/// Canvas positions of `spans` along one axis. A span starts past its own
+/// leading bezel, and no earlier than the far edge of any span that ends
+/// before it starts in the logical layout, so a row of monitors adds up
+/// its bezels and scaled widths once, however many rows there are.
+fn spread(spans: &[AxisSpan]) -> Vec<i32> {
+    let mut order: Vec<_> = (0..spans.len()).collect();
+    order.sort_by_key(|&i| spans[i].start);
+
+    let mut positions = vec![0; spans.len()];
+    for (k, &i) in order.iter().enumerate() {
+        let span = &spans[i];
+        let previous_end = order[..k]
+            .iter()
+            .filter(|&&j| spans[j].start + spans[j].length <= span.start)
+            .map(|&j| positions[j] + spans[j].scaled + spans[j].after)
+            .max();
+        positions[i] = span.before + previous_end.map_or(span.start, |end| end.max(span.start));
+    }
+    positions
 }
 
 #[cfg(test)]
@@ -247,9 +541,12 @@ mod tests {
 
     use std::collections::HashMap;
 
+    use crate::model::{ContentFit, CONFIG_VERSION};
+
     #[test]
     fn test_wallpaper_per_monitor() {
         let config = WallpaperConfig {
+            version: CONFIG_VERSION,
             mode: WallpaperMode::WallpaperPerMonitor,
             monitors: vec![
                 MonitorConfig::Primary {
@@ -258,6 +555,8 @@ mod tests {
                     wallpaper_source: WallpaperSource::Filepath {
                         filepath: "/videos/test.mp4".into(),
                     },
+                    overrides: RenderOverrides::default(),
+                    wpe_properties: BTreeMap::new(),
                 },
                 MonitorConfig::Primary {
                     monitor: "DP-2".into(),
@@ -265,8 +564,13 @@ mod tests {
                     wallpaper_source: WallpaperSource::Uri {
                         uri: "https://example.com".into(),
                     },
+                    overrides: RenderOverrides::default(),
+                    wpe_properties: BTreeMap::new(),
                 },
             ],
+            groups: vec![],
+            placements: Default::default(),
+            schedule: None,
         };
 
         let monitor_map = HashMap::from([
@@ -277,6 +581,7 @@ mod tests {
                     y: 0,
                     width: 1920,
                     height: 1080,
+                    ..Default::default()
                 },
             ),
             (
@@ -286,6 +591,7 @@ mod tests {
                     y: 0,
                     width: 2560,
                     height: 1440,
+                    ..Default::default()
                 },
             ),
         ]);
@@ -334,6 +640,7 @@ mod tests {
     #[test]
     fn test_clone_single_wallpaper() {
         let config = WallpaperConfig {
+            version: CONFIG_VERSION,
             mode: WallpaperMode::CloneSingleWallpaper,
             monitors: vec![
                 MonitorConfig::Primary {
@@ -342,12 +649,21 @@ mod tests {
                     wallpaper_source: WallpaperSource::Filepath {
                         filepath: "/videos/main.mp4".into(),
                     },
+                    overrides: RenderOverrides::default(),
+                    wpe_properties: BTreeMap::new(),
                 },
                 MonitorConfig::Clone {
                     monitor: "DP-2".into(),
                     clone_source: None,
+                    overrides: RenderOverrides {
+                        content_fit: Some(ContentFit::Contain),
+                        ..Default::default()
+                    },
                 },
             ],
+            groups: vec![],
+            placements: Default::default(),
+            schedule: None,
         };
 
         let monitor_map = HashMap::from([
@@ -358,6 +674,7 @@ mod tests {
                     y: 0,
                     width: 1920,
                     height: 1080,
+                    ..Default::default()
                 },
             ),
             (
@@ -367,6 +684,7 @@ mod tests {
                     y: 0,
                     width: 1920,
                     height: 1080,
+                    ..Default::default()
                 },
             ),
         ]);
@@ -387,6 +705,7 @@ mod tests {
                 WindowRole::Clone { source } => {
                     assert_eq!(window.monitor, "DP-2");
                     assert_eq!(source, "DP-1");
+                    assert_eq!(window.overrides.content_fit, Some(ContentFit::Contain));
                     clone_found = true;
                 }
             }
@@ -398,6 +717,7 @@ mod tests {
     #[test]
     fn test_stretch_single_wallpaper() {
         let config = WallpaperConfig {
+            version: CONFIG_VERSION,
             mode: WallpaperMode::StretchSingleWallpaper,
             monitors: vec![MonitorConfig::Primary {
                 monitor: "STRETCH".into(),
@@ -405,7 +725,12 @@ mod tests {
                 wallpaper_source: WallpaperSource::Filepath {
                     filepath: "/videos/wide.mp4".into(),
                 },
+                overrides: RenderOverrides::default(),
+                wpe_properties: BTreeMap::new(),
             }],
+            groups: vec![],
+            placements: Default::default(),
+            schedule: None,
         };
 
         let monitor_map = HashMap::from([
@@ -416,6 +741,7 @@ mod tests {
                     y: 1600,
                     width: 1920,
                     height: 1080,
+                    ..Default::default()
                 },
             ),
             (
@@ -425,6 +751,7 @@ mod tests {
                     y: 600,
                     width: 2560,
                     height: 1440,
+                    ..Default::default()
                 },
             ),
             (
@@ -434,6 +761,7 @@ mod tests {
                     y: 0,
                     width: 1440,
                     height: 2560,
+                    ..Default::default()
                 },
             ),
         ]);
@@ -476,4 +804,334 @@ mod tests {
             assert_eq!(vp.canvas_height, expected_canvas.1);
         }
     }
+
+    #[test]
+    fn test_span_groups() {
+        let json = r#"{
+            "mode": "span_groups",
+            "groups": [{ "name": "wall", "monitors": ["DP-3", "DP-1", "DP-2", "DP-9"] }],
+            "monitors": [
+                { "monitor": "eDP-1", "wallpaper_type": "web", "uri": "https://example.com" },
+                { "monitor": "wall", "wallpaper_type": "video", "filepath": "/videos/wide.mp4", "volume": 30 },
+                { "monitor": "DP-2", "wallpaper_type": "video", "filepath": "/videos/other.mp4" }
+            ]
+        }"#;
+        let config = WallpaperConfig::from_json(json).unwrap();
+
+        let monitor = |x, y, width, height| MonitorInfo {
+            x,
+            y,
+            width,
+            height,
+            ..Default::default()
+        };
+        // A laptop panel below a wall of three monitors that starts at x=1920.
+        let monitor_map = HashMap::from([
+            ("eDP-1".to_string(), monitor(0, 1080, 1920, 1080)),
+            ("DP-1".to_string(), monitor(1920, 0, 1920, 1080)),
+            ("DP-2".to_string(), monitor(3840, 0, 1920, 1080)),
+            ("DP-3".to_string(), monitor(5760, 0, 1080, 1920)),
+        ]);
+
+        let layout = WindowLayout::new(&config, &monitor_map);
+        let summary: Vec<_> = layout
+            .windows
+            .iter()
+            .map(|window| (window.monitor.as_str(), &window.role, &window.viewport))
+            .collect();
+        let span = |offset_x| {
+            Some(Viewport {
+                offset_x,
+                offset_y: 0,
+                canvas_width: 4920,
+                canvas_height: 1920,
+                render_scale: 1.0,
+            })
+        };
+        let wall = WindowRole::Primary {
+            wallpaper_type: WallpaperType::Video,
+            wallpaper_source: WallpaperSource::Filepath {
+                filepath: "/videos/wide.mp4".into(),
+            },
+            wpe_properties: BTreeMap::new(),
+        };
+        let clone = WindowRole::Clone {
+            source: "DP-3".into(),
+        };
+
+        // The group's first connected monitor is its primary, clones come
+        // after every primary, and DP-2 stays in the group that claimed it
+        // first.
+        assert_eq!(summary.len(), 4);
+        assert_eq!(summary[0].0, "eDP-1");
+        assert_eq!(summary[0].2, &None);
+        assert_eq!(summary[1], ("DP-3", &wall, &span(3840)));
+        assert_eq!(summary[2], ("DP-1", &clone, &span(0)));
+        assert_eq!(summary[3], ("DP-2", &clone, &span(1920)));
+        assert_eq!(layout.windows[1].overrides.volume, Some(30));
+        assert_eq!(layout.windows[2].overrides, RenderOverrides::default());
+    }
+
+    #[test]
+    fn test_stretch_bezel_compensation() {
+        let viewports = |json: &str, monitor_map: &MonitorMap| {
+            let config = WallpaperConfig::from_json(json).unwrap();
+            WindowLayout::new(&config, monitor_map)
+                .windows
+                .into_iter()
+                .map(|window| (window.monitor, window.viewport.unwrap()))
+                .collect::<Vec<_>>()
+        };
+        let viewport = |offset_x, offset_y, canvas_width, canvas_height| Viewport {
+            offset_x,
+            offset_y,
+            canvas_width,
+            canvas_height,
+            render_scale: 1.0,
+        };
+
+        // A 1080p laptop panel left of a physically coarser 1440p monitor:
+        // millimeter bezels convert at each monitor's own density, and the
+        // scale makes a DP-1 pixel cover 1.3 canvas pixels.
+        let mixed = r#"{
+            "mode": "stretch_single_wallpaper",
+            "monitors": [{ "monitor": "STRETCH", "wallpaper_type": "video", "filepath": "/a.mp4" }],
+            "placements": {
+                "eDP-1": { "bezel": { "right": 5, "unit": "mm" }, "offset_y": 100 },
+                "DP-1": { "bezel": { "left": 10, "unit": "mm" }, "scale": 1.3 }
+            }
+        }"#;
+        let monitor_map = HashMap::from([
+            (
+                "eDP-1".to_string(),
+                MonitorInfo {
+                    x: 0,
+                    y: 0,
+                    width: 1920,
+                    height: 1080,
+                    width_mm: 344,
+                    height_mm: 194,
+                    ..Default::default()
+                },
+            ),
+            (
+                "DP-1".to_string(),
+                MonitorInfo {
+                    x: 1920,
+                    y: 0,
+                    width: 2560,
+                    height: 1440,
+                    width_mm: 597,
+                    height_mm: 336,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        // eDP-1's 28px bezel and DP-1's 43px one put DP-1 at 1991 on a
+        // 5319x1872 canvas, which DP-1 sees shrunk by its scale.
+        assert_eq!(
+            viewports(mixed, &monitor_map),
+            vec![
+                ("DP-1".to_string(), viewport(1532, 0, 4092, 1440)),
+                ("eDP-1".to_string(), viewport(0, 100, 5319, 1872)),
+            ]
+        );
+
+        // In a 2x2 grid each row and column adds its bezels once.
+        let grid = r#"{
+            "mode": "stretch_single_wallpaper",
+            "monitors": [{ "monitor": "STRETCH", "wallpaper_type": "video", "filepath": "/a.mp4" }],
+            "placements": {
+                "A": { "bezel": { "left": 10, "right": 10, "top": 10, "bottom": 10 } },
+                "B": { "bezel": { "left": 10, "right": 10, "top": 10, "bottom": 10 } },
+                "C": { "bezel": { "left": 10, "right": 10, "top": 10, "bottom": 10 } },
+                "D": { "bezel": { "left": 10, "right": 10, "top": 10, "bottom": 10 } }
+            }
+        }"#;
+        let monitor_map: MonitorMap = [
+            ("A", 0, 0),
+            ("B", 1920, 0),
+            ("C", 0, 1080),
+            ("D", 1920, 1080),
+        ]
+        .into_iter()
+        .map(|(name, x, y)| {
+            let info = MonitorInfo {
+                x,
+                y,
+                width: 1920,
+                height: 1080,
+                ..Default::default()
+            };
+            (name.to_string(), info)
+        })
+        .collect();
+        assert_eq!(
+            viewports(grid, &monitor_map),
+            vec![
+                ("A".to_string(), viewport(10, 10, 3880, 2200)),
+                ("B".to_string(), viewport(1950, 10, 3880, 2200)),
+                ("C".to_string(), viewport(10, 1110, 3880, 2200)),
+                ("D".to_string(), viewport(1950, 1110, 3880, 2200)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stretch_negative_origin_and_scale() {
+        let config = WallpaperConfig::from_json(
+            r#"{
+                "mode": "stretch_single_wallpaper",
+                "monitors": [{ "monitor": "STRETCH", "wallpaper_type": "video", "filepath": "/a.mp4" }]
+            }"#,
+        )
+        .unwrap();
+        // A 2x laptop panel left of the primary monitor and a 1.25x one
+        // above it.
+        let monitor_map = HashMap::from([
+            (
+                "DP-1".to_string(),
+                MonitorInfo {
+                    x: 0,
+                    y: 0,
+                    width: 2560,
+                    height: 1440,
+                    ..Default::default()
+                },
+            ),
+            (
+                "HDMI-1".to_string(),
+                MonitorInfo {
+                    x: 0,
+                    y: -1080,
+                    width: 1920,
+                    height: 1080,
+                    scale: 1.25,
+                    ..Default::default()
+                },
+            ),
+            (
+                "eDP-1".to_string(),
+                MonitorInfo {
+                    x: -1280,
+                    y: 200,
+                    width: 1280,
+                    height: 800,
+                    scale: 2.0,
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let layout = WindowLayout::new(&config, &monitor_map);
+        let viewports: Vec<_> = layout
+            .windows
+            .iter()
+            .map(|window| (window.monitor.as_str(), window.viewport.clone().unwrap()))
+            .collect();
+        let viewport = |offset_x, offset_y, render_scale| Viewport {
+            offset_x,
+            offset_y,
+            canvas_width: 3840,
+            canvas_height: 2520,
+            render_scale,
+        };
+        // The canvas starts at (-1280, -1080), and every renderer draws at
+        // the laptop panel's density.
+        assert_eq!(
+            viewports,
+            vec![
+                ("DP-1", viewport(1280, 1080, 2.0)),
+                ("HDMI-1", viewport(1280, 0, 1.6)),
+                ("eDP-1", viewport(0, 1280, 1.0)),
+            ]
+        );
+        assert_eq!(
+            layout.windows[2].geometry,
+            WindowGeometry {
+                x: -1280,
+                y: 200,
+                width: 1280,
+                height: 800
+            }
+        );
+    }
+
+    #[test]
+    fn test_layout_diff() {
+        let monitor = |x| MonitorInfo {
+            x,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            ..Default::default()
+        };
+        let clone = WallpaperConfig::from_json(
+            r#"{
+                "mode": "clone_single_wallpaper",
+                "monitors": [
+                    { "monitor": "eDP-1", "wallpaper_type": "video", "filepath": "/a.mp4" },
+                    { "monitor": "DP-1" },
+                    { "monitor": "HDMI-1" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let before = HashMap::from([
+            ("eDP-1".to_string(), monitor(0)),
+            ("DP-1".to_string(), monitor(1920)),
+        ]);
+        let old = WindowLayout::new(&clone, &before);
+        assert!(old.diff(&old).is_empty());
+
+        // Plugging in a monitor left of the clone moves the clone; the
+        // primary's window and renderer are untouched.
+        let after = HashMap::from([
+            ("eDP-1".to_string(), monitor(0)),
+            ("HDMI-1".to_string(), monitor(1920)),
+            ("DP-1".to_string(), monitor(3840)),
+        ]);
+        let new = WindowLayout::new(&clone, &after);
+        let diff = old.diff(&new);
+        assert_eq!(diff.kept, ["eDP-1"]);
+        let moved: Vec<_> = diff.moved.iter().map(|w| w.monitor.as_str()).collect();
+        assert_eq!(moved, ["DP-1"]);
+        assert_eq!(diff.moved[0].geometry.x, 3840);
+        let added: Vec<_> = diff.added.iter().map(|w| w.monitor.as_str()).collect();
+        assert_eq!(added, ["HDMI-1"]);
+        assert!(diff.removed.is_empty());
+        // And unplugging it again only closes its window.
+        let diff = new.diff(&old);
+        assert_eq!(diff.removed, ["HDMI-1"]);
+        assert!(diff.added.is_empty());
+
+        // A stretched canvas moves every window when it grows, and
+        // rebuilds everything when another monitor becomes its primary.
+        let stretch = WallpaperConfig::from_json(
+            r#"{
+                "mode": "stretch_single_wallpaper",
+                "monitors": [{ "monitor": "STRETCH", "wallpaper_type": "video", "filepath": "/a.mp4" }]
+            }"#,
+        )
+        .unwrap();
+        let old = WindowLayout::new(&stretch, &before);
+        let diff = old.diff(&WindowLayout::new(&stretch, &after));
+        let moved: Vec<_> = diff.moved.iter().map(|w| w.monitor.as_str()).collect();
+        assert_eq!(moved, ["DP-1", "eDP-1"]);
+        let added: Vec<_> = diff.added.iter().map(|w| w.monitor.as_str()).collect();
+        assert_eq!(added, ["HDMI-1"]);
+
+        let mut after = before.clone();
+        after.insert("DP-0".to_string(), monitor(-1920));
+        let diff = old.diff(&WindowLayout::new(&stretch, &after));
+        assert!(diff.kept.is_empty() && diff.moved.is_empty());
+        assert_eq!(diff.removed, ["DP-1", "eDP-1"]);
+        let added: Vec<_> = diff.added.iter().map(|w| w.monitor.as_str()).collect();
+        assert_eq!(added, ["DP-0", "DP-1", "eDP-1"]);
+
+        let diff = old.replace_all(&old);
+        assert!(diff.kept.is_empty() && diff.moved.is_empty());
+        assert_eq!(diff.removed, ["DP-1", "eDP-1"]);
+        assert_eq!(diff.added, old.windows);
+    }
 }
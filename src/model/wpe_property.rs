@@ -0,0 +1,267 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// A user property a Wallpaper Engine package declares in its
+/// `project.json` (`general.properties`), as `GetWpeProperties` reports it
+/// for settings panels.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WpeProperty {
+    pub name: String,
+    /// `slider`, `bool`, `color`, `combo`, `textinput`, `file`,
+    /// `directory`, or `text` for a plain label.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Display text (the property's `text`): a localization key, plain
+    /// text or HTML.
+    pub label: String,
+    /// What the wallpaper gets: the override when there is one, else the
+    /// default. Null for labels.
+    pub value: Value,
+    pub default: Value,
+    /// Position in the package's settings list.
+    pub order: i64,
+    /// A `combo`'s choices.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<WpePropertyOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    /// A JavaScript expression over other properties' values that decides
+    /// whether the property is shown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+/// One choice of a `combo` property.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WpePropertyOption {
+    pub label: String,
+    pub value: Value,
+}
+
+impl WpeProperty {
+    /// The properties `general.properties` declares, in display order,
+    /// with values from `overrides` where they name one.
+    pub fn parse_all(
+        properties: &Map<String, Value>,
+        overrides: &BTreeMap<String, Value>,
+    ) -> Vec<Self> {
+        let mut parsed: Vec<Self> = properties
+            .iter()
+            .map(|(name, declared)| Self::parse(name, declared, overrides.get(name)))
+            .collect();
+        parsed.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.name.cmp(&b.name)));
+        parsed
+    }
+
+    fn parse(name: &str, declared: &Value, value: Option<&Value>) -> Self {
+        let string = |key| {
+            declared
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let number = |key| declared.get(key).and_then(Value::as_f64);
+        let options = declared
+            .get("options")
+            .and_then(Value::as_array)
+            .map(|options| {
+                options
+                    .iter()
+                    .map(|option| WpePropertyOption {
+                        label: option
+                            .get("label")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                        value: option.get("value").cloned().unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let default = declared.get("value").cloned().unwrap_or_default();
+        Self {
+            name: name.to_string(),
+            kind: string("type").to_ascii_lowercase(),
+            label: string("text"),
+            value: value.cloned().unwrap_or_else(|| default.clone()),
+            default,
+            order: declared.get("order").and_then(Value::as_i64).unwrap_or(0),
+            options,
+            min: number("min"),
+            max: number("max"),
+            step: number("step"),
+            condition: declared
+                .get("condition")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }
+    }
+
+    /// The argument Wallpaper Engine passes to
+    /// `wallpaperPropertyListener.applyUserProperties`: `{name: {value: …}}`
+    /// for every property with a value.
+    pub fn user_properties(properties: &[Self]) -> Value {
+        let entries = properties
+            .iter()
+            .filter(|property| !property.value.is_null())
+            .map(|property| (property.name.clone(), user_property(&property.value)));
+        Value::Object(entries.collect())
+    }
+
+    /// The values that differ from the package defaults, by name.
+    pub fn changed(properties: &[Self]) -> BTreeMap<String, Value> {
+        properties
+            .iter()
+            .filter(|property| !property.value.is_null() && property.value != property.default)
+            .map(|property| (property.name.clone(), property.value.clone()))
+            .collect()
+    }
+}
+
+/// One entry of `applyUserProperties`' argument.
+pub fn user_property(value: &Value) -> Value {
+    serde_json::json!({ "value": value })
+}
+
+/// A property setting as linux-wallpaperengine takes it (`--set-property`):
+/// `name=value`, with strings as they are and other values as JSON.
+pub fn engine_assignment(name: &str, value: &Value) -> String {
+    match value {
+        Value::String(value) => format!("{}={}", name, value),
+        value => format!("{}={}", name, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn declared() -> Map<String, Value> {
+        let Value::Object(properties) = json!({
+            "schemecolor": {
+                "order": 0,
+                "text": "ui_browse_properties_scheme_color",
+                "type": "color",
+                "value": "0 0 0"
+            },
+            "speed": {
+                "order": 2,
+                "text": "Speed",
+                "type": "slider",
+                "min": 0,
+                "max": 10,
+                "step": 0.5,
+                "value": 5
+            },
+            "mode": {
+                "order": 1,
+                "text": "Mode",
+                "type": "Combo",
+                "options": [
+                    { "label": "Calm", "value": "1" },
+                    { "label": "Wild", "value": "2" }
+                ],
+                "value": "1",
+                "condition": "speed.value > 0"
+            },
+            "about": { "order": 3, "text": "<b>Made by someone</b>", "type": "text" }
+        }) else {
+            unreachable!()
+        };
+        properties
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let properties = WpeProperty::parse_all(&declared(), &BTreeMap::new());
+        let names: Vec<&str> = properties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["schemecolor", "mode", "speed", "about"]);
+
+        let mode = &properties[1];
+        assert_eq!(mode.kind, "combo");
+        assert_eq!(mode.label, "Mode");
+        assert_eq!(mode.options.len(), 2);
+        assert_eq!(mode.options[1].label, "Wild");
+        assert_eq!(mode.options[1].value, json!("2"));
+        assert_eq!(mode.condition.as_deref(), Some("speed.value > 0"));
+
+        let speed = &properties[2];
+        assert_eq!(
+            (speed.min, speed.max, speed.step),
+            (Some(0.0), Some(10.0), Some(0.5))
+        );
+        assert_eq!(speed.value, json!(5));
+
+        assert_eq!(properties[3].value, Value::Null);
+        let reported = serde_json::to_value(&properties[3]).unwrap();
+        assert_eq!(reported["type"], "text");
+        assert!(reported.get("options").is_none());
+    }
+
+    #[test]
+    fn test_user_properties_with_overrides() {
+        let overrides = BTreeMap::from([("speed".to_string(), json!(8))]);
+        let properties = WpeProperty::parse_all(&declared(), &overrides);
+        assert_eq!(properties[2].value, json!(8));
+        assert_eq!(properties[2].default, json!(5));
+        assert_eq!(
+            WpeProperty::user_properties(&properties),
+            json!({
+                "schemecolor": { "value": "0 0 0" },
+                "mode": { "value": "1" },
+                "speed": { "value": 8 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_engine_assignments() {
+        let overrides = BTreeMap::from([
+            ("speed".to_string(), json!(8)),
+            ("mode".to_string(), json!("1")),
+            ("schemecolor".to_string(), json!("1 0.5 0")),
+        ]);
+        let properties = WpeProperty::parse_all(&declared(), &overrides);
+        // `mode` keeps its default.
+        let changed = WpeProperty::changed(&properties);
+        assert_eq!(
+            changed,
+            BTreeMap::from([
+                ("schemecolor".to_string(), json!("1 0.5 0")),
+                ("speed".to_string(), json!(8)),
+            ])
+        );
+        let assignments: Vec<String> = changed
+            .iter()
+            .map(|(name, value)| engine_assignment(name, value))
+            .collect();
+        assert_eq!(assignments, ["schemecolor=1 0.5 0", "speed=8"]);
+        assert_eq!(engine_assignment("clock", &json!(true)), "clock=true");
+    }
+}
@@ -0,0 +1,696 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Now-playing information from MPRIS players on the session bus, for
+//! Wallpaper Engine web wallpapers' media listeners.
+//!
+//! A thread follows the bus for `org.mpris.MediaPlayer2.*` names and
+//! starts a watcher for each player, which re-reads the player whenever
+//! its properties change until it leaves the bus. The main thread keeps
+//! the latest state of every player and forwards the current one — the
+//! player that most recently started playing — to subscribers as
+//! Wallpaper Engine media events.
+//! The watchers take the connection as a parameter, so tests can point
+//! them at a fake player on a peer-to-peer connection.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use futures_lite::{future, StreamExt};
+use gtk::{gdk_pixbuf, gio, glib};
+use serde_json::Value;
+use tracing::{debug, info};
+use zbus::zvariant::{self, OwnedValue};
+
+use crate::model::{MediaEventKind, NowPlaying, PlaybackState, Thumbnail};
+
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+trait MprisPlayer {
+    #[zbus(property)]
+    fn metadata(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn position(&self) -> zbus::Result<i64>;
+
+    #[zbus(property)]
+    fn rate(&self) -> zbus::Result<f64>;
+
+    #[zbus(signal)]
+    fn seeked(&self, position: i64) -> zbus::Result<()>;
+}
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+/// How often playing media's position is announced.
+const TIMELINE_INTERVAL: Duration = Duration::from_secs(1);
+/// Album art is scaled down to this many pixels square to pick colours.
+const COLOR_SAMPLE_SIZE: i32 = 16;
+
+const ALL_KINDS: [MediaEventKind; 5] = [
+    MediaEventKind::Status,
+    MediaEventKind::Properties,
+    MediaEventKind::Thumbnail,
+    MediaEventKind::Playback,
+    MediaEventKind::Timeline,
+];
+
+/// News from a player's watcher.
+#[derive(Debug)]
+enum PlayerEvent {
+    /// A player was (re-)read.
+    Update(Watched, Box<NowPlaying>),
+    /// A player jumped to a position.
+    Seeked(Watched, Duration),
+    /// A player's watcher ended (sent last): the player left the bus, or
+    /// its name changed hands and a new watcher took over.
+    Gone(Watched),
+}
+
+/// The player a watcher follows, and the watcher's serial number. Serials
+/// grow with every watcher started, so a player's old watcher, winding
+/// down, cannot overwrite or remove what its new one reported.
+#[derive(Debug, Clone, PartialEq)]
+struct Watched {
+    name: String,
+    serial: u64,
+}
+
+type Events = async_channel::Sender<PlayerEvent>;
+
+/// Keeps its callback receiving media events; dropping it unsubscribes.
+#[must_use = "dropping the subscription unsubscribes at once"]
+pub struct MediaSubscription {
+    id: u64,
+}
+
+impl Drop for MediaSubscription {
+    fn drop(&mut self) {
+        MEDIA.with_borrow_mut(|media| {
+            media.listeners.retain(|(id, _)| *id != self.id);
+            if media.listeners.is_empty() {
+                if let Some(timeline) = media.timeline.take() {
+                    timeline.remove();
+                }
+            }
+        });
+    }
+}
+
+/// Call `f` on the main thread with every media event for the current
+/// player while the returned subscription lives, starting with its full
+/// state (or a stopped, empty one when nothing plays).
+pub fn subscribe(f: impl Fn(MediaEventKind, &Value) + 'static) -> MediaSubscription {
+    let f: Listener = Rc::new(f);
+    let (id, catch_up) = MEDIA.with_borrow_mut(|media| {
+        if !media.started {
+            media.started = true;
+            start();
+        }
+        if media.timeline.is_none() {
+            media.timeline = Some(glib::timeout_add_local(TIMELINE_INTERVAL, || {
+                let events = MEDIA.with_borrow(|media| match media.current_player() {
+                    Some(player) if player.now_playing.state == PlaybackState::Playing => {
+                        media.events(&[MediaEventKind::Timeline])
+                    }
+                    _ => Vec::new(),
+                });
+                dispatch(events);
+                glib::ControlFlow::Continue
+            }));
+        }
+        media.next_id += 1;
+        media.listeners.push((media.next_id, f.clone()));
+        let catch_up: Vec<_> = media
+            .events(&ALL_KINDS)
+            .into_iter()
+            .map(|(kind, event, _)| (kind, event))
+            .collect();
+        (media.next_id, catch_up)
+    });
+    for (kind, event) in catch_up {
+        f(kind, &event);
+    }
+    MediaSubscription { id }
+}
+
+type Listener = Rc<dyn Fn(MediaEventKind, &Value)>;
+
+thread_local! {
+    static MEDIA: RefCell<Media> = RefCell::default();
+}
+
+struct Player {
+    now_playing: NowPlaying,
+    read_at: Instant,
+    /// The serial of the watcher that read it.
+    serial: u64,
+}
+
+#[derive(Default)]
+struct Media {
+    started: bool,
+    listeners: Vec<(u64, Listener)>,
+    next_id: u64,
+    players: HashMap<String, Player>,
+    /// The bus name of the player whose media is announced.
+    current: Option<String>,
+    timeline: Option<glib::SourceId>,
+}
+
+impl Media {
+    fn current_player(&self) -> Option<&Player> {
+        self.current
+            .as_ref()
+            .and_then(|name| self.players.get(name))
+    }
+
+    /// The current player's events of `kinds`, paired with the listeners
+    /// to call.
+    fn events(&self, kinds: &[MediaEventKind]) -> Vec<(MediaEventKind, Value, Vec<Listener>)> {
+        let stopped = NowPlaying::default();
+        let (now_playing, elapsed) = match self.current_player() {
+            Some(player) => (&player.now_playing, player.read_at.elapsed()),
+            None => (&stopped, Duration::ZERO),
+        };
+        let listeners: Vec<Listener> = self.listeners.iter().map(|(_, f)| f.clone()).collect();
+        kinds
+            .iter()
+            .map(|&kind| (kind, now_playing.event(kind, elapsed), listeners.clone()))
+            .collect()
+    }
+
+    /// Take in a watcher's news; returns the events to announce.
+    fn handle(&mut self, event: PlayerEvent) -> Vec<(MediaEventKind, Value, Vec<Listener>)> {
+        match event {
+            PlayerEvent::Update(Watched { name, serial }, now_playing) => {
+                if self
+                    .players
+                    .get(&name)
+                    .is_some_and(|player| player.serial > serial)
+                {
+                    return Vec::new();
+                }
+                let playing = now_playing.state == PlaybackState::Playing;
+                let player = Player {
+                    now_playing: *now_playing,
+                    read_at: Instant::now(),
+                    serial,
+                };
+                let old = self.players.insert(name.clone(), player);
+                if self.current.as_ref() == Some(&name) {
+                    let old = old.map(|old| old.now_playing).unwrap_or_default();
+                    let kinds = self.players[&name].now_playing.changes_from(&old);
+                    self.events(&kinds)
+                } else if playing || self.current_player().is_none() {
+                    debug!("Media integration follows {}", name);
+                    self.current = Some(name);
+                    self.events(&ALL_KINDS[1..])
+                } else {
+                    Vec::new()
+                }
+            }
+            PlayerEvent::Seeked(Watched { name, serial }, position) => {
+                let Some(player) = self
+                    .players
+                    .get_mut(&name)
+                    .filter(|player| player.serial == serial)
+                else {
+                    return Vec::new();
+                };
+                player.now_playing.position = position;
+                player.read_at = Instant::now();
+                if self.current.as_ref() == Some(&name) {
+                    self.events(&[MediaEventKind::Timeline])
+                } else {
+                    Vec::new()
+                }
+            }
+            PlayerEvent::Gone(Watched { name, serial }) => {
+                if self
+                    .players
+                    .get(&name)
+                    .is_none_or(|player| player.serial != serial)
+                {
+                    return Vec::new();
+                }
+                self.players.remove(&name);
+                if self.current.as_ref() != Some(&name) {
+                    return Vec::new();
+                }
+                // Fall back to another player, playing ones first.
+                self.current = self
+                    .players
+                    .iter()
+                    .max_by_key(|(_, player)| player.now_playing.state == PlaybackState::Playing)
+                    .map(|(name, _)| name.clone());
+                self.events(&ALL_KINDS[1..])
+            }
+        }
+    }
+}
+
+fn dispatch(events: Vec<(MediaEventKind, Value, Vec<Listener>)>) {
+    for (kind, event, listeners) in events {
+        for listener in listeners {
+            listener(kind, &event);
+        }
+    }
+}
+
+/// Watch the session bus's players, handling their news on the main
+/// thread.
+fn start() {
+    let (events, received) = async_channel::unbounded();
+    std::thread::spawn(move || {
+        match zbus::blocking::Connection::session().and_then(|conn| watch_players(&conn, &events)) {
+            Ok(()) => debug!("MPRIS watcher finished"),
+            Err(e) => info!("Not watching MPRIS players: {}", e),
+        }
+    });
+    glib::spawn_future_local(async move {
+        while let Ok(event) = received.recv().await {
+            let events = MEDIA.with_borrow_mut(|media| media.handle(event));
+            dispatch(events);
+        }
+    });
+}
+
+/// Follow players appearing on and leaving the bus, watching each.
+fn watch_players(conn: &zbus::blocking::Connection, events: &Events) -> zbus::Result<()> {
+    let dbus = zbus::blocking::fdo::DBusProxy::new(conn)?;
+    // Subscribe before listing, so no player falls in between.
+    let changes = dbus.receive_name_owner_changed()?;
+    let mut watchers = Watchers::default();
+    for name in dbus.list_names()? {
+        if name.starts_with(MPRIS_PREFIX) {
+            watchers.spawn(conn, name.to_string(), events);
+        }
+    }
+    for signal in changes {
+        if events.is_closed() {
+            break;
+        }
+        let args = signal.args()?;
+        let name = args.name().to_string();
+        if !name.starts_with(MPRIS_PREFIX) {
+            continue;
+        }
+        if args.new_owner().is_none() {
+            // Dropping its sender ends the player's watcher, which then
+            // reports the player gone.
+            watchers.stop.remove(&name);
+        } else {
+            watchers.spawn(conn, name, events);
+        }
+    }
+    Ok(())
+}
+
+/// The running player watchers.
+#[derive(Default)]
+struct Watchers {
+    /// Each watched player's stop channel: its watcher runs until the
+    /// sender is dropped.
+    stop: HashMap<String, async_channel::Sender<()>>,
+    /// The last serial handed out.
+    serial: u64,
+}
+
+impl Watchers {
+    /// Watch `name` on a thread of its own, replacing the watcher of an
+    /// earlier owner of the name.
+    fn spawn(&mut self, conn: &zbus::blocking::Connection, name: String, events: &Events) {
+        self.serial += 1;
+        let watched = Watched {
+            name,
+            serial: self.serial,
+        };
+        let (watcher, stop) = async_channel::bounded(1);
+        self.stop.insert(watched.name.clone(), watcher);
+        let conn = conn.clone();
+        let events = events.clone();
+        std::thread::spawn(move || {
+            match watch_player(&conn, &watched, &events, &stop) {
+                Ok(()) => debug!("MPRIS watcher for {} finished", watched.name),
+                Err(e) => info!("Not watching {}: {}", watched.name, e),
+            }
+            let _ = events.send_blocking(PlayerEvent::Gone(watched));
+        });
+    }
+}
+
+async fn player_proxy(
+    conn: &zbus::Connection,
+    name: &str,
+) -> zbus::Result<MprisPlayerProxy<'static>> {
+    MprisPlayerProxy::builder(conn)
+        .destination(name.to_owned())?
+        // Read afresh on every change rather than race the proxy's cache.
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await
+}
+
+/// What woke a player's watcher.
+enum Wake {
+    Changed,
+    Seeked(Duration),
+    /// Told to stop, or the player's signals ended.
+    Done,
+}
+
+/// Send `name`'s state, then again on every property change and its
+/// position on every seek, until `stop` closes.
+fn watch_player(
+    conn: &zbus::blocking::Connection,
+    watched: &Watched,
+    events: &Events,
+    stop: &async_channel::Receiver<()>,
+) -> zbus::Result<()> {
+    let name = watched.name.as_str();
+    zbus::block_on(async {
+        let player = player_proxy(conn.inner(), name).await?;
+        let properties = zbus::fdo::PropertiesProxy::builder(conn.inner())
+            .destination(name.to_owned())?
+            .path(MPRIS_PATH)?
+            .build()
+            .await?;
+        // Subscribe before reading, so no change falls in between.
+        let mut changes = properties.receive_properties_changed().await?;
+        let mut seeks = player.receive_seeked().await?;
+
+        let mut art = ArtCache::default();
+        let update = now_playing(&player, &mut art).await?;
+        if events
+            .send(PlayerEvent::Update(watched.clone(), Box::new(update)))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        loop {
+            let wake = future::or(
+                async {
+                    let _ = stop.recv().await;
+                    Wake::Done
+                },
+                future::or(
+                    async { changes.next().await.map_or(Wake::Done, |_| Wake::Changed) },
+                    async {
+                        // A seek that can't be read re-reads the player.
+                        seeks.next().await.map_or(Wake::Done, |signal| {
+                            signal.args().map_or(Wake::Changed, |args| {
+                                Wake::Seeked(
+                                    Duration::from_micros((*args.position()).max(0) as u64),
+                                )
+                            })
+                        })
+                    },
+                ),
+            )
+            .await;
+            let event = match wake {
+                Wake::Done => break,
+                Wake::Seeked(position) => PlayerEvent::Seeked(watched.clone(), position),
+                Wake::Changed => {
+                    // A player between tracks may briefly fail to answer;
+                    // wait for the next change.
+                    let Ok(update) = now_playing(&player, &mut art).await else {
+                        continue;
+                    };
+                    PlayerEvent::Update(watched.clone(), Box::new(update))
+                }
+            };
+            if events.send(event).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The thumbnail for the last art URL seen, so unchanged art isn't
+/// re-read on every property change.
+#[derive(Default)]
+struct ArtCache {
+    url: String,
+    thumbnail: Option<Thumbnail>,
+}
+
+/// Read a player's state.
+async fn now_playing(
+    player: &MprisPlayerProxy<'_>,
+    art: &mut ArtCache,
+) -> zbus::Result<NowPlaying> {
+    let metadata = player.metadata().await?;
+    let field = |key: &str| metadata.get(key).map(|value| &**value);
+    let art_url = field("mpris:artUrl").map(string).unwrap_or_default();
+    if art_url != art.url {
+        art.thumbnail = thumbnail(&art_url);
+        art.url = art_url.clone();
+    }
+    let length = field("mpris:length").and_then(|value| match value {
+        zvariant::Value::I64(us) => u64::try_from(*us).ok(),
+        zvariant::Value::U64(us) => Some(*us),
+        _ => None,
+    });
+    Ok(NowPlaying {
+        title: field("xesam:title").map(string).unwrap_or_default(),
+        artist: field("xesam:artist")
+            .map(strings)
+            .unwrap_or_default()
+            .join(", "),
+        album_title: field("xesam:album").map(string).unwrap_or_default(),
+        album_artist: field("xesam:albumArtist")
+            .map(strings)
+            .unwrap_or_default()
+            .join(", "),
+        genres: field("xesam:genre").map(strings).unwrap_or_default(),
+        art_url,
+        thumbnail: art.thumbnail.clone(),
+        state: player
+            .playback_status()
+            .await?
+            .parse()
+            .unwrap_or(PlaybackState::Stopped),
+        // Position and Rate are optional for players.
+        position: Duration::from_micros(player.position().await.unwrap_or(0).max(0) as u64),
+        length: length.map(Duration::from_micros),
+        rate: player.rate().await.unwrap_or(1.0),
+    })
+}
+
+fn string(value: &zvariant::Value) -> String {
+    match value {
+        zvariant::Value::Str(s) => s.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// A string list, which some players send as a lone string.
+fn strings(value: &zvariant::Value) -> Vec<String> {
+    match value {
+        zvariant::Value::Array(array) => {
+            array.iter().map(string).filter(|s| !s.is_empty()).collect()
+        }
+        zvariant::Value::Str(s) => vec![s.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// The thumbnail for `art_url`: local art is inlined as a `data:` URL
+/// (pages can't always read the player's cache directory) and sampled for
+/// colours; remote art is passed through as is.
+fn thumbnail(art_url: &str) -> Option<Thumbnail> {
+    if art_url.is_empty() {
+        return None;
+    }
+    let Some(path) = gio::File::for_uri(art_url).path() else {
+        return Some(Thumbnail::new(art_url.to_string(), &[]));
+    };
+    let bytes = std::fs::read(&path).ok()?;
+    let (content_type, _) = gio::content_type_guess(Some(&path), &bytes);
+    let mime = gio::content_type_get_mime_type(&content_type)?;
+    let url = format!("data:{};base64,{}", mime, glib::base64_encode(&bytes));
+    let pixels =
+        gdk_pixbuf::Pixbuf::from_file_at_scale(&path, COLOR_SAMPLE_SIZE, COLOR_SAMPLE_SIZE, false)
+            .map(|pixbuf| pixels(&pixbuf))
+            .unwrap_or_default();
+    Some(Thumbnail::new(url, &pixels))
+}
+
+/// Every pixel's RGB.
+fn pixels(pixbuf: &gdk_pixbuf::Pixbuf) -> Vec<[u8; 3]> {
+    let bytes = pixbuf.read_pixel_bytes();
+    let stride = pixbuf.rowstride() as usize;
+    let channels = pixbuf.n_channels() as usize;
+    let width = pixbuf.width() as usize;
+    bytes
+        .chunks(stride)
+        .take(pixbuf.height() as usize)
+        .flat_map(|row| {
+            row.chunks_exact(channels)
+                .take(width)
+                .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAKE_PLAYER: &str = "org.mpris.MediaPlayer2.fake";
+
+    struct FakePlayer {
+        title: String,
+        playback_status: String,
+    }
+
+    #[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl FakePlayer {
+        #[zbus(property)]
+        fn metadata(&self) -> HashMap<String, OwnedValue> {
+            let value = |value: zvariant::Value| OwnedValue::try_from(value).unwrap();
+            HashMap::from([
+                ("xesam:title".into(), value(self.title.as_str().into())),
+                ("xesam:artist".into(), value(vec!["Band", "Guest"].into())),
+                ("mpris:length".into(), value(180_000_000i64.into())),
+            ])
+        }
+
+        #[zbus(property)]
+        fn playback_status(&self) -> String {
+            self.playback_status.clone()
+        }
+
+        #[zbus(property(emits_changed_signal = "false"))]
+        fn position(&self) -> i64 {
+            42_000_000
+        }
+    }
+
+    /// The fake player on a peer-to-peer connection standing in for the
+    /// session bus, and the watcher's side.
+    fn mock_bus() -> (zbus::blocking::Connection, zbus::blocking::Connection) {
+        crate::test_support::mock_bus(|builder| {
+            builder.serve_at(
+                MPRIS_PATH,
+                FakePlayer {
+                    title: "Song".into(),
+                    playback_status: "Paused".into(),
+                },
+            )
+        })
+    }
+
+    fn next_update(received: &async_channel::Receiver<PlayerEvent>) -> NowPlaying {
+        match received.recv_blocking() {
+            Ok(PlayerEvent::Update(watched, now_playing)) => {
+                assert_eq!(watched.name, FAKE_PLAYER);
+                *now_playing
+            }
+            other => panic!("expected an update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_watcher_follows_fake_player() {
+        let (service, client) = mock_bus();
+        let (events, received) = async_channel::unbounded();
+        let (watcher, stop) = async_channel::bounded(1);
+        let watched = Watched {
+            name: FAKE_PLAYER.into(),
+            serial: 1,
+        };
+        let watching = std::thread::spawn(move || watch_player(&client, &watched, &events, &stop));
+
+        let now_playing = next_update(&received);
+        assert_eq!(now_playing.title, "Song");
+        assert_eq!(now_playing.artist, "Band, Guest");
+        assert_eq!(now_playing.state, PlaybackState::Paused);
+        assert_eq!(now_playing.position, Duration::from_secs(42));
+        assert_eq!(now_playing.length, Some(Duration::from_secs(180)));
+        // The fake player has no Rate.
+        assert_eq!(now_playing.rate, 1.0);
+
+        let player = service
+            .object_server()
+            .interface::<_, FakePlayer>(MPRIS_PATH)
+            .unwrap();
+        player.get_mut().playback_status = "Playing".into();
+        zbus::block_on(
+            player
+                .get()
+                .playback_status_changed(player.signal_emitter()),
+        )
+        .unwrap();
+        assert_eq!(next_update(&received).state, PlaybackState::Playing);
+
+        player.get_mut().title = "Next Song".into();
+        zbus::block_on(player.get().metadata_changed(player.signal_emitter())).unwrap();
+        let now_playing = next_update(&received);
+        assert_eq!(now_playing.title, "Next Song");
+        assert_eq!(
+            now_playing.event(MediaEventKind::Properties, Duration::ZERO)["title"],
+            "Next Song"
+        );
+
+        // The player left the bus: the watcher ends.
+        drop(watcher);
+        watching.join().unwrap().unwrap();
+        assert!(received.recv_blocking().is_err());
+    }
+
+    #[test]
+    fn test_old_watcher_news_is_ignored() {
+        let watched = |serial| Watched {
+            name: FAKE_PLAYER.into(),
+            serial,
+        };
+        let playing = |title: &str| {
+            Box::new(NowPlaying {
+                title: title.into(),
+                state: PlaybackState::Playing,
+                ..Default::default()
+            })
+        };
+        let mut media = Media::default();
+        media.handle(PlayerEvent::Update(watched(1), playing("Old")));
+        // The player restarted: its new watcher reports before the old one
+        // winds down.
+        media.handle(PlayerEvent::Update(watched(2), playing("New")));
+        media.handle(PlayerEvent::Update(watched(1), playing("Stale")));
+        media.handle(PlayerEvent::Seeked(watched(1), Duration::from_secs(9)));
+        media.handle(PlayerEvent::Gone(watched(1)));
+        let current = media.current_player().unwrap();
+        assert_eq!(current.now_playing.title, "New");
+        assert_eq!(current.now_playing.position, Duration::ZERO);
+
+        media.handle(PlayerEvent::Gone(watched(2)));
+        assert!(media.current_player().is_none());
+        assert!(media.players.is_empty());
+    }
+}
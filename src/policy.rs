@@ -0,0 +1,279 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Automatic playback policy: decides when renderers should hold still even
+//! though the user asked for playback.
+//!
+//! Observers run where their source lives — X11 fullscreen tracking and the
+//! login1/screensaver/power listeners on threads of their own, Wayland surface
+//! suspension on the main thread — and report into one [`Policy`] on the
+//! GLib main thread. `RendererState` asks the policy which monitors are
+//! held, and why, whenever it reports a change.
+
+mod power;
+mod session;
+mod x11;
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use gtk::{gdk, gio, glib, prelude::*};
+use tracing::debug;
+
+use crate::model::{MonitorListModelExt as _, PowerState};
+use crate::monitor_watcher::MonitorWatcher;
+use crate::window::HotaruApplicationWindow;
+
+/// Why a monitor's wallpaper is paused. The lowercase string form (strum)
+/// is what D-Bus reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum_macros::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum PauseReason {
+    /// Paused by a client (`Pause`).
+    User,
+    /// Every monitor showing it is covered by a fullscreen window, or its
+    /// surfaces are suspended by the compositor.
+    Covered,
+    Locked,
+    Idle,
+    /// The power policy's `pause` action (on battery or power-saver).
+    Power,
+}
+
+/// A window rectangle in X11 root-window (device pixel) coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Area {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Area {
+    fn contains(&self, other: &Area) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && self.x + self.width >= other.x + other.width
+            && self.y + self.height >= other.y + other.height
+    }
+}
+
+/// Observations reported by the watcher threads.
+#[derive(Debug, PartialEq)]
+enum PolicyEvent {
+    /// The visible fullscreen windows changed.
+    Fullscreen(Vec<Area>),
+    /// logind `LockedHint`.
+    Locked(bool),
+    /// logind `IdleHint`.
+    Idle(bool),
+    /// A screensaver service (by bus name; GNOME's is the lock screen)
+    /// became active or inactive.
+    ScreenSaver(&'static str, bool),
+    /// UPower `OnBattery`.
+    OnBattery(bool),
+    /// Whether power-profiles-daemon's active profile is `power-saver`.
+    PowerSaver(bool),
+}
+
+type Events = async_channel::Sender<PolicyEvent>;
+
+pub struct Policy {
+    inner: Rc<PolicyInner>,
+}
+
+struct PolicyInner {
+    settings: gio::Settings,
+    fullscreen: RefCell<Vec<Area>>,
+    /// Connectors whose wallpaper window the compositor reports suspended.
+    suspended: RefCell<HashSet<String>>,
+    locked: Cell<bool>,
+    idle: Cell<bool>,
+    /// Screensaver services reporting active. Each watched service
+    /// reports on its own, so one that errs or lags cannot clear another.
+    screensavers: RefCell<HashSet<&'static str>>,
+    power: Cell<PowerState>,
+    on_change: Box<dyn Fn()>,
+}
+
+impl Policy {
+    /// Start the observers. `on_change` runs on the main loop whenever an
+    /// observation, one of the `pause-when-*` settings or the power policy
+    /// table changes.
+    pub fn new(settings: &gio::Settings, on_change: impl Fn() + 'static) -> Self {
+        let inner = Rc::new(PolicyInner {
+            settings: settings.clone(),
+            fullscreen: RefCell::new(Vec::new()),
+            suspended: RefCell::new(HashSet::new()),
+            locked: Cell::new(false),
+            idle: Cell::new(false),
+            screensavers: RefCell::new(HashSet::new()),
+            power: Cell::new(PowerState::default()),
+            on_change: Box::new(on_change),
+        });
+
+        for key in [
+            "pause-when-covered",
+            "pause-when-locked",
+            "pause-when-idle",
+            "on-battery-action",
+            "power-saver-action",
+            "reduced-fps",
+        ] {
+            let weak = Rc::downgrade(&inner);
+            settings.connect_changed(Some(key), move |_settings, key| {
+                debug!("Playback policy setting {} changed", key);
+                if let Some(inner) = weak.upgrade() {
+                    (inner.on_change)();
+                }
+            });
+        }
+
+        let (event_tx, event_rx) = async_channel::unbounded();
+        let is_x11 =
+            gdk::Display::default().is_some_and(|display| display.is::<gdk_x11::X11Display>());
+        if is_x11 {
+            x11::spawn(event_tx.clone());
+        }
+        session::spawn(event_tx.clone());
+        power::spawn(event_tx);
+
+        let weak = Rc::downgrade(&inner);
+        glib::spawn_future_local(async move {
+            while let Ok(event) = event_rx.recv().await {
+                let Some(inner) = weak.upgrade() else {
+                    break;
+                };
+                debug!("Policy event: {:?}", event);
+                match event {
+                    PolicyEvent::Fullscreen(areas) => {
+                        inner.fullscreen.replace(areas);
+                    }
+                    PolicyEvent::Locked(locked) => inner.locked.set(locked),
+                    PolicyEvent::Idle(idle) => inner.idle.set(idle),
+                    PolicyEvent::ScreenSaver(service, active) => {
+                        let mut screensavers = inner.screensavers.borrow_mut();
+                        if active {
+                            screensavers.insert(service);
+                        } else {
+                            screensavers.remove(service);
+                        }
+                    }
+                    PolicyEvent::OnBattery(on_battery) => inner.power.set(PowerState {
+                        on_battery,
+                        ..inner.power.get()
+                    }),
+                    PolicyEvent::PowerSaver(power_saver) => inner.power.set(PowerState {
+                        power_saver,
+                        ..inner.power.get()
+                    }),
+                }
+                (inner.on_change)();
+            }
+        });
+
+        Self { inner }
+    }
+
+    /// Track a wallpaper window's `suspended` state, which Wayland
+    /// compositors set when the surface is fully obscured (xdg-shell
+    /// `suspended`). Layer-shell surfaces never get it; there the X11 and
+    /// session observers are all there is.
+    pub fn watch_window(&self, window: &HotaruApplicationWindow) {
+        let weak = Rc::downgrade(&self.inner);
+        window.connect_suspended_notify(move |window| {
+            let Some(inner) = weak.upgrade() else {
+                return;
+            };
+            let connector = window.monitor_connector();
+            let changed = if window.is_suspended() && window.is_visible() {
+                inner.suspended.borrow_mut().insert(connector)
+            } else {
+                inner.suspended.borrow_mut().remove(&connector)
+            };
+            if changed {
+                (inner.on_change)();
+            }
+        });
+    }
+
+    /// Forget the suspended state of windows that were closed by a rebuild.
+    pub fn forget_windows(&self) {
+        self.inner.suspended.borrow_mut().clear();
+    }
+
+    /// Forget the suspended state of the window on `connector`, which an
+    /// incremental rebuild closed.
+    pub fn forget_window(&self, connector: &str) {
+        self.inner.suspended.borrow_mut().remove(connector);
+    }
+
+    /// Reasons that hold every monitor, in priority order.
+    pub fn global_reasons(&self) -> Vec<PauseReason> {
+        let settings = &self.inner.settings;
+        let mut reasons = Vec::new();
+        let locked = self.inner.locked.get() || !self.inner.screensavers.borrow().is_empty();
+        if locked && settings.boolean("pause-when-locked") {
+            reasons.push(PauseReason::Locked);
+        }
+        if self.inner.idle.get() && settings.boolean("pause-when-idle") {
+            reasons.push(PauseReason::Idle);
+        }
+        reasons
+    }
+
+    /// The power conditions last reported by UPower and
+    /// power-profiles-daemon. What they mean for playback is the
+    /// `PowerPolicy` table's call.
+    pub fn power_state(&self) -> PowerState {
+        self.inner.power.get()
+    }
+
+    /// Connectors whose monitor is currently covered.
+    pub fn covered(&self) -> HashSet<String> {
+        if !self.inner.settings.boolean("pause-when-covered") {
+            return HashSet::new();
+        }
+        let mut covered = self.inner.suspended.borrow().clone();
+        let fullscreen = self.inner.fullscreen.borrow();
+        if fullscreen.is_empty() {
+            return covered;
+        }
+        let Ok(monitors) = MonitorWatcher::monitors().and_then(|list| list.monitor_vec()) else {
+            return covered;
+        };
+        for monitor in monitors {
+            let Some(connector) = monitor.connector() else {
+                continue;
+            };
+            // GDK geometry is in logical pixels; X11 reports device pixels.
+            let geometry = monitor.geometry();
+            let scale = monitor.scale_factor();
+            let area = Area {
+                x: geometry.x() * scale,
+                y: geometry.y() * scale,
+                width: geometry.width() * scale,
+                height: geometry.height() * scale,
+            };
+            if fullscreen.iter().any(|window| window.contains(&area)) {
+                covered.insert(connector.to_string());
+            }
+        }
+        covered
+    }
+}
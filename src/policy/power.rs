@@ -0,0 +1,176 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Power tracking on the system bus: UPower's `OnBattery` and
+//! power-profiles-daemon's `ActiveProfile`. The watchers take the
+//! connection as a parameter, so tests can point them at mock services on
+//! a peer-to-peer connection.
+
+use tracing::{debug, info};
+
+use super::{Events, PolicyEvent};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+trait UPower {
+    #[zbus(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+}
+
+#[zbus::proxy(
+    interface = "net.hadess.PowerProfiles",
+    default_service = "net.hadess.PowerProfiles",
+    default_path = "/net/hadess/PowerProfiles"
+)]
+trait PowerProfiles {
+    #[zbus(property)]
+    fn active_profile(&self) -> zbus::Result<String>;
+}
+
+const POWER_SAVER_PROFILE: &str = "power-saver";
+
+type Watcher = fn(&zbus::blocking::Connection, &Events) -> zbus::Result<()>;
+
+pub(super) fn spawn(events: Events) {
+    let watchers: [(&str, Watcher); 2] = [
+        ("UPower OnBattery", watch_on_battery),
+        ("power-profiles-daemon", watch_power_profile),
+    ];
+    for (name, watch) in watchers {
+        let events = events.clone();
+        std::thread::spawn(move || {
+            match zbus::blocking::Connection::system().and_then(|conn| watch(&conn, &events)) {
+                Ok(()) => debug!("{} watcher finished", name),
+                // Expected on desktops without UPower or power profiles.
+                Err(e) => info!("Not watching {}: {}", name, e),
+            }
+        });
+    }
+}
+
+fn watch_on_battery(conn: &zbus::blocking::Connection, events: &Events) -> zbus::Result<()> {
+    let upower = UPowerProxyBlocking::new(conn)?;
+    // Yields the current value first, then every change.
+    for change in upower.receive_on_battery_changed() {
+        if events
+            .send_blocking(PolicyEvent::OnBattery(change.get()?))
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn watch_power_profile(conn: &zbus::blocking::Connection, events: &Events) -> zbus::Result<()> {
+    let profiles = PowerProfilesProxyBlocking::new(conn)?;
+    for change in profiles.receive_active_profile_changed() {
+        let power_saver = change.get()? == POWER_SAVER_PROFILE;
+        if events
+            .send_blocking(PolicyEvent::PowerSaver(power_saver))
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPOWER_PATH: &str = "/org/freedesktop/UPower";
+    const PROFILES_PATH: &str = "/net/hadess/PowerProfiles";
+
+    struct MockUPower {
+        on_battery: bool,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.UPower")]
+    impl MockUPower {
+        #[zbus(property)]
+        fn on_battery(&self) -> bool {
+            self.on_battery
+        }
+    }
+
+    struct MockPowerProfiles {
+        active_profile: String,
+    }
+
+    #[zbus::interface(name = "net.hadess.PowerProfiles")]
+    impl MockPowerProfiles {
+        #[zbus(property)]
+        fn active_profile(&self) -> String {
+            self.active_profile.clone()
+        }
+    }
+
+    /// The mock services on a peer-to-peer connection standing in for the
+    /// system bus, and the watchers' side.
+    fn mock_bus() -> (zbus::blocking::Connection, zbus::blocking::Connection) {
+        crate::test_support::mock_bus(|builder| {
+            builder
+                .serve_at(UPOWER_PATH, MockUPower { on_battery: false })?
+                .serve_at(
+                    PROFILES_PATH,
+                    MockPowerProfiles {
+                        active_profile: "balanced".into(),
+                    },
+                )
+        })
+    }
+
+    #[test]
+    fn test_watchers_follow_mock_services() {
+        let (service, client) = mock_bus();
+        let (events, received) = async_channel::unbounded();
+
+        let conn = client.clone();
+        let tx = events.clone();
+        std::thread::spawn(move || watch_on_battery(&conn, &tx));
+        assert_eq!(received.recv_blocking(), Ok(PolicyEvent::OnBattery(false)));
+
+        let upower = service
+            .object_server()
+            .interface::<_, MockUPower>(UPOWER_PATH)
+            .unwrap();
+        upower.get_mut().on_battery = true;
+        zbus::block_on(upower.get().on_battery_changed(upower.signal_emitter())).unwrap();
+        assert_eq!(received.recv_blocking(), Ok(PolicyEvent::OnBattery(true)));
+
+        std::thread::spawn(move || watch_power_profile(&client, &events));
+        assert_eq!(received.recv_blocking(), Ok(PolicyEvent::PowerSaver(false)));
+
+        let profiles = service
+            .object_server()
+            .interface::<_, MockPowerProfiles>(PROFILES_PATH)
+            .unwrap();
+        profiles.get_mut().active_profile = POWER_SAVER_PROFILE.into();
+        zbus::block_on(
+            profiles
+                .get()
+                .active_profile_changed(profiles.signal_emitter()),
+        )
+        .unwrap();
+        assert_eq!(received.recv_blocking(), Ok(PolicyEvent::PowerSaver(true)));
+    }
+}
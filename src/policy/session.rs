@@ -0,0 +1,133 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Session lock and idle tracking: logind's `LockedHint`/`IdleHint` on the
+//! system bus, and the screensaver `ActiveChanged` signal on the session
+//! bus (freedesktop and GNOME names). Each source blocks on its own thread;
+//! a missing service just ends that thread.
+
+use tracing::{debug, info};
+
+use super::{Events, PolicyEvent};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1/session/auto"
+)]
+trait Login1Session {
+    #[zbus(property)]
+    fn locked_hint(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.ScreenSaver",
+    default_service = "org.freedesktop.ScreenSaver",
+    default_path = "/org/freedesktop/ScreenSaver"
+)]
+trait ScreenSaver {
+    fn get_active(&self) -> zbus::Result<bool>;
+
+    #[zbus(signal)]
+    fn active_changed(&self, active: bool) -> zbus::Result<()>;
+}
+
+pub(super) fn spawn(events: Events) {
+    let watchers: [(&str, fn(&Events) -> zbus::Result<()>); 4] = [
+        ("login1 LockedHint", watch_locked_hint),
+        ("login1 IdleHint", watch_idle_hint),
+        ("org.freedesktop.ScreenSaver", |events| {
+            watch_screensaver(
+                events,
+                "org.freedesktop.ScreenSaver",
+                "/org/freedesktop/ScreenSaver",
+            )
+        }),
+        ("org.gnome.ScreenSaver", |events| {
+            watch_screensaver(events, "org.gnome.ScreenSaver", "/org/gnome/ScreenSaver")
+        }),
+    ];
+    for (name, watch) in watchers {
+        let events = events.clone();
+        std::thread::spawn(move || match watch(&events) {
+            Ok(()) => debug!("{} watcher finished", name),
+            // Expected on systems without the service (no logind, no
+            // screensaver, or GNOME's name only).
+            Err(e) => info!("Not watching {}: {}", name, e),
+        });
+    }
+}
+
+fn watch_locked_hint(events: &Events) -> zbus::Result<()> {
+    let conn = zbus::blocking::Connection::system()?;
+    let session = Login1SessionProxyBlocking::new(&conn)?;
+    // Yields the current value first, then every change.
+    for change in session.receive_locked_hint_changed() {
+        if events
+            .send_blocking(PolicyEvent::Locked(change.get()?))
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn watch_idle_hint(events: &Events) -> zbus::Result<()> {
+    let conn = zbus::blocking::Connection::system()?;
+    let session = Login1SessionProxyBlocking::new(&conn)?;
+    for change in session.receive_idle_hint_changed() {
+        if events
+            .send_blocking(PolicyEvent::Idle(change.get()?))
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn watch_screensaver(events: &Events, service: &'static str, path: &str) -> zbus::Result<()> {
+    let conn = zbus::blocking::Connection::session()?;
+    let screensaver = ScreenSaverProxyBlocking::builder(&conn)
+        .destination(service)?
+        .path(path)?
+        .interface(service)?
+        .build()?;
+    // Subscribe before reading the state, so no change falls in between.
+    let changes = screensaver.receive_active_changed()?;
+    let active = screensaver.get_active()?;
+    if events
+        .send_blocking(PolicyEvent::ScreenSaver(service, active))
+        .is_err()
+    {
+        return Ok(());
+    }
+    for signal in changes {
+        let active = *signal.args()?.active();
+        if events
+            .send_blocking(PolicyEvent::ScreenSaver(service, active))
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
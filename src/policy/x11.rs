@@ -0,0 +1,175 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! X11 fullscreen tracking: a thread with its own connection follows the
+//! EWMH root properties and every client's `_NET_WM_STATE`, and reports
+//! where visible fullscreen windows are.
+
+use tracing::{debug, warn};
+use x11rb::{
+    connection::Connection,
+    errors::ReplyError,
+    protocol::{
+        xproto::{AtomEnum, ChangeWindowAttributesAux, ConnectionExt, EventMask, Window},
+        Event,
+    },
+    rust_connection::RustConnection,
+};
+
+use super::{Area, PolicyEvent};
+
+struct Atoms {
+    active_window: u32,
+    client_list: u32,
+    wm_state: u32,
+    fullscreen: u32,
+    hidden: u32,
+}
+
+impl Atoms {
+    fn intern(conn: &RustConnection) -> anyhow::Result<Self> {
+        let atom = |name: &[u8]| -> anyhow::Result<u32> {
+            Ok(conn.intern_atom(false, name)?.reply()?.atom)
+        };
+        Ok(Self {
+            active_window: atom(b"_NET_ACTIVE_WINDOW")?,
+            client_list: atom(b"_NET_CLIENT_LIST_STACKING")?,
+            wm_state: atom(b"_NET_WM_STATE")?,
+            fullscreen: atom(b"_NET_WM_STATE_FULLSCREEN")?,
+            hidden: atom(b"_NET_WM_STATE_HIDDEN")?,
+        })
+    }
+}
+
+pub(super) fn spawn(events: async_channel::Sender<PolicyEvent>) {
+    std::thread::spawn(move || {
+        if let Err(e) = run(&events) {
+            warn!("X11 fullscreen tracking stopped: {:#}", e);
+        }
+    });
+}
+
+fn run(events: &async_channel::Sender<PolicyEvent>) -> anyhow::Result<()> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen_num].root;
+    let atoms = Atoms::intern(&conn)?;
+    conn.change_window_attributes(
+        root,
+        &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+    )?;
+
+    let mut last = None;
+    loop {
+        let areas = fullscreen_areas(&conn, root, &atoms)?;
+        if last.as_ref() != Some(&areas) {
+            debug!("Fullscreen windows: {:?}", areas);
+            if events
+                .send_blocking(PolicyEvent::Fullscreen(areas.clone()))
+                .is_err()
+            {
+                // The policy is gone.
+                return Ok(());
+            }
+            last = Some(areas);
+        }
+
+        wait_for_change(&conn, &atoms)?;
+    }
+}
+
+/// Block until something that can move a fullscreen window happens, then
+/// drain the burst so one rescan covers it.
+fn wait_for_change(conn: &RustConnection, atoms: &Atoms) -> anyhow::Result<()> {
+    let is_relevant = |event: Event| match event {
+        Event::PropertyNotify(e) => {
+            e.atom == atoms.active_window || e.atom == atoms.client_list || e.atom == atoms.wm_state
+        }
+        Event::ConfigureNotify(_) | Event::UnmapNotify(_) | Event::MapNotify(_) => true,
+        _ => false,
+    };
+    loop {
+        let mut relevant = is_relevant(conn.wait_for_event()?);
+        while let Some(event) = conn.poll_for_event()? {
+            relevant |= is_relevant(event);
+        }
+        if relevant {
+            return Ok(());
+        }
+    }
+}
+
+/// Root-relative rectangles of mapped, non-minimized fullscreen clients.
+/// Also subscribes to each client's property and structure changes, so a
+/// window going fullscreen or moving wakes the loop.
+fn fullscreen_areas(
+    conn: &RustConnection,
+    root: Window,
+    atoms: &Atoms,
+) -> anyhow::Result<Vec<Area>> {
+    let clients = conn
+        .get_property(
+            false,
+            root,
+            atoms.client_list,
+            AtomEnum::WINDOW,
+            0,
+            u32::MAX,
+        )?
+        .reply()?;
+    let mut areas = Vec::new();
+    for window in clients.value32().into_iter().flatten() {
+        // Clients vanish at any time; a failed request just skips one.
+        conn.change_window_attributes(
+            window,
+            &ChangeWindowAttributesAux::new()
+                .event_mask(EventMask::PROPERTY_CHANGE | EventMask::STRUCTURE_NOTIFY),
+        )?
+        .ignore_error();
+        match client_area(conn, root, window, atoms) {
+            Ok(Some(area)) => areas.push(area),
+            Ok(None) | Err(ReplyError::X11Error(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(areas)
+}
+
+fn client_area(
+    conn: &RustConnection,
+    root: Window,
+    window: Window,
+    atoms: &Atoms,
+) -> Result<Option<Area>, ReplyError> {
+    let state = conn
+        .get_property(false, window, atoms.wm_state, AtomEnum::ATOM, 0, 64)?
+        .reply()?;
+    let Some(state) = state.value32() else {
+        return Ok(None);
+    };
+    let state: Vec<u32> = state.collect();
+    if !state.contains(&atoms.fullscreen) || state.contains(&atoms.hidden) {
+        return Ok(None);
+    }
+    let geometry = conn.get_geometry(window)?.reply()?;
+    let origin = conn.translate_coordinates(window, root, 0, 0)?.reply()?;
+    Ok(Some(Area {
+        x: origin.dst_x.into(),
+        y: origin.dst_y.into(),
+        width: geometry.width.into(),
+        height: geometry.height.into(),
+    }))
+}
@@ -15,26 +15,41 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-#[cfg(any(feature = "mpv", feature = "wpe"))]
+#[cfg(any(feature = "mpv", feature = "wpe", feature = "shader"))]
 mod gl_loader;
 mod gstgtk4;
+mod image;
 #[cfg(feature = "mpv")]
 mod mpv;
+mod playlist;
 #[cfg(feature = "wpe")]
 mod scene;
+#[cfg(feature = "shader")]
+mod shader;
 mod web;
 
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
 use enum_dispatch::enum_dispatch;
-use gtk::{gdk, prelude::*, Widget};
+use glib::subclass::Signal;
+use gtk::{gdk, gio, glib, prelude::*, Widget};
 
-use crate::model::{VideoRenderer, WallpaperSource, WallpaperType};
+use crate::directory::DirectoryScan;
+use crate::model::{
+    ContentFit, RenderOverrides, VideoRenderer, WallpaperSource, WallpaperType, WpeProperty,
+};
 use crate::wpe::{WpePackage, WpeType};
 
 pub use gstgtk4::GstGtk4Widget;
+pub use image::ImageWidget;
 #[cfg(feature = "mpv")]
 pub use mpv::MpvWidget;
+pub use playlist::PlaylistWidget;
 #[cfg(feature = "wpe")]
 pub use scene::SceneWidget;
+#[cfg(feature = "shader")]
+pub use shader::ShaderWidget;
 pub use web::WebWidget;
 
 /// Wrap `paintable` in an expanding Picture (optionally graphics-offloaded)
@@ -73,6 +88,81 @@ fn mirror_by_snapshot(
     picture_box(&paintable, enable_graphics_offload, content_fit).0
 }
 
+/// Signal every renderer widget declares, carrying a
+/// ([`RendererErrorKind`], message) pair.
+const ERROR_SIGNAL: &str = "renderer-error";
+
+/// What kind of failure a renderer reports. The kebab-case string form
+/// (strum) is what the D-Bus `RendererError` signal carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::Enum, strum_macros::Display)]
+#[enum_type(name = "HotaruRendererErrorKind")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RendererErrorKind {
+    /// The wallpaper could not be loaded or played: a missing file, an
+    /// unsupported format, a page that failed to load.
+    Load,
+    /// A Wallpaper Engine package could not be resolved or is invalid.
+    Package,
+    /// The wallpaper needs something this build lacks (a cargo feature).
+    Unsupported,
+    /// The rendering backend failed: no GL context, libmpv or the scene
+    /// engine library failing to initialize.
+    Backend,
+}
+
+/// A failure reported by a renderer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RendererError {
+    pub kind: RendererErrorKind,
+    pub message: String,
+}
+
+impl RendererError {
+    fn emit(&self, widget: &impl IsA<Widget>) {
+        widget.emit_by_name::<()>(ERROR_SIGNAL, &[&self.kind, &self.message]);
+    }
+}
+
+/// The `renderer-error` signal, for a renderer widget class's `signals()`.
+fn error_signal() -> Signal {
+    Signal::builder(ERROR_SIGNAL)
+        .param_types([RendererErrorKind::static_type(), String::static_type()])
+        .build()
+}
+
+/// Log a renderer failure and emit it as `renderer-error` on `widget`.
+/// Emission waits for an idle callback, so failures raised while the
+/// renderer is still being built reach handlers connected right after.
+fn report_error(widget: &impl IsA<Widget>, kind: RendererErrorKind, message: impl Into<String>) {
+    let error = RendererError {
+        kind,
+        message: message.into(),
+    };
+    tracing::error!("{}", error.message);
+    let widget = widget.upcast_ref::<Widget>().downgrade();
+    glib::idle_add_local_once(move || {
+        if let Some(widget) = widget.upgrade() {
+            error.emit(&widget);
+        }
+    });
+}
+
+/// Signal every renderer widget declares, emitted once when it first has
+/// something to show: a decoded video frame, a drawn scene, a loaded page.
+const FIRST_FRAME_SIGNAL: &str = "first-frame";
+
+/// The `first-frame` signal, for a renderer widget class's `signals()`.
+fn first_frame_signal() -> Signal {
+    Signal::builder(FIRST_FRAME_SIGNAL).build()
+}
+
+/// Emit `first-frame` on `widget` unless `shown` says it already was.
+fn report_first_frame(widget: &impl IsA<Widget>, shown: &Cell<bool>) {
+    if !shown.replace(true) {
+        widget.emit_by_name::<()>(FIRST_FRAME_SIGNAL, &[]);
+    }
+}
+
 #[enum_dispatch]
 pub trait RendererWidget: AsRef<Widget> {
     fn mirror(&self, enable_graphics_offload: bool, content_fit: gtk::ContentFit) -> gtk::Box;
@@ -83,6 +173,9 @@ pub trait RendererWidget: AsRef<Widget> {
     fn set_volume(&self, volume: i32);
     fn set_mute(&self, mute: bool);
     fn set_content_fit(&self, fit: gtk::ContentFit);
+    /// Cap the frame rate (power policy). `Some(0)` holds the current
+    /// frame without counting as a pause; `None` lifts the cap.
+    fn set_fps_limit(&self, limit: Option<u32>);
     fn widget(&self) -> &Widget {
         self.as_ref()
     }
@@ -96,11 +189,256 @@ pub enum Renderer {
     GstGtk4(GstGtk4Widget),
     #[cfg(feature = "mpv")]
     Mpv(MpvWidget),
+    Image(ImageWidget),
     #[cfg(feature = "wpe")]
     Scene(SceneWidget),
+    #[cfg(feature = "shader")]
+    Shader(ShaderWidget),
+    Playlist(PlaylistWidget),
+}
+
+/// A primary renderer on screen, with every monitor showing it: its own
+/// window's plus those of any clones mirroring it.
+#[derive(Debug)]
+pub struct ActiveRenderer {
+    pub renderer: Renderer,
+    /// What the config asked it to show.
+    pub source: WallpaperSource,
+    pub outputs: Vec<String>,
+    /// Whether `play()` (rather than `pause()`) was called last, so
+    /// playback changes only reach the renderer on a transition.
+    pub playing: Cell<bool>,
+    /// The power policy's frame-rate cap, as last applied.
+    pub fps_limit: Cell<Option<u32>>,
+    /// Per-monitor volume (`SetMonitorVolume`) that replaces the `volume`
+    /// setting for this renderer.
+    pub volume_override: Cell<Option<i32>>,
+    /// The primary monitor entry's render overrides.
+    pub overrides: RenderOverrides,
+    /// The clone windows showing this renderer.
+    pub mirrors: Vec<Mirror>,
+}
+
+impl ActiveRenderer {
+    /// The volume this renderer plays at, given the `volume` setting.
+    pub fn volume(&self, setting: i32) -> i32 {
+        self.volume_override
+            .get()
+            .or(self.overrides.volume)
+            .unwrap_or(setting)
+    }
+
+    /// Whether this renderer is muted, given the `mute` setting.
+    pub fn mute(&self, setting: bool) -> bool {
+        self.overrides.mute.unwrap_or(setting)
+    }
+
+    /// Apply the `content-fit` setting to the renderer and its mirrors,
+    /// except where their monitor entries override it.
+    pub fn set_content_fit(&self, setting: gtk::ContentFit) {
+        self.renderer
+            .set_content_fit(self.overrides.content_fit.map_or(setting, Into::into));
+        for mirror in &self.mirrors {
+            mirror.set_content_fit(setting);
+        }
+    }
+}
+
+/// A clone window's copy of a renderer's output, as `mirror()` built it.
+#[derive(Debug)]
+pub struct Mirror {
+    /// Connector of the clone window showing it.
+    pub monitor: String,
+    pub widget: gtk::Box,
+    /// The clone entry's `content_fit` override.
+    pub content_fit: Option<ContentFit>,
+}
+
+impl Mirror {
+    /// Apply the `content-fit` setting unless the clone entry overrides it.
+    pub fn set_content_fit(&self, setting: gtk::ContentFit) {
+        let fit = self.content_fit.map_or(setting, Into::into);
+        // `picture_box` puts the picture first, possibly inside a
+        // GraphicsOffload.
+        let picture = self.widget.first_child().and_then(|child| {
+            match child.downcast::<gtk::GraphicsOffload>() {
+                Ok(offload) => offload.child()?.downcast().ok(),
+                Err(child) => child.downcast::<gtk::Picture>().ok(),
+            }
+        });
+        if let Some(picture) = picture {
+            picture.set_content_fit(fit);
+        }
+    }
+}
+
+/// The active renderers, keyed by their primary window's connector.
+pub type RendererMap = BTreeMap<String, ActiveRenderer>;
+
+/// Optional renderer support: compiled in (cargo features `mpv`, `wpe`)
+/// and, for scenes, whether the engine library loads. Keyed as the D-Bus
+/// `Capabilities` property reports it.
+pub fn capabilities() -> BTreeMap<String, bool> {
+    #[cfg(feature = "wpe")]
+    let wpe_library = SceneWidget::engine_available();
+    #[cfg(not(feature = "wpe"))]
+    let wpe_library = false;
+    BTreeMap::from([
+        ("mpv".to_string(), cfg!(feature = "mpv")),
+        ("wpe".to_string(), cfg!(feature = "wpe")),
+        ("wpe-library".to_string(), wpe_library),
+        ("shader".to_string(), cfg!(feature = "shader")),
+    ])
 }
 
 impl Renderer {
+    /// Call `f` with every failure this renderer reports, including ones
+    /// raised while it was being built.
+    pub fn connect_error<F: Fn(RendererError) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.widget().connect_closure(
+            ERROR_SIGNAL,
+            false,
+            glib::closure_local!(move |_widget: Widget,
+                                       kind: RendererErrorKind,
+                                       message: String| {
+                f(RendererError { kind, message })
+            }),
+        )
+    }
+
+    /// Call `f` when this renderer first has something on screen.
+    pub fn connect_first_frame<F: Fn() + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.widget().connect_closure(
+            FIRST_FRAME_SIGNAL,
+            false,
+            glib::closure_local!(move |_widget: Widget| f()),
+        )
+    }
+
+    /// Short name of the backend, as D-Bus `ActiveRenderers` reports it.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Web(_) => "web",
+            Self::GstGtk4(_) => "gst-gtk4",
+            #[cfg(feature = "mpv")]
+            Self::Mpv(_) => "mpv",
+            Self::Image(_) => "image",
+            #[cfg(feature = "wpe")]
+            Self::Scene(_) => "scene",
+            #[cfg(feature = "shader")]
+            Self::Shader(_) => "shader",
+            Self::Playlist(_) => "playlist",
+        }
+    }
+
+    /// The user properties of the Wallpaper Engine package on screen (a
+    /// playlist's current entry), valued as the wallpaper last got them.
+    pub fn wpe_properties(&self) -> Result<Vec<WpeProperty>, String> {
+        match self {
+            Self::Web(web) => web.wpe_user_properties(),
+            #[cfg(feature = "wpe")]
+            Self::Scene(scene) => WpePackage::from_dir(scene.filepath().into())
+                .map(|package| package.user_properties(&scene.wpe_overrides()))
+                .map_err(|e| format!("{:#}", e)),
+            Self::Playlist(playlist) => playlist.wpe_properties(),
+            _ => Err(format!(
+                "the {} renderer shows no Wallpaper Engine web or scene package",
+                self.kind()
+            )),
+        }
+    }
+
+    /// Set a user property of the Wallpaper Engine web package on screen
+    /// and hand it to the running wallpaper. Scenes take their properties
+    /// at load only.
+    pub fn set_wpe_property(&self, name: &str, value: serde_json::Value) -> Result<(), String> {
+        match self {
+            Self::Web(web) => web.set_wpe_property(name, value),
+            Self::Playlist(playlist) => playlist.set_wpe_property(name, value),
+            _ => Err(format!(
+                "the {} renderer cannot change Wallpaper Engine properties at runtime",
+                self.kind()
+            )),
+        }
+    }
+
+    /// Construct the renderer for a primary window's wallpaper source.
+    /// `wpe_properties` replaces a Wallpaper Engine package's property
+    /// defaults.
+    pub fn with_source(
+        wallpaper_type: &WallpaperType,
+        wallpaper_source: &WallpaperSource,
+        wpe_properties: &BTreeMap<String, serde_json::Value>,
+        video_renderer: VideoRenderer,
+        enable_graphics_offload: bool,
+    ) -> Self {
+        // Playlists wrap renderers of their own, so they come before the
+        // WPE dispatch: a wpe playlist is a playlist of wpe packages.
+        match wallpaper_source {
+            WallpaperSource::Playlist { playlist, order } => {
+                return Self::Playlist(PlaylistWidget::new(
+                    playlist,
+                    *order,
+                    *wallpaper_type,
+                    video_renderer,
+                    enable_graphics_offload,
+                ));
+            }
+            WallpaperSource::Directory {
+                directory,
+                recursive,
+                extensions,
+                duration,
+                order,
+            } => {
+                let scan = DirectoryScan {
+                    directory: directory.into(),
+                    recursive: *recursive,
+                    extensions: extensions.clone(),
+                    duration: *duration,
+                };
+                return Self::Playlist(PlaylistWidget::with_directory(
+                    scan,
+                    *order,
+                    video_renderer,
+                    enable_graphics_offload,
+                ));
+            }
+            _ => {}
+        }
+        if *wallpaper_type == WallpaperType::Wpe {
+            // WPE packages resolve their real renderer from project.json,
+            // so they take the whole source (filepath or workshop_id).
+            return Self::with_wpe(
+                wallpaper_source,
+                wpe_properties,
+                video_renderer,
+                enable_graphics_offload,
+            );
+        }
+        match wallpaper_source {
+            WallpaperSource::Filepath { filepath } => Self::with_filepath(
+                filepath,
+                wallpaper_type,
+                video_renderer,
+                enable_graphics_offload,
+            ),
+            WallpaperSource::Uri { uri } => {
+                Self::with_uri(uri, wallpaper_type, video_renderer, enable_graphics_offload)
+            }
+            WallpaperSource::WorkshopId { workshop_id } => failed(
+                RendererErrorKind::Package,
+                format!(
+                    "workshop_id ({}) requires wallpaper_type: wpe; showing blank",
+                    workshop_id
+                ),
+            ),
+            WallpaperSource::Playlist { .. } | WallpaperSource::Directory { .. } => {
+                unreachable!("handled above")
+            }
+        }
+    }
+
     pub fn with_filepath(
         filepath: &str,
         wallpaper_type: &WallpaperType,
@@ -118,11 +456,17 @@ impl Renderer {
                 #[cfg(not(feature = "mpv"))]
                 VideoRenderer::Mpv => unreachable!(),
             },
+            WallpaperType::Image => Self::Image(ImageWidget::with_filepath(
+                filepath,
+                enable_graphics_offload,
+            )),
+            WallpaperType::Shader => with_shader(filepath),
             WallpaperType::Web => Self::Web(WebWidget::with_filepath(filepath)),
             WallpaperType::Wpe => Self::with_wpe(
                 &WallpaperSource::Filepath {
                     filepath: filepath.to_string(),
                 },
+                &BTreeMap::new(),
                 video_renderer,
                 enable_graphics_offload,
             ),
@@ -135,14 +479,17 @@ impl Renderer {
     /// video/web packages to hotaru's own video/web renderers.
     pub fn with_wpe(
         source: &WallpaperSource,
+        wpe_properties: &BTreeMap<String, serde_json::Value>,
         video_renderer: VideoRenderer,
         enable_graphics_offload: bool,
     ) -> Self {
         let package = match WpePackage::resolve(source) {
             Ok(package) => package,
             Err(e) => {
-                tracing::error!("Failed to load Wallpaper Engine package: {:#}", e);
-                return blank();
+                return failed(
+                    RendererErrorKind::Package,
+                    format!("Failed to load Wallpaper Engine package: {:#}", e),
+                );
             }
         };
 
@@ -150,7 +497,10 @@ impl Renderer {
             WpeType::Scene => {
                 #[cfg(feature = "wpe")]
                 {
-                    Self::Scene(SceneWidget::with_filepath(&package.dir.to_string_lossy()))
+                    Self::Scene(SceneWidget::with_filepath(
+                        &package.dir.to_string_lossy(),
+                        &WpeProperty::changed(&package.user_properties(wpe_properties)),
+                    ))
                 }
                 #[cfg(not(feature = "wpe"))]
                 {
@@ -164,23 +514,23 @@ impl Renderer {
                     video_renderer,
                     enable_graphics_offload,
                 ),
-                Err(e) => {
-                    tracing::error!("Invalid Wallpaper Engine package: {:#}", e);
-                    blank()
-                }
+                Err(e) => failed(
+                    RendererErrorKind::Package,
+                    format!("Invalid Wallpaper Engine package: {:#}", e),
+                ),
             },
             WpeType::Web => match package.entry() {
                 // Web packages get the Wallpaper Engine JS API and their
-                // default properties injected (see web.rs).
+                // properties injected (see web.rs).
                 Ok(entry) => Self::Web(WebWidget::with_wpe(
                     &entry.to_string_lossy(),
-                    &package.user_properties_json(),
+                    &package.user_properties_json(wpe_properties),
                     &package.dir.to_string_lossy(),
                 )),
-                Err(e) => {
-                    tracing::error!("Invalid Wallpaper Engine package: {:#}", e);
-                    blank()
-                }
+                Err(e) => failed(
+                    RendererErrorKind::Package,
+                    format!("Invalid Wallpaper Engine package: {:#}", e),
+                ),
             },
         }
     }
@@ -201,32 +551,67 @@ impl Renderer {
                 #[cfg(not(feature = "mpv"))]
                 VideoRenderer::Mpv => unreachable!(),
             },
+            WallpaperType::Image => {
+                Self::Image(ImageWidget::with_uri(uri, enable_graphics_offload))
+            }
+            // Shaders are compiled from a file read on realize.
+            WallpaperType::Shader => match gio::File::for_uri(uri).path() {
+                Some(path) => with_shader(&path.to_string_lossy()),
+                None => failed(
+                    RendererErrorKind::Load,
+                    format!("shader wallpaper must be a local file, not {}", uri),
+                ),
+            },
             WallpaperType::Web => Self::Web(WebWidget::with_uri(uri)),
-            WallpaperType::Wpe => {
-                tracing::error!(
+            WallpaperType::Wpe => failed(
+                RendererErrorKind::Package,
+                format!(
                     "wpe wallpaper cannot be a URI ({}); use filepath or workshop_id",
                     uri
-                );
-                blank()
-            }
+                ),
+            ),
         }
     }
 }
 
 /// A blank fallback renderer, used when a wallpaper cannot be constructed.
-fn blank() -> Renderer {
-    Renderer::Web(WebWidget::with_uri("about:blank"))
+/// It reports the failure as its own.
+fn failed(kind: RendererErrorKind, message: String) -> Renderer {
+    let widget = WebWidget::with_uri("about:blank");
+    report_error(&widget, kind, message);
+    Renderer::Web(widget)
+}
+
+/// A shader renderer, or a placeholder in builds without the 'shader'
+/// feature.
+fn with_shader(filepath: &str) -> Renderer {
+    #[cfg(feature = "shader")]
+    {
+        Renderer::Shader(ShaderWidget::with_filepath(filepath))
+    }
+    #[cfg(not(feature = "shader"))]
+    {
+        failed(
+            RendererErrorKind::Unsupported,
+            format!(
+                "shader wallpaper {} requested but this build lacks the 'shader' feature, \
+                 showing a blank wallpaper",
+                filepath
+            ),
+        )
+    }
 }
 
 /// Placeholder for scene packages in builds without the 'wpe' feature.
 /// (WPE video/web packages still render — only the scene backend is gated.)
 #[cfg(not(feature = "wpe"))]
 fn scene_unsupported() -> Renderer {
-    tracing::error!(
+    failed(
+        RendererErrorKind::Unsupported,
         "scene wallpaper requested but this build lacks the 'wpe' feature, \
          showing a blank wallpaper"
-    );
-    blank()
+            .to_string(),
+    )
 }
 
 /// Downgrade renderer choices this build cannot honor.
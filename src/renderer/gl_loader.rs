@@ -16,10 +16,11 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Process-wide OpenGL symbol resolver shared by the GL-based renderers
-//! (libmpv, wallpaper-engine scenes). Both libraries resolve every GL
-//! function through a caller-provided loader; GTK exposes no public one, so
-//! resolve via eglGetProcAddress or glXGetProcAddressARB depending on which
-//! platform GDK actually realized its GL context on.
+//! (libmpv, wallpaper-engine scenes, shaders). Both libraries resolve every
+//! GL function through a caller-provided loader, and the shader renderer
+//! calls GL directly; GTK exposes no public loader, so resolve via
+//! eglGetProcAddress or glXGetProcAddressARB depending on which platform
+//! GDK actually realized its GL context on.
 
 use std::ffi::{c_char, c_void, CString};
 use std::ptr;
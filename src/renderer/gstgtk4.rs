@@ -16,10 +16,13 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use glib::Object;
-use gtk::{gio, glib, prelude::*};
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 use tracing::info;
 
-use super::{picture_box, RendererWidget};
+use super::{
+    error_signal, first_frame_signal, picture_box, report_error, report_first_frame,
+    RendererErrorKind, RendererWidget,
+};
 
 glib::wrapper! {
     pub struct GstGtk4Widget(ObjectSubclass<imp::GstGtk4Widget>)
@@ -44,24 +47,22 @@ impl GstGtk4Widget {
 impl RendererWidget for GstGtk4Widget {
     fn mirror(&self, enable_graphics_offload: bool, content_fit: gtk::ContentFit) -> gtk::Box {
         // The sink exposes a real gdk::Paintable, so clones share the video
-        // texture directly instead of snapshotting the widget.
+        // texture directly instead of snapshotting the widget. Each clone
+        // keeps its own content fit (see `Mirror`).
         let paintable = self.paintable().unwrap();
-        let (widget, picture) = picture_box(&paintable, enable_graphics_offload, content_fit);
-        self.picture()
-            .bind_property("content-fit", &picture, "content-fit")
-            .build();
-        widget
+        picture_box(&paintable, enable_graphics_offload, content_fit).0
     }
 
     fn play(&self) {
-        self.player().play();
+        self.imp().set_playing(true);
     }
 
     fn pause(&self) {
-        self.player().pause();
+        self.imp().set_playing(false);
     }
 
     fn stop(&self) {
+        self.imp().playing.set(false);
         self.player().stop();
     }
 
@@ -77,16 +78,24 @@ impl RendererWidget for GstGtk4Widget {
     fn set_content_fit(&self, fit: gtk::ContentFit) {
         self.picture().set_content_fit(fit);
     }
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        self.imp().set_fps_limit(limit);
+    }
 }
 
 mod imp {
     use super::*;
 
     use std::cell::{Cell, RefCell};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, OnceLock};
 
+    use glib::subclass::Signal;
     use glib::Properties;
-    use gtk::{gdk, subclass::prelude::*};
-    use tracing::{debug, error, warn};
+    use gst::prelude::*;
+    use gtk::gdk;
+    use tracing::{debug, warn};
 
     #[derive(Properties, Default)]
     #[properties(wrapper_type = super::GstGtk4Widget)]
@@ -108,6 +117,69 @@ mod imp {
         paintable: RefCell<Option<gdk::Paintable>>,
         #[property(get)]
         picture: RefCell<gtk::Picture>,
+        /// Whether `play()` (rather than `pause()`/`stop()`) was called last.
+        pub(super) playing: Cell<bool>,
+        /// Runtime cap from the power policy; `Some(0)` holds the frame.
+        fps_limit: Cell<Option<u32>>,
+        /// The cap as a minimum buffer spacing in ns (0: uncapped), read by
+        /// the sink pad probe on the streaming thread.
+        min_frame_interval_ns: Arc<AtomicU64>,
+        /// Whether `first-frame` was emitted.
+        first_frame: Cell<bool>,
+    }
+
+    impl GstGtk4Widget {
+        pub(super) fn set_playing(&self, playing: bool) {
+            self.playing.set(playing);
+            self.sync_state();
+        }
+
+        pub(super) fn set_fps_limit(&self, limit: Option<u32>) {
+            let was_held = self.fps_limit.replace(limit) == Some(0);
+            let interval = limit
+                .filter(|&limit| limit > 0)
+                .map_or(0, |limit| 1_000_000_000 / u64::from(limit));
+            self.min_frame_interval_ns
+                .store(interval, Ordering::Relaxed);
+            if was_held != (limit == Some(0)) && self.playing.get() {
+                self.sync_state();
+            }
+        }
+
+        /// A held frame is a pipeline pause, so decoding and audio stop
+        /// too; the renderer still counts as playing.
+        fn sync_state(&self) {
+            let player = self.player.borrow();
+            if self.playing.get() && self.fps_limit.get() != Some(0) {
+                player.play();
+            } else {
+                player.pause();
+            }
+        }
+
+        /// Drop buffers that reach the sink sooner than the FPS cap allows.
+        /// Timestamps going backwards (a loop or seek) restart the count.
+        fn install_frame_limiter(&self, sink: &gst::Element) {
+            let Some(pad) = sink.static_pad("sink") else {
+                warn!("Video sink has no sink pad; FPS cap disabled");
+                return;
+            };
+            let interval = self.min_frame_interval_ns.clone();
+            let last_pts = AtomicU64::new(u64::MAX);
+            pad.add_probe(gst::PadProbeType::BUFFER, move |_pad, info| {
+                let Some(pts) = info.buffer().and_then(|buffer| buffer.pts()) else {
+                    return gst::PadProbeReturn::Ok;
+                };
+                let pts = pts.nseconds();
+                let last = last_pts.load(Ordering::Relaxed);
+                let interval = interval.load(Ordering::Relaxed);
+                if interval > 0 && pts >= last && pts - last < interval {
+                    return gst::PadProbeReturn::Drop;
+                }
+                last_pts.store(pts, Ordering::Relaxed);
+                gst::PadProbeReturn::Ok
+            });
+        }
     }
 
     #[glib::object_subclass]
@@ -149,6 +221,16 @@ mod imp {
                 .bidirectional()
                 .build();
 
+            self.install_frame_limiter(&sink);
+
+            // The sink invalidates the paintable's contents for every new
+            // frame.
+            paintable.connect_invalidate_contents(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_paintable| report_first_frame(&*imp.obj(), &imp.first_frame)
+            ));
+
             adapter.connect_end_of_stream(move |adapter| {
                 adapter.play().seek(gst::ClockTime::from_seconds(0));
             });
@@ -161,9 +243,17 @@ mod imp {
                 warn!("{}", error);
             });
 
-            adapter.connect_error(move |_adapter, error, _structure| {
-                error!("{}", error);
-            });
+            adapter.connect_error(glib::clone!(
+                #[weak]
+                obj,
+                move |_adapter, error, _structure| {
+                    report_error(
+                        &obj,
+                        RendererErrorKind::Load,
+                        format!("Failed to play {}: {}", obj.uri(), error),
+                    );
+                }
+            ));
 
             self.sink.replace(Some(sink));
             self.renderer.replace(Some(renderer));
@@ -172,6 +262,11 @@ mod imp {
             self.paintable.replace(Some(paintable));
             self.picture.replace(picture);
         }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
     }
 
     impl WidgetImpl for GstGtk4Widget {}
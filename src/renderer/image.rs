@@ -0,0 +1,306 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::{Duration, SystemTime};
+
+use glib::Object;
+use gtk::{gdk, gdk_pixbuf, gio, glib, prelude::*, subclass::prelude::*};
+use tracing::info;
+
+use super::{
+    error_signal, first_frame_signal, picture_box, report_error, report_first_frame,
+    RendererErrorKind, RendererWidget,
+};
+
+glib::wrapper! {
+    pub struct ImageWidget(ObjectSubclass<imp::ImageWidget>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ImageWidget {
+    pub fn with_filepath(filepath: &str, enable_graphics_offload: bool) -> Self {
+        let uri = gio::File::for_path(filepath).uri();
+        Self::with_uri(&uri, enable_graphics_offload)
+    }
+
+    pub fn with_uri(uri: &str, enable_graphics_offload: bool) -> Self {
+        Object::builder()
+            .property("uri", uri)
+            .property("enable-graphics-offload", enable_graphics_offload)
+            .build()
+    }
+}
+
+impl RendererWidget for ImageWidget {
+    fn mirror(&self, enable_graphics_offload: bool, content_fit: gtk::ContentFit) -> gtk::Box {
+        // Clones draw the same frame texture, animated or not.
+        picture_box(&self.imp().paintable, enable_graphics_offload, content_fit).0
+    }
+
+    fn play(&self) {
+        let imp = self.imp();
+        imp.playing.set(true);
+        imp.schedule_frame();
+    }
+
+    fn pause(&self) {
+        let imp = self.imp();
+        imp.playing.set(false);
+        imp.cancel_frame();
+    }
+
+    fn stop(&self) {
+        self.pause();
+    }
+
+    fn set_volume(&self, _volume: i32) {}
+
+    fn set_mute(&self, _mute: bool) {}
+
+    fn set_content_fit(&self, fit: gtk::ContentFit) {
+        self.imp().picture.borrow().set_content_fit(fit);
+    }
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        let imp = self.imp();
+        imp.fps_limit.set(limit);
+        imp.schedule_frame();
+    }
+}
+
+glib::wrapper! {
+    /// The frame on screen, shared by the widget and its mirrors.
+    struct ImagePaintable(ObjectSubclass<imp::ImagePaintable>)
+        @implements gdk::Paintable;
+}
+
+impl Default for ImagePaintable {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl ImagePaintable {
+    fn set_texture(&self, texture: gdk::Texture) {
+        let old = self.imp().texture.replace(Some(texture.clone()));
+        let resized = old
+            .is_none_or(|old| (old.width(), old.height()) != (texture.width(), texture.height()));
+        if resized {
+            self.invalidate_size();
+        }
+        self.invalidate_contents();
+    }
+}
+
+/// Read `file` as a gdk-pixbuf animation; still images are one-frame
+/// animations.
+async fn load(file: &gio::File) -> Result<gdk_pixbuf::PixbufAnimation, glib::Error> {
+    let stream = file.read_future(glib::Priority::DEFAULT).await?;
+    gdk_pixbuf::PixbufAnimation::from_stream_future(&stream).await
+}
+
+mod imp {
+    use super::*;
+
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
+
+    use glib::subclass::Signal;
+    use glib::Properties;
+    use tracing::debug;
+
+    #[derive(Properties, Default)]
+    #[properties(wrapper_type = super::ImageWidget)]
+    pub struct ImageWidget {
+        #[property(get, set, construct_only)]
+        uri: RefCell<String>,
+        #[property(get, set, construct_only, name = "enable-graphics-offload")]
+        enable_graphics_offload: Cell<bool>,
+        pub(super) paintable: super::ImagePaintable,
+        pub(super) picture: RefCell<gtk::Picture>,
+        /// Set for an animated image once it is loaded.
+        frames: RefCell<Option<gdk_pixbuf::PixbufAnimationIter>>,
+        frame_source: RefCell<Option<glib::SourceId>>,
+        /// Whether `play()` (rather than `pause()`/`stop()`) was called last.
+        pub(super) playing: Cell<bool>,
+        /// Runtime cap from the power policy; `Some(0)` holds the frame.
+        pub(super) fps_limit: Cell<Option<u32>>,
+        /// Whether `first-frame` was emitted.
+        first_frame: Cell<bool>,
+    }
+
+    impl ImageWidget {
+        fn set_animation(&self, animation: gdk_pixbuf::PixbufAnimation) {
+            if animation.is_static_image() {
+                debug!("Loaded still image {}", self.uri.borrow());
+                if let Some(pixbuf) = animation.static_image() {
+                    self.paintable
+                        .set_texture(gdk::Texture::for_pixbuf(&pixbuf));
+                }
+            } else {
+                debug!("Loaded animated image {}", self.uri.borrow());
+                let frames = animation.iter(Some(SystemTime::now()));
+                self.paintable
+                    .set_texture(gdk::Texture::for_pixbuf(&frames.pixbuf()));
+                self.frames.replace(Some(frames));
+                self.schedule_frame();
+            }
+            report_first_frame(&*self.obj(), &self.first_frame);
+        }
+
+        /// Arm the timer for the next frame of an animated image, unless it
+        /// is paused, held, or on a frame that shows forever. Under an FPS
+        /// cap frames come no faster than the cap; the animation clock
+        /// skips the ones in between.
+        pub(super) fn schedule_frame(&self) {
+            self.cancel_frame();
+            if !self.playing.get() || self.fps_limit.get() == Some(0) {
+                return;
+            }
+            let Some(mut delay) = self
+                .frames
+                .borrow()
+                .as_ref()
+                .and_then(|frames| frames.delay_time())
+            else {
+                return;
+            };
+            if let Some(limit) = self.fps_limit.get() {
+                delay = delay.max(Duration::from_secs(1) / limit);
+            }
+            let source = glib::timeout_add_local_once(
+                delay,
+                glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move || {
+                        // The source is gone once it fires.
+                        imp.frame_source.take();
+                        imp.next_frame();
+                    }
+                ),
+            );
+            self.frame_source.replace(Some(source));
+        }
+
+        pub(super) fn cancel_frame(&self) {
+            if let Some(source) = self.frame_source.take() {
+                source.remove();
+            }
+        }
+
+        fn next_frame(&self) {
+            let pixbuf = self
+                .frames
+                .borrow()
+                .as_ref()
+                .and_then(|frames| frames.advance(SystemTime::now()).then(|| frames.pixbuf()));
+            if let Some(pixbuf) = pixbuf {
+                self.paintable
+                    .set_texture(gdk::Texture::for_pixbuf(&pixbuf));
+            }
+            self.schedule_frame();
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImageWidget {
+        const NAME: &'static str = "ImageWidget";
+        type Type = super::ImageWidget;
+        type ParentType = gtk::Box;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for ImageWidget {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            info!("Using gdk-pixbuf for image rendering");
+            let obj = self.obj();
+            let (content, picture) = picture_box(
+                &self.paintable,
+                self.enable_graphics_offload.get(),
+                gtk::ContentFit::Cover,
+            );
+            obj.append(&content);
+            self.picture.replace(picture);
+
+            // Decoding a large image takes a while; keep the main loop
+            // going meanwhile.
+            let file = gio::File::for_uri(&self.uri.borrow());
+            let weak = obj.downgrade();
+            glib::spawn_future_local(async move {
+                let result = load(&file).await;
+                let Some(obj) = weak.upgrade() else {
+                    return;
+                };
+                match result {
+                    Ok(animation) => obj.imp().set_animation(animation),
+                    Err(error) => report_error(
+                        &obj,
+                        RendererErrorKind::Load,
+                        format!("Failed to load {}: {}", file.uri(), error),
+                    ),
+                }
+            });
+        }
+
+        fn dispose(&self) {
+            self.cancel_frame();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
+    }
+
+    impl WidgetImpl for ImageWidget {}
+
+    impl BoxImpl for ImageWidget {}
+
+    #[derive(Default)]
+    pub struct ImagePaintable {
+        pub(super) texture: RefCell<Option<gdk::Texture>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImagePaintable {
+        const NAME: &'static str = "HotaruImagePaintable";
+        type Type = super::ImagePaintable;
+        type Interfaces = (gdk::Paintable,);
+    }
+
+    impl ObjectImpl for ImagePaintable {}
+
+    impl PaintableImpl for ImagePaintable {
+        fn snapshot(&self, snapshot: &gdk::Snapshot, width: f64, height: f64) {
+            if let Some(texture) = self.texture.borrow().as_ref() {
+                texture.snapshot(snapshot, width, height);
+            }
+        }
+
+        fn intrinsic_width(&self) -> i32 {
+            self.texture.borrow().as_ref().map_or(0, |t| t.width())
+        }
+
+        fn intrinsic_height(&self) -> i32 {
+            self.texture.borrow().as_ref().map_or(0, |t| t.height())
+        }
+    }
+}
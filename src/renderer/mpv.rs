@@ -18,7 +18,10 @@
 use glib::Object;
 use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 
-use super::{mirror_by_snapshot, RendererWidget};
+use super::{
+    error_signal, first_frame_signal, mirror_by_snapshot, report_error, report_first_frame,
+    RendererErrorKind, RendererWidget,
+};
 
 glib::wrapper! {
     pub struct MpvWidget(ObjectSubclass<imp::MpvWidget>)
@@ -44,15 +47,19 @@ impl RendererWidget for MpvWidget {
     }
 
     fn play(&self) {
-        self.imp().set_mpv_property("pause", false);
+        self.imp().set_paused(false);
     }
 
     fn pause(&self) {
-        self.imp().set_mpv_property("pause", true);
+        self.imp().set_paused(true);
     }
 
     fn stop(&self) {
-        self.imp().run_mpv_command("stop", &[]);
+        let imp = self.imp();
+        // A stopped player is idle, which the load check would take for
+        // a failed load.
+        imp.cancel_load_check();
+        imp.run_mpv_command("stop", &[]);
     }
 
     fn set_volume(&self, volume: i32) {
@@ -82,27 +89,36 @@ impl RendererWidget for MpvWidget {
             }
         }
     }
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        self.imp().set_fps_limit(limit);
+    }
 }
 
 mod imp {
     use super::*;
 
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
     use std::ffi::c_void;
     use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::Arc;
+    use std::sync::{Arc, OnceLock};
+    use std::time::Duration;
 
+    use glib::subclass::Signal;
     use glib::Properties;
     use libmpv2::{
         render::{OpenGLInitParams, RenderContext, RenderParam, RenderParamApiType},
         Mpv, SetData,
     };
-    use tracing::{debug, error, info, warn};
+    use tracing::{debug, info, warn};
 
     use crate::renderer::gl_loader::{
         current_framebuffer_binding, get_proc_address_str, init_gl_resolver,
     };
 
+    /// How often a pending load is checked for failure.
+    const LOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
     fn get_proc_address(_ctx: &(), name: &str) -> *mut c_void {
         get_proc_address_str(name)
     }
@@ -122,6 +138,16 @@ mod imp {
         /// Set by mpv's render thread when a new frame is ready, polled on
         /// the frame clock to schedule a redraw on the main thread.
         needs_redraw: Arc<AtomicBool>,
+        paused: Cell<bool>,
+        /// Runtime cap from the power policy; `Some(0)` holds the frame.
+        fps_limit: Cell<Option<u32>>,
+        /// Frame-clock time (µs) of the last scheduled render, for FPS capping.
+        last_render_us: Cell<i64>,
+        /// Whether a frame arrived since the last `loadfile`.
+        frame_shown: Cell<bool>,
+        /// Whether `first-frame` was emitted.
+        first_frame: Cell<bool>,
+        load_check: RefCell<Option<glib::SourceId>>,
     }
 
     impl MpvWidget {
@@ -133,6 +159,23 @@ mod imp {
             }
         }
 
+        pub(super) fn set_paused(&self, paused: bool) {
+            self.paused.set(paused);
+            self.sync_pause();
+        }
+
+        pub(super) fn set_fps_limit(&self, limit: Option<u32>) {
+            self.fps_limit.set(limit);
+            self.sync_pause();
+        }
+
+        /// mpv's own `pause` covers both a pause and a held frame, so a
+        /// static wallpaper stops decoding (and its audio) too.
+        fn sync_pause(&self) {
+            let held = self.fps_limit.get() == Some(0);
+            self.set_mpv_property("pause", self.paused.get() || held);
+        }
+
         pub(super) fn run_mpv_command(&self, name: &str, args: &[&str]) {
             if let Some(mpv) = self.mpv.borrow().as_ref() {
                 if let Err(e) = mpv.command(name, args) {
@@ -153,12 +196,64 @@ mod imp {
             }
             debug!("mpv loadfile: {}", uri);
             self.run_mpv_command("loadfile", &[&uri, "replace"]);
+            self.watch_load(uri.clone());
+        }
+
+        /// mpv loads asynchronously and we do not drain its event queue, so
+        /// poll for the outcome: a failed load leaves the player idle
+        /// (`loop-file` keeps a working one busy), and the first frame
+        /// settles it.
+        fn watch_load(&self, uri: String) {
+            self.frame_shown.set(false);
+            let source = glib::timeout_add_local(
+                LOAD_CHECK_INTERVAL,
+                glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        let idle = imp
+                            .mpv
+                            .borrow()
+                            .as_ref()
+                            .and_then(|mpv| mpv.get_property::<bool>("idle-active").ok())
+                            .unwrap_or(false);
+                        if !imp.frame_shown.get() && !idle {
+                            return glib::ControlFlow::Continue;
+                        }
+                        // The source is gone once this returns Break.
+                        imp.load_check.take();
+                        if idle {
+                            report_error(
+                                &*imp.obj(),
+                                RendererErrorKind::Load,
+                                format!("mpv failed to load {}", uri),
+                            );
+                        }
+                        glib::ControlFlow::Break
+                    }
+                ),
+            );
+            if let Some(old) = self.load_check.replace(Some(source)) {
+                old.remove();
+            }
+        }
+
+        pub(super) fn cancel_load_check(&self) {
+            if let Some(source) = self.load_check.take() {
+                source.remove();
+            }
         }
 
         fn setup_render_context(&self, gl_area: &gtk::GLArea) {
             gl_area.make_current();
             if let Some(e) = gl_area.error() {
-                error!("GLArea failed to create a GL context: {}", e);
+                report_error(
+                    &*self.obj(),
+                    RendererErrorKind::Backend,
+                    format!("GLArea failed to create a GL context: {}", e),
+                );
                 return;
             }
             init_gl_resolver();
@@ -178,7 +273,11 @@ mod imp {
                 let render_context = match render_context {
                     Ok(render_context) => render_context,
                     Err(e) => {
-                        error!("Failed to create mpv render context: {}", e);
+                        report_error(
+                            &*self.obj(),
+                            RendererErrorKind::Backend,
+                            format!("Failed to create mpv render context: {}", e),
+                        );
                         return;
                     }
                 };
@@ -196,13 +295,30 @@ mod imp {
                 needs_redraw.store(true, Ordering::Release);
             });
 
-            let needs_redraw = self.needs_redraw.clone();
-            let tick_id = gl_area.add_tick_callback(move |gl_area, _clock| {
-                if needs_redraw.swap(false, Ordering::AcqRel) {
-                    gl_area.queue_render();
+            // Under an FPS cap, a ready frame waits (and newer ones replace
+            // it) until the interval has passed; mpv drops what is never
+            // drawn.
+            let tick_id = gl_area.add_tick_callback(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move |gl_area, clock| {
+                    let now = clock.frame_time();
+                    if let Some(limit) = imp.fps_limit.get().filter(|&limit| limit > 0) {
+                        if now - imp.last_render_us.get() < 1_000_000 / i64::from(limit) {
+                            return glib::ControlFlow::Continue;
+                        }
+                    }
+                    if imp.needs_redraw.swap(false, Ordering::AcqRel) {
+                        imp.last_render_us.set(now);
+                        imp.frame_shown.set(true);
+                        gl_area.queue_render();
+                        report_first_frame(&*imp.obj(), &imp.first_frame);
+                    }
+                    glib::ControlFlow::Continue
                 }
-                glib::ControlFlow::Continue
-            });
+            ));
             if let Some(old_tick) = self.tick_id.replace(Some(tick_id)) {
                 old_tick.remove();
             }
@@ -230,6 +346,7 @@ mod imp {
             if let Some(tick_id) = self.tick_id.take() {
                 tick_id.remove();
             }
+            self.cancel_load_check();
             // mpv_render_context_free needs the GL context current.
             gl_area.make_current();
             self.render_context.replace(None);
@@ -277,7 +394,11 @@ mod imp {
                     self.mpv.replace(Some(mpv));
                 }
                 Err(e) => {
-                    error!("Failed to initialize mpv: {}", e);
+                    report_error(
+                        &*obj,
+                        RendererErrorKind::Backend,
+                        format!("Failed to initialize mpv: {}", e),
+                    );
                 }
             }
 
@@ -306,6 +427,11 @@ mod imp {
 
             self.gl_area.replace(gl_area);
         }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
     }
 
     impl WidgetImpl for MpvWidget {}
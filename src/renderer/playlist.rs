@@ -0,0 +1,440 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Playlist renderer: a container that shows one entry of a playlist at a
+//! time through the regular renderers, and swaps the child in place when
+//! the entry's duration elapses — the window and its clones stay up. The
+//! swap plays the configured transition through a [`TransitionBox`].
+
+use glib::Object;
+use gtk::{glib, prelude::*, subclass::prelude::*};
+
+use super::{
+    error_signal, first_frame_signal, mirror_by_snapshot, report_first_frame, RendererWidget,
+};
+use crate::directory::{DirectoryScan, DirectoryWatcher};
+use crate::model::{
+    PlaylistEntry, PlaylistOrder, Transition, VideoRenderer, WallpaperType, WpeProperty,
+};
+use crate::transition_box::TransitionBox;
+
+glib::wrapper! {
+    pub struct PlaylistWidget(ObjectSubclass<imp::PlaylistWidget>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl PlaylistWidget {
+    /// `wallpaper_type` is the enclosing monitor's type, used for entries
+    /// that do not state their own.
+    pub fn new(
+        entries: &[PlaylistEntry],
+        order: PlaylistOrder,
+        wallpaper_type: WallpaperType,
+        video_renderer: VideoRenderer,
+        enable_graphics_offload: bool,
+    ) -> Self {
+        let obj: Self = Object::builder().build();
+        let imp = obj.imp();
+        imp.entries.replace(entries.to_vec());
+        imp.order.set(order);
+        imp.default_type.set(Some(wallpaper_type));
+        imp.video_renderer.set(video_renderer);
+        imp.enable_graphics_offload.set(enable_graphics_offload);
+        imp.reset_sequence();
+        imp.show_current();
+        obj
+    }
+
+    /// A playlist over the wallpapers in a directory, updated as the
+    /// directory changes.
+    pub fn with_directory(
+        scan: DirectoryScan,
+        order: PlaylistOrder,
+        video_renderer: VideoRenderer,
+        enable_graphics_offload: bool,
+    ) -> Self {
+        // Entries carry their detected type; the default is never used.
+        let obj = Self::new(
+            &scan.entries(),
+            order,
+            WallpaperType::Video,
+            video_renderer,
+            enable_graphics_offload,
+        );
+        let watcher = DirectoryWatcher::new(
+            scan,
+            glib::clone!(
+                #[weak]
+                obj,
+                move |entries| obj.set_entries(&entries)
+            ),
+        );
+        obj.imp().watcher.replace(Some(watcher));
+        obj
+    }
+
+    /// Replace the entry list. The entry on screen keeps playing if it is
+    /// still listed; otherwise the playlist moves on to the new first one.
+    pub fn set_entries(&self, entries: &[PlaylistEntry]) {
+        self.imp().replace_entries(entries);
+    }
+
+    /// Advance to the next entry now.
+    pub fn next(&self) {
+        self.imp().advance(1);
+    }
+
+    /// Go back to the previous entry now.
+    pub fn previous(&self) {
+        self.imp().advance(-1);
+    }
+
+    /// The transition played between entries.
+    pub fn set_transition(&self, transition: Transition) {
+        self.imp().transitions.set_transition(transition);
+    }
+
+    /// See [`Renderer::wpe_properties`]; the current entry's.
+    pub fn wpe_properties(&self) -> Result<Vec<WpeProperty>, String> {
+        match self.imp().current.borrow().as_ref() {
+            Some(renderer) => renderer.wpe_properties(),
+            None => Err("the playlist shows nothing".into()),
+        }
+    }
+
+    /// See [`Renderer::set_wpe_property`]. Applies to the current entry
+    /// until the playlist moves on.
+    pub fn set_wpe_property(&self, name: &str, value: serde_json::Value) -> Result<(), String> {
+        match self.imp().current.borrow().as_ref() {
+            Some(renderer) => renderer.set_wpe_property(name, value),
+            None => Err("the playlist shows nothing".into()),
+        }
+    }
+}
+
+impl RendererWidget for PlaylistWidget {
+    fn mirror(&self, enable_graphics_offload: bool, content_fit: gtk::ContentFit) -> gtk::Box {
+        // The child renderer changes under the clones' feet, so mirror the
+        // container itself rather than any one child's output.
+        mirror_by_snapshot(self, enable_graphics_offload, content_fit)
+    }
+
+    fn play(&self) {
+        let imp = self.imp();
+        imp.playing.set(true);
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.play();
+        }
+        imp.schedule_advance();
+    }
+
+    fn pause(&self) {
+        let imp = self.imp();
+        imp.playing.set(false);
+        imp.cancel_advance();
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.pause();
+        }
+    }
+
+    fn stop(&self) {
+        let imp = self.imp();
+        imp.playing.set(false);
+        imp.cancel_advance();
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.stop();
+        }
+    }
+
+    fn set_volume(&self, volume: i32) {
+        let imp = self.imp();
+        imp.volume.set(Some(volume));
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.set_volume(volume);
+        }
+    }
+
+    fn set_mute(&self, mute: bool) {
+        let imp = self.imp();
+        imp.mute.set(Some(mute));
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.set_mute(mute);
+        }
+    }
+
+    fn set_content_fit(&self, fit: gtk::ContentFit) {
+        let imp = self.imp();
+        imp.content_fit.set(Some(fit));
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.set_content_fit(fit);
+        }
+    }
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        // Only the child is capped: a held playlist keeps rotating, showing
+        // each entry as a still.
+        let imp = self.imp();
+        imp.fps_limit.set(limit);
+        if let Some(renderer) = imp.current.borrow().as_ref() {
+            renderer.set_fps_limit(limit);
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
+
+    use glib::subclass::Signal;
+    use glib::Properties;
+    use tracing::{debug, warn};
+
+    use crate::renderer::Renderer;
+
+    #[derive(Properties, Default)]
+    #[properties(wrapper_type = super::PlaylistWidget)]
+    pub struct PlaylistWidget {
+        /// Index (into the configured entry list) of the entry on screen.
+        #[property(get)]
+        position: Cell<u32>,
+        pub(super) entries: RefCell<Vec<PlaylistEntry>>,
+        pub(super) order: Cell<PlaylistOrder>,
+        pub(super) default_type: Cell<Option<WallpaperType>>,
+        pub(super) video_renderer: Cell<VideoRenderer>,
+        pub(super) enable_graphics_offload: Cell<bool>,
+        /// Play order as indices into `entries`; a permutation when
+        /// shuffling, regenerated at the start of every cycle.
+        sequence: RefCell<Vec<usize>>,
+        /// Position within `sequence`.
+        cursor: Cell<usize>,
+        pub(super) current: RefCell<Option<Renderer>>,
+        advance_source: RefCell<Option<glib::SourceId>>,
+        /// Renderers start paused; `play()` starts the rotation.
+        pub(super) playing: Cell<bool>,
+        /// Holds the entry on screen, and the one before it while the
+        /// transition between them plays.
+        pub(super) transitions: TransitionBox,
+        /// Keeps a directory source's monitors alive.
+        pub(super) watcher: RefCell<Option<DirectoryWatcher>>,
+        // Cached so every newly swapped-in child gets the current values.
+        pub(super) volume: Cell<Option<i32>>,
+        pub(super) mute: Cell<Option<bool>>,
+        pub(super) content_fit: Cell<Option<gtk::ContentFit>>,
+        pub(super) fps_limit: Cell<Option<u32>>,
+        /// Whether `first-frame` was emitted: for the first entry only,
+        /// later ones replace it in place.
+        first_frame: Cell<bool>,
+    }
+
+    impl PlaylistWidget {
+        pub(super) fn reset_sequence(&self) {
+            let mut sequence: Vec<usize> = (0..self.entries.borrow().len()).collect();
+            if self.order.get() == PlaylistOrder::Shuffle {
+                // Fisher-Yates with GLib's RNG.
+                for i in (1..sequence.len()).rev() {
+                    let j = glib::random_int_range(0, i as i32 + 1) as usize;
+                    sequence.swap(i, j);
+                }
+            }
+            self.sequence.replace(sequence);
+            self.cursor.set(0);
+        }
+
+        pub(super) fn replace_entries(&self, entries: &[PlaylistEntry]) {
+            let current = self
+                .current
+                .borrow()
+                .is_some()
+                .then(|| {
+                    self.entries
+                        .borrow()
+                        .get(self.position.get() as usize)
+                        .cloned()
+                })
+                .flatten();
+            self.entries.replace(entries.to_vec());
+            self.reset_sequence();
+
+            let Some(index) =
+                current.and_then(|current| entries.iter().position(|e| *e == current))
+            else {
+                self.show_current();
+                return;
+            };
+            // Still listed: point the cursor at it without interrupting it.
+            let cursor = self
+                .sequence
+                .borrow()
+                .iter()
+                .position(|&i| i == index)
+                .unwrap_or_default();
+            self.cursor.set(cursor);
+            if self.position.replace(index as u32) != index as u32 {
+                self.obj().notify_position();
+            }
+            if self.advance_source.borrow().is_none() {
+                // A list that just grew past one entry starts rotating.
+                self.schedule_advance();
+            }
+        }
+
+        pub(super) fn advance(&self, step: isize) {
+            let len = self.sequence.borrow().len();
+            if len == 0 {
+                return;
+            }
+            let next = self.cursor.get() as isize + step;
+            if next >= len as isize && self.order.get() == PlaylistOrder::Shuffle {
+                self.reset_sequence();
+            } else {
+                self.cursor.set(next.rem_euclid(len as isize) as usize);
+            }
+            self.show_current();
+        }
+
+        /// Replace the child with the renderer for the entry at the cursor.
+        /// The old entry keeps playing, muted, until the transition to the
+        /// new one is over.
+        pub(super) fn show_current(&self) {
+            self.cancel_advance();
+            let obj = self.obj();
+            let old = self.current.take();
+            if let Some(old) = &old {
+                old.set_mute(true);
+            }
+
+            let Some(&index) = self.sequence.borrow().get(self.cursor.get()) else {
+                warn!("Playlist is empty, nothing to show");
+                self.transitions.clear();
+                if let Some(old) = old {
+                    old.stop();
+                }
+                return;
+            };
+            let entry = self.entries.borrow()[index].clone();
+            let wallpaper_type = entry
+                .wallpaper_type
+                .or(self.default_type.get())
+                .unwrap_or(WallpaperType::Video);
+            debug!("Playlist entry {}: {:?}", index, entry.wallpaper_source);
+
+            let renderer = Renderer::with_source(
+                &wallpaper_type,
+                &entry.wallpaper_source,
+                &entry.wpe_properties,
+                self.video_renderer.get(),
+                self.enable_graphics_offload.get(),
+            );
+            if let Some(fit) = self.content_fit.get() {
+                renderer.set_content_fit(fit);
+            }
+            if let Some(volume) = self.volume.get() {
+                renderer.set_volume(volume);
+            }
+            if let Some(mute) = self.mute.get() {
+                renderer.set_mute(mute);
+            }
+            renderer.set_fps_limit(self.fps_limit.get());
+            // The entry's failures are the playlist's own.
+            renderer.connect_error(glib::clone!(
+                #[weak]
+                obj,
+                move |error| error.emit(&obj)
+            ));
+            renderer.connect_first_frame(glib::clone!(
+                #[weak]
+                obj,
+                move || report_first_frame(&obj, &obj.imp().first_frame)
+            ));
+            self.transitions.push(renderer.widget(), move || {
+                if let Some(old) = old {
+                    old.stop();
+                }
+            });
+            self.transitions.reveal_on_first_frame(&renderer);
+            if self.playing.get() {
+                renderer.play();
+            }
+            self.current.replace(Some(renderer));
+
+            self.position.set(index as u32);
+            obj.notify_position();
+            self.schedule_advance();
+        }
+
+        pub(super) fn schedule_advance(&self) {
+            self.cancel_advance();
+            if !self.playing.get() || self.sequence.borrow().len() < 2 {
+                return;
+            }
+            let index = self.position.get() as usize;
+            let duration = self.entries.borrow()[index].duration.max(1);
+            let source = glib::timeout_add_seconds_local_once(
+                duration,
+                glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move || {
+                        // The source is gone once it fires; forget its id so
+                        // cancel_advance doesn't remove it a second time.
+                        imp.advance_source.take();
+                        imp.advance(1);
+                    }
+                ),
+            );
+            self.advance_source.replace(Some(source));
+        }
+
+        pub(super) fn cancel_advance(&self) {
+            if let Some(source) = self.advance_source.take() {
+                source.remove();
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PlaylistWidget {
+        const NAME: &'static str = "PlaylistWidget";
+        type Type = super::PlaylistWidget;
+        type ParentType = gtk::Box;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for PlaylistWidget {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().append(&self.transitions);
+        }
+
+        fn dispose(&self) {
+            self.cancel_advance();
+            self.watcher.take();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
+    }
+
+    impl WidgetImpl for PlaylistWidget {}
+
+    impl BoxImpl for PlaylistWidget {}
+}
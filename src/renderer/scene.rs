@@ -18,12 +18,17 @@
 //! Wallpaper Engine scene renderer, backed by linux-wallpaperengine's
 //! embedding API (wpe_embed.h). The engine library is dlopen'd at runtime,
 //! so builds and installs work without it; a scene wallpaper then fails
-//! with a logged error instead of a startup failure.
+//! with a reported error instead of a startup failure.
+
+use std::collections::BTreeMap;
 
 use glib::Object;
 use gtk::{glib, prelude::*, subclass::prelude::*};
 
-use super::{mirror_by_snapshot, RendererWidget};
+use super::{
+    error_signal, first_frame_signal, mirror_by_snapshot, report_error, report_first_frame,
+    RendererErrorKind, RendererWidget,
+};
 
 glib::wrapper! {
     pub struct SceneWidget(ObjectSubclass<imp::SceneWidget>)
@@ -33,9 +38,28 @@ glib::wrapper! {
 
 impl SceneWidget {
     /// Scenes are always local package directories, so there is no URI
-    /// constructor.
-    pub fn with_filepath(filepath: &str) -> Self {
-        Object::builder().property("filepath", filepath).build()
+    /// constructor. `properties` are the user property values that differ
+    /// from the package defaults, handed to the engine at load.
+    pub fn with_filepath(filepath: &str, properties: &BTreeMap<String, serde_json::Value>) -> Self {
+        Object::builder()
+            .property("filepath", filepath)
+            .property(
+                "wpe-properties",
+                serde_json::to_string(properties).unwrap_or_default(),
+            )
+            .build()
+    }
+
+    /// The user property values the scene loads with, where they differ
+    /// from the package defaults.
+    pub fn wpe_overrides(&self) -> BTreeMap<String, serde_json::Value> {
+        serde_json::from_str(&self.wpe_properties()).unwrap_or_default()
+    }
+
+    /// Whether the engine library loads (and passes the ABI check). The
+    /// first call attempts the dlopen.
+    pub fn engine_available() -> bool {
+        imp::engine_loaded()
     }
 }
 
@@ -69,6 +93,10 @@ impl RendererWidget for SceneWidget {
     fn set_content_fit(&self, fit: gtk::ContentFit) {
         self.imp().set_content_fit(fit);
     }
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        self.imp().set_fps_limit(limit);
+    }
 }
 
 mod imp {
@@ -79,8 +107,9 @@ mod imp {
     use std::ptr;
     use std::sync::OnceLock;
 
+    use glib::subclass::Signal;
     use glib::Properties;
-    use tracing::{error, info};
+    use tracing::info;
 
     use crate::renderer::gl_loader::{
         current_framebuffer_binding, get_proc_address_cstr, init_gl_resolver,
@@ -92,7 +121,8 @@ mod imp {
     const ASSETS_ENV: &str = "HOTARU_WPE_ASSETS";
     const DEFAULT_LIBRARY: &str = "liblinux-wallpaperengine-lib.so";
 
-    use crate::wpe::fps_limit;
+    use crate::model::engine_assignment;
+    use crate::wpe::capped_fps;
 
     /// Embed ABI this build was compiled against (WPE_EMBED_ABI_VERSION in
     /// wpe_embed.h). The structs and signatures below are hand-mirrored from
@@ -114,6 +144,8 @@ mod imp {
         disable_audio_processing: c_int,
         volume: c_int,
         scaling: *const c_char,
+        /// NULL-terminated `name=value` user property settings (the
+        /// engine's `--set-property`), or null.
         properties: *const *const c_char,
     }
 
@@ -147,7 +179,8 @@ mod imp {
     unsafe impl Send for WpeLib {}
     unsafe impl Sync for WpeLib {}
 
-    static WPE_LIB: OnceLock<Option<WpeLib>> = OnceLock::new();
+    /// The loaded library, or why it failed to load.
+    static WPE_LIB: OnceLock<Result<WpeLib, String>> = OnceLock::new();
 
     /// The library to dlopen: `$HOTARU_WPE_LIBRARY` if set, else the first
     /// existing `<prefix>/lib{,64}/hotaru/liblinux-wallpaperengine-lib.so`
@@ -172,18 +205,21 @@ mod imp {
     }
 
     fn wpe_lib() -> Option<&'static WpeLib> {
+        load_wpe_lib().ok()
+    }
+
+    fn load_wpe_lib() -> Result<&'static WpeLib, &'static str> {
         WPE_LIB
             .get_or_init(|| {
                 let lib_name = library_name();
                 let lib = match unsafe { libloading::Library::new(&lib_name) } {
                     Ok(lib) => lib,
                     Err(e) => {
-                        error!(
+                        return Err(format!(
                             "Failed to load wallpaper engine library {} ({}); \
                              run 'make wpe-lib install', or set {} to its full path",
                             lib_name, e, LIBRARY_ENV
-                        );
-                        return None;
+                        ));
                     }
                 };
                 macro_rules! sym {
@@ -191,8 +227,7 @@ mod imp {
                         match unsafe { lib.get($name) } {
                             Ok(sym) => *sym,
                             Err(e) => {
-                                error!("{} lacks {:?}: {}", lib_name, $name, e);
-                                return None;
+                                return Err(format!("{} lacks {:?}: {}", lib_name, $name, e));
                             }
                         }
                     };
@@ -204,12 +239,11 @@ mod imp {
                 let abi_version: unsafe extern "C" fn() -> c_int = sym!(b"wpe_abi_version\0");
                 let reported = unsafe { abi_version() };
                 if reported != WPE_ABI_VERSION {
-                    error!(
+                    return Err(format!(
                         "{} reports embed ABI version {}, but this build expects {}; \
                          rebuild the library and hotaru from matching sources",
                         lib_name, reported, WPE_ABI_VERSION
-                    );
-                    return None;
+                    ));
                 }
 
                 let resolved = WpeLib {
@@ -223,9 +257,14 @@ mod imp {
                     _lib: lib,
                 };
                 info!("wallpaper engine library loaded: {}", lib_name);
-                Some(resolved)
+                Ok(resolved)
             })
             .as_ref()
+            .map_err(String::as_str)
+    }
+
+    pub(super) fn engine_loaded() -> bool {
+        wpe_lib().is_some()
     }
 
     unsafe extern "C" fn get_proc_address(
@@ -254,6 +293,9 @@ mod imp {
     pub struct SceneWidget {
         #[property(get, set)]
         filepath: RefCell<String>,
+        /// JSON object of the user property values set at load.
+        #[property(get, set, name = "wpe-properties")]
+        wpe_properties: RefCell<String>,
         #[property(get, name = "gl-area")]
         gl_area: RefCell<gtk::GLArea>,
         ctx: Cell<*mut WpeContext>,
@@ -261,20 +303,55 @@ mod imp {
         paused: Cell<bool>,
         /// Frame-clock time (µs) of the last scheduled render, for FPS capping.
         last_render_us: Cell<i64>,
+        /// Runtime cap from the power policy; `Some(0)` holds the frame.
+        fps_limit: Cell<Option<u32>>,
         // Cached so values set before realize (or between rebuilds) apply
         // when the engine context exists.
         volume: Cell<i32>,
         mute: Cell<bool>,
         content_fit: Cell<Option<gtk::ContentFit>>,
+        /// Whether `first-frame` was emitted.
+        first_frame: Cell<bool>,
+        /// Whether the engine context captures audio for audio-reactive
+        /// scenes, which it does when created playing.
+        audio_processing: Cell<bool>,
     }
 
     impl SceneWidget {
         pub(super) fn set_paused(&self, paused: bool) {
             self.paused.set(paused);
+            self.sync_paused();
+        }
+
+        pub(super) fn set_fps_limit(&self, limit: Option<u32>) {
+            self.fps_limit.set(limit);
+            self.sync_paused();
+        }
+
+        /// Whether the engine should stand still: paused, or held on a
+        /// static frame.
+        fn is_held(&self) -> bool {
+            self.paused.get() || self.fps_limit.get() == Some(0)
+        }
+
+        fn frame_interval_us(&self) -> i64 {
+            1_000_000 / capped_fps(self.fps_limit.get()).max(1)
+        }
+
+        fn sync_paused(&self) {
+            let held = self.is_held();
             let ctx = self.ctx.get();
+            // The engine takes audio processing at scene load; a scene
+            // created held gets a context that captures once it plays.
+            if !held && !ctx.is_null() && !self.audio_processing.get() {
+                let gl_area = self.gl_area.borrow().clone();
+                self.teardown_context(&gl_area);
+                self.setup_context(&gl_area);
+                return;
+            }
             if let (Some(lib), false) = (wpe_lib(), ctx.is_null()) {
-                unsafe { (lib.set_paused)(ctx, paused as c_int) };
-                if !paused {
+                unsafe { (lib.set_paused)(ctx, held as c_int) };
+                if !held {
                     self.gl_area.borrow().queue_render();
                 }
             }
@@ -314,13 +391,23 @@ mod imp {
             if filepath.is_empty() {
                 return;
             }
-            let Some(lib) = wpe_lib() else {
-                return;
+            let obj = self.obj();
+            // Every scene reports a library failure; it is only loaded once.
+            let lib = match load_wpe_lib() {
+                Ok(lib) => lib,
+                Err(e) => {
+                    report_error(&*obj, RendererErrorKind::Backend, e);
+                    return;
+                }
             };
 
             gl_area.make_current();
             if let Some(e) = gl_area.error() {
-                error!("GLArea failed to create a GL context: {}", e);
+                report_error(
+                    &*obj,
+                    RendererErrorKind::Backend,
+                    format!("GLArea failed to create a GL context: {}", e),
+                );
                 return;
             }
             init_gl_resolver();
@@ -337,12 +424,29 @@ mod imp {
                 }
             });
             let Ok(background) = CString::new(filepath.as_str()) else {
-                error!("invalid scene path: {}", filepath);
+                report_error(
+                    &*obj,
+                    RendererErrorKind::Load,
+                    format!("invalid scene path: {}", filepath),
+                );
                 return;
             };
             let scaling = to_wpe_scaling(self.content_fit.get().unwrap_or(gtk::ContentFit::Cover));
             let scaling = CString::new(scaling).unwrap();
-
+            let assignments: Vec<CString> = obj
+                .wpe_overrides()
+                .iter()
+                .filter_map(|(name, value)| CString::new(engine_assignment(name, value)).ok())
+                .collect();
+            let mut properties: Vec<*const c_char> = assignments
+                .iter()
+                .map(|assignment| assignment.as_ptr())
+                .collect();
+            properties.push(ptr::null());
+
+            // The engine's own capture (PulseAudio + FFT) feeds
+            // audio-reactive scenes; it only runs for a playing scene.
+            let audio_processing = !self.is_held();
             let params = WpeInitParams {
                 assets_dir: assets.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
                 background: background.as_ptr(),
@@ -356,10 +460,14 @@ mod imp {
                 disable_mouse: 0,
                 disable_parallax: 0,
                 disable_audio: 0,
-                disable_audio_processing: 1,
+                disable_audio_processing: !audio_processing as c_int,
                 volume: to_wpe_volume(self.volume.get()),
                 scaling: scaling.as_ptr(),
-                properties: ptr::null(),
+                properties: if assignments.is_empty() {
+                    ptr::null()
+                } else {
+                    properties.as_ptr()
+                },
             };
 
             let mut error_msg: *mut c_char = ptr::null_mut();
@@ -375,22 +483,27 @@ mod imp {
                     unsafe { libc::free(error_msg as *mut c_void) };
                     msg
                 };
-                error!("Failed to load scene {}: {}", filepath, msg);
+                report_error(
+                    &*obj,
+                    RendererErrorKind::Load,
+                    format!("Failed to load scene {}: {}", filepath, msg),
+                );
                 return;
             }
             info!("scene loaded: {}", filepath);
             self.ctx.set(ctx);
+            self.audio_processing.set(audio_processing);
 
             unsafe {
                 (lib.set_audio_enabled)(ctx, !self.mute.get() as c_int);
-                (lib.set_paused)(ctx, self.paused.get() as c_int);
+                (lib.set_paused)(ctx, self.is_held() as c_int);
             }
 
             // Scenes animate continuously: redraw on frame clock ticks while
-            // playing, capped at the FPS limit so wallpapers don't render at
-            // full refresh on high-Hz displays. A paused scene stays a still
-            // frame (damage events still repaint it via the render handler).
-            let frame_interval_us = 1_000_000 / fps_limit();
+            // playing, capped at the FPS limit (lowered further by the power
+            // policy) so wallpapers don't render at full refresh on high-Hz
+            // displays. A paused or held scene stays a still frame (damage
+            // events still repaint it via the render handler).
             let tick_id = gl_area.add_tick_callback(glib::clone!(
                 #[weak(rename_to = imp)]
                 self,
@@ -398,7 +511,7 @@ mod imp {
                 glib::ControlFlow::Break,
                 move |gl_area, clock| {
                     let now = clock.frame_time();
-                    if !imp.paused.get() && now - imp.last_render_us.get() >= frame_interval_us {
+                    if !imp.is_held() && now - imp.last_render_us.get() >= imp.frame_interval_us() {
                         imp.last_render_us.set(now);
                         gl_area.queue_render();
                     }
@@ -427,6 +540,7 @@ mod imp {
                 .map(|clock| clock.frame_time() as f64 / 1_000_000.0)
                 .unwrap_or_default();
             unsafe { (lib.render)(ctx, fbo, width, height, time) };
+            report_first_frame(&*self.obj(), &self.first_frame);
         }
 
         fn feed_mouse(&self, x: f64, y: f64) {
@@ -502,6 +616,11 @@ mod imp {
 
             self.gl_area.replace(gl_area);
         }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
     }
 
     impl WidgetImpl for SceneWidget {}
@@ -0,0 +1,668 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! GLSL shader renderer: a `gtk::GLArea` running a wallpaper's fragment
+//! shader over the whole window, with Shadertoy's uniforms. GL entry points
+//! are resolved through the shared `gl_loader`, like the other GL-based
+//! renderers.
+
+use glib::Object;
+use gtk::{glib, prelude::*, subclass::prelude::*};
+
+use super::{
+    error_signal, first_frame_signal, mirror_by_snapshot, report_error, report_first_frame,
+    RendererErrorKind, RendererWidget,
+};
+
+glib::wrapper! {
+    pub struct ShaderWidget(ObjectSubclass<imp::ShaderWidget>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ShaderWidget {
+    pub fn with_filepath(filepath: &str) -> Self {
+        Object::builder().property("filepath", filepath).build()
+    }
+}
+
+impl RendererWidget for ShaderWidget {
+    fn mirror(&self, enable_graphics_offload: bool, content_fit: gtk::ContentFit) -> gtk::Box {
+        // The shader draws straight into its GLArea and exposes no
+        // gdk::Paintable.
+        mirror_by_snapshot(&self.gl_area(), enable_graphics_offload, content_fit)
+    }
+
+    fn play(&self) {
+        self.imp().set_paused(false);
+    }
+
+    fn pause(&self) {
+        self.imp().set_paused(true);
+    }
+
+    fn stop(&self) {
+        self.imp().set_paused(true);
+    }
+
+    fn set_volume(&self, _volume: i32) {}
+
+    fn set_mute(&self, _mute: bool) {}
+
+    /// A shader fills whatever size it is given; there is nothing to fit.
+    fn set_content_fit(&self, _fit: gtk::ContentFit) {}
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        self.imp().set_fps_limit(limit);
+    }
+}
+
+mod imp {
+    use super::*;
+
+    use std::cell::{Cell, RefCell};
+    use std::ffi::{c_char, c_void, CString};
+    use std::path::Path;
+    use std::ptr;
+    use std::sync::OnceLock;
+
+    use glib::subclass::Signal;
+    use glib::Properties;
+    use gtk::gdk;
+    use tracing::{info, warn};
+
+    use crate::model::{ShaderSource, SHADER_CHANNELS};
+    use crate::renderer::gl_loader::{get_proc_address_cstr, init_gl_resolver};
+    use crate::wpe::capped_fps;
+
+    const GL_TRIANGLES: u32 = 0x0004;
+    const GL_UNSIGNED_BYTE: u32 = 0x1401;
+    const GL_RGBA: u32 = 0x1908;
+    const GL_RGBA8: i32 = 0x8058;
+    const GL_UNPACK_ALIGNMENT: u32 = 0x0CF5;
+    const GL_TEXTURE_2D: u32 = 0x0DE1;
+    const GL_TEXTURE0: u32 = 0x84C0;
+    const GL_TEXTURE_MAG_FILTER: u32 = 0x2800;
+    const GL_TEXTURE_MIN_FILTER: u32 = 0x2801;
+    const GL_TEXTURE_WRAP_S: u32 = 0x2802;
+    const GL_TEXTURE_WRAP_T: u32 = 0x2803;
+    const GL_LINEAR: i32 = 0x2601;
+    const GL_LINEAR_MIPMAP_LINEAR: i32 = 0x2703;
+    const GL_REPEAT: i32 = 0x2901;
+    const GL_FRAGMENT_SHADER: u32 = 0x8B30;
+    const GL_VERTEX_SHADER: u32 = 0x8B31;
+    const GL_COMPILE_STATUS: u32 = 0x8B81;
+    const GL_LINK_STATUS: u32 = 0x8B82;
+    const GL_INFO_LOG_LENGTH: u32 = 0x8B84;
+
+    /// One triangle covering the viewport, from `gl_VertexID` alone.
+    const VERTEX_SHADER: &str = "#version 330 core
+void main() {
+    vec2 corner = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+    gl_Position = vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
+";
+
+    /// The GL 3.3 core entry points the renderer uses.
+    struct Gl {
+        create_shader: unsafe extern "C" fn(u32) -> u32,
+        shader_source: unsafe extern "C" fn(u32, i32, *const *const c_char, *const i32),
+        compile_shader: unsafe extern "C" fn(u32),
+        get_shaderiv: unsafe extern "C" fn(u32, u32, *mut i32),
+        get_shader_info_log: unsafe extern "C" fn(u32, i32, *mut i32, *mut c_char),
+        delete_shader: unsafe extern "C" fn(u32),
+        create_program: unsafe extern "C" fn() -> u32,
+        attach_shader: unsafe extern "C" fn(u32, u32),
+        link_program: unsafe extern "C" fn(u32),
+        get_programiv: unsafe extern "C" fn(u32, u32, *mut i32),
+        get_program_info_log: unsafe extern "C" fn(u32, i32, *mut i32, *mut c_char),
+        delete_program: unsafe extern "C" fn(u32),
+        use_program: unsafe extern "C" fn(u32),
+        get_uniform_location: unsafe extern "C" fn(u32, *const c_char) -> i32,
+        uniform1f: unsafe extern "C" fn(i32, f32),
+        uniform1i: unsafe extern "C" fn(i32, i32),
+        uniform3f: unsafe extern "C" fn(i32, f32, f32, f32),
+        uniform4f: unsafe extern "C" fn(i32, f32, f32, f32, f32),
+        gen_vertex_arrays: unsafe extern "C" fn(i32, *mut u32),
+        bind_vertex_array: unsafe extern "C" fn(u32),
+        delete_vertex_arrays: unsafe extern "C" fn(i32, *const u32),
+        draw_arrays: unsafe extern "C" fn(u32, i32, i32),
+        viewport: unsafe extern "C" fn(i32, i32, i32, i32),
+        gen_textures: unsafe extern "C" fn(i32, *mut u32),
+        delete_textures: unsafe extern "C" fn(i32, *const u32),
+        bind_texture: unsafe extern "C" fn(u32, u32),
+        active_texture: unsafe extern "C" fn(u32),
+        tex_image_2d: unsafe extern "C" fn(u32, i32, i32, i32, i32, i32, u32, u32, *const c_void),
+        tex_parameteri: unsafe extern "C" fn(u32, u32, i32),
+        generate_mipmap: unsafe extern "C" fn(u32),
+        pixel_storei: unsafe extern "C" fn(u32, i32),
+    }
+
+    /// The resolved entry points, or the first one that failed to resolve.
+    static GL: OnceLock<Result<Gl, String>> = OnceLock::new();
+
+    /// Resolve the entry points once `init_gl_resolver` ran.
+    fn load_gl() -> Result<&'static Gl, &'static str> {
+        GL.get_or_init(|| {
+            macro_rules! gl_fn {
+                ($name:literal) => {{
+                    let ptr = get_proc_address_cstr($name.as_ptr());
+                    if ptr.is_null() {
+                        return Err(format!("GL lacks {:?}", $name));
+                    }
+                    // SAFETY: the pointer is the named GL entry point, whose
+                    // signature the field's type mirrors.
+                    unsafe { std::mem::transmute::<*mut c_void, _>(ptr) }
+                }};
+            }
+            Ok(Gl {
+                create_shader: gl_fn!(c"glCreateShader"),
+                shader_source: gl_fn!(c"glShaderSource"),
+                compile_shader: gl_fn!(c"glCompileShader"),
+                get_shaderiv: gl_fn!(c"glGetShaderiv"),
+                get_shader_info_log: gl_fn!(c"glGetShaderInfoLog"),
+                delete_shader: gl_fn!(c"glDeleteShader"),
+                create_program: gl_fn!(c"glCreateProgram"),
+                attach_shader: gl_fn!(c"glAttachShader"),
+                link_program: gl_fn!(c"glLinkProgram"),
+                get_programiv: gl_fn!(c"glGetProgramiv"),
+                get_program_info_log: gl_fn!(c"glGetProgramInfoLog"),
+                delete_program: gl_fn!(c"glDeleteProgram"),
+                use_program: gl_fn!(c"glUseProgram"),
+                get_uniform_location: gl_fn!(c"glGetUniformLocation"),
+                uniform1f: gl_fn!(c"glUniform1f"),
+                uniform1i: gl_fn!(c"glUniform1i"),
+                uniform3f: gl_fn!(c"glUniform3f"),
+                uniform4f: gl_fn!(c"glUniform4f"),
+                gen_vertex_arrays: gl_fn!(c"glGenVertexArrays"),
+                bind_vertex_array: gl_fn!(c"glBindVertexArray"),
+                delete_vertex_arrays: gl_fn!(c"glDeleteVertexArrays"),
+                draw_arrays: gl_fn!(c"glDrawArrays"),
+                viewport: gl_fn!(c"glViewport"),
+                gen_textures: gl_fn!(c"glGenTextures"),
+                delete_textures: gl_fn!(c"glDeleteTextures"),
+                bind_texture: gl_fn!(c"glBindTexture"),
+                active_texture: gl_fn!(c"glActiveTexture"),
+                tex_image_2d: gl_fn!(c"glTexImage2D"),
+                tex_parameteri: gl_fn!(c"glTexParameteri"),
+                generate_mipmap: gl_fn!(c"glGenerateMipmap"),
+                pixel_storei: gl_fn!(c"glPixelStorei"),
+            })
+        })
+        .as_ref()
+        .map_err(String::as_str)
+    }
+
+    fn gl() -> Option<&'static Gl> {
+        GL.get().and_then(|gl| gl.as_ref().ok())
+    }
+
+    /// Compile one stage, or return its info log.
+    fn compile(gl: &Gl, kind: u32, code: &str) -> Result<u32, String> {
+        let code = CString::new(code).map_err(|_| "shader code contains a NUL byte")?;
+        unsafe {
+            let shader = (gl.create_shader)(kind);
+            (gl.shader_source)(shader, 1, &code.as_ptr(), ptr::null());
+            (gl.compile_shader)(shader);
+            let mut status = 0;
+            (gl.get_shaderiv)(shader, GL_COMPILE_STATUS, &mut status);
+            if status != 0 {
+                return Ok(shader);
+            }
+            let mut length = 0;
+            (gl.get_shaderiv)(shader, GL_INFO_LOG_LENGTH, &mut length);
+            let mut log = vec![0u8; length.max(1) as usize];
+            (gl.get_shader_info_log)(shader, length, ptr::null_mut(), log.as_mut_ptr().cast());
+            (gl.delete_shader)(shader);
+            Err(info_log(log))
+        }
+    }
+
+    /// Compile and link the program, or return the failing info log.
+    fn link(gl: &Gl, fragment: &str) -> Result<u32, String> {
+        let vertex = compile(gl, GL_VERTEX_SHADER, VERTEX_SHADER)?;
+        let fragment = match compile(gl, GL_FRAGMENT_SHADER, fragment) {
+            Ok(fragment) => fragment,
+            Err(log) => {
+                unsafe { (gl.delete_shader)(vertex) };
+                return Err(log);
+            }
+        };
+        unsafe {
+            let program = (gl.create_program)();
+            (gl.attach_shader)(program, vertex);
+            (gl.attach_shader)(program, fragment);
+            (gl.link_program)(program);
+            // The program keeps what it needs.
+            (gl.delete_shader)(vertex);
+            (gl.delete_shader)(fragment);
+            let mut status = 0;
+            (gl.get_programiv)(program, GL_LINK_STATUS, &mut status);
+            if status != 0 {
+                return Ok(program);
+            }
+            let mut length = 0;
+            (gl.get_programiv)(program, GL_INFO_LOG_LENGTH, &mut length);
+            let mut log = vec![0u8; length.max(1) as usize];
+            (gl.get_program_info_log)(program, length, ptr::null_mut(), log.as_mut_ptr().cast());
+            (gl.delete_program)(program);
+            Err(info_log(log))
+        }
+    }
+
+    fn info_log(mut log: Vec<u8>) -> String {
+        if let Some(end) = log.iter().position(|&b| b == 0) {
+            log.truncate(end);
+        }
+        String::from_utf8_lossy(&log).trim_end().to_string()
+    }
+
+    /// Upload an image as a repeating, mipmapped RGBA texture, bottom row
+    /// first as Shadertoy flips its textures. Returns the texture and its
+    /// size.
+    fn load_channel(gl: &Gl, path: &Path) -> Result<(u32, i32, i32), glib::Error> {
+        let texture = gdk::Texture::from_filename(path)?;
+        let (width, height) = (texture.width(), texture.height());
+        let mut downloader = gdk::TextureDownloader::new(&texture);
+        downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+        let (bytes, stride) = downloader.download_bytes();
+        let row = width as usize * 4;
+        let pixels: Vec<u8> = bytes
+            .chunks(stride)
+            .take(height as usize)
+            .rev()
+            .flat_map(|line| &line[..row])
+            .copied()
+            .collect();
+        let mut id = 0;
+        unsafe {
+            (gl.gen_textures)(1, &mut id);
+            (gl.bind_texture)(GL_TEXTURE_2D, id);
+            (gl.pixel_storei)(GL_UNPACK_ALIGNMENT, 1);
+            (gl.tex_image_2d)(
+                GL_TEXTURE_2D,
+                0,
+                GL_RGBA8,
+                width,
+                height,
+                0,
+                GL_RGBA,
+                GL_UNSIGNED_BYTE,
+                pixels.as_ptr().cast(),
+            );
+            (gl.generate_mipmap)(GL_TEXTURE_2D);
+            (gl.tex_parameteri)(
+                GL_TEXTURE_2D,
+                GL_TEXTURE_MIN_FILTER,
+                GL_LINEAR_MIPMAP_LINEAR,
+            );
+            (gl.tex_parameteri)(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR);
+            (gl.tex_parameteri)(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_REPEAT);
+            (gl.tex_parameteri)(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_REPEAT);
+            (gl.bind_texture)(GL_TEXTURE_2D, 0);
+        }
+        Ok((id, width, height))
+    }
+
+    /// Where the program takes each uniform; -1 for ones it does not use.
+    #[derive(Clone, Copy, Default)]
+    struct Uniforms {
+        resolution: i32,
+        time: i32,
+        time_delta: i32,
+        frame: i32,
+        mouse: i32,
+        date: i32,
+        channels: [i32; SHADER_CHANNELS],
+        channel_resolutions: [i32; SHADER_CHANNELS],
+    }
+
+    impl Uniforms {
+        fn locate(gl: &Gl, program: u32) -> Self {
+            let locate = |name: &str| {
+                let name = CString::new(name).unwrap();
+                unsafe { (gl.get_uniform_location)(program, name.as_ptr()) }
+            };
+            Uniforms {
+                resolution: locate("iResolution"),
+                time: locate("iTime"),
+                time_delta: locate("iTimeDelta"),
+                frame: locate("iFrame"),
+                mouse: locate("iMouse"),
+                date: locate("iDate"),
+                channels: std::array::from_fn(|i| locate(&format!("iChannel{i}"))),
+                channel_resolutions: std::array::from_fn(|i| {
+                    locate(&format!("iChannelResolution[{i}]"))
+                }),
+            }
+        }
+    }
+
+    #[derive(Properties, Default)]
+    #[properties(wrapper_type = super::ShaderWidget)]
+    pub struct ShaderWidget {
+        #[property(get, set)]
+        filepath: RefCell<String>,
+        #[property(get, name = "gl-area")]
+        gl_area: RefCell<gtk::GLArea>,
+        /// The linked program; 0 before realize or after a failed build.
+        program: Cell<u32>,
+        vertex_array: Cell<u32>,
+        uniforms: Cell<Uniforms>,
+        /// Each bound channel's texture and size.
+        channels: Cell<[Option<(u32, i32, i32)>; SHADER_CHANNELS]>,
+        tick_id: RefCell<Option<gtk::TickCallbackId>>,
+        paused: Cell<bool>,
+        /// Frame-clock time (µs) of the last scheduled render, for FPS capping.
+        last_render_us: Cell<i64>,
+        /// Runtime cap from the power policy; `Some(0)` holds the frame.
+        fps_limit: Cell<Option<u32>>,
+        /// Shader time (`iTime`) in seconds, which stands still while held.
+        time: Cell<f64>,
+        /// Frame-clock time (µs) of the last frame drawn while running.
+        last_frame_us: Cell<Option<i64>>,
+        /// Frames drawn while running (`iFrame`).
+        frame: Cell<i32>,
+        /// Pointer position in framebuffer pixels, origin bottom-left.
+        mouse: Cell<(f32, f32)>,
+        /// Whether `first-frame` was emitted.
+        first_frame: Cell<bool>,
+    }
+
+    impl ShaderWidget {
+        pub(super) fn set_paused(&self, paused: bool) {
+            self.paused.set(paused);
+            self.sync_held();
+        }
+
+        pub(super) fn set_fps_limit(&self, limit: Option<u32>) {
+            self.fps_limit.set(limit);
+            self.sync_held();
+        }
+
+        /// Whether the shader should stand still: paused, or held on a
+        /// static frame.
+        fn is_held(&self) -> bool {
+            self.paused.get() || self.fps_limit.get() == Some(0)
+        }
+
+        fn frame_interval_us(&self) -> i64 {
+            1_000_000 / capped_fps(self.fps_limit.get()).max(1)
+        }
+
+        fn sync_held(&self) {
+            // Time resumes where it stopped rather than jumping ahead.
+            self.last_frame_us.set(None);
+            if !self.is_held() {
+                self.gl_area.borrow().queue_render();
+            }
+        }
+
+        fn setup_context(&self, gl_area: &gtk::GLArea) {
+            let filepath = self.filepath.borrow().clone();
+            if filepath.is_empty() {
+                return;
+            }
+            let obj = self.obj();
+            gl_area.make_current();
+            if let Some(e) = gl_area.error() {
+                report_error(
+                    &*obj,
+                    RendererErrorKind::Backend,
+                    format!("GLArea failed to create a GL context: {}", e),
+                );
+                return;
+            }
+            init_gl_resolver();
+            let gl = match load_gl() {
+                Ok(gl) => gl,
+                Err(e) => {
+                    report_error(&*obj, RendererErrorKind::Backend, e);
+                    return;
+                }
+            };
+
+            let path = Path::new(&filepath);
+            let code = match std::fs::read_to_string(path) {
+                Ok(code) => code,
+                Err(e) => {
+                    report_error(
+                        &*obj,
+                        RendererErrorKind::Load,
+                        format!("Failed to read shader {}: {}", filepath, e),
+                    );
+                    return;
+                }
+            };
+            let source = ShaderSource::parse(&code, path.parent().unwrap_or(Path::new("/")));
+            let program = match link(gl, &source.fragment) {
+                Ok(program) => program,
+                Err(log) => {
+                    warn!("Shader {} failed to build:\n{}", filepath, log);
+                    report_error(
+                        &*obj,
+                        RendererErrorKind::Load,
+                        format!("Failed to build shader {}: {}", filepath, log),
+                    );
+                    return;
+                }
+            };
+            info!("Shader loaded: {}", filepath);
+            self.program.set(program);
+            self.uniforms.set(Uniforms::locate(gl, program));
+            let mut vertex_array = 0;
+            unsafe { (gl.gen_vertex_arrays)(1, &mut vertex_array) };
+            self.vertex_array.set(vertex_array);
+
+            let channels = source.channels.map(|file| {
+                let file = file?;
+                load_channel(gl, &file)
+                    .inspect_err(|e| warn!("Shader channel {} not loaded: {}", file.display(), e))
+                    .ok()
+            });
+            self.channels.set(channels);
+
+            // Shaders animate continuously: redraw on frame clock ticks
+            // while running, capped at the same FPS limit as scenes.
+            let tick_id = gl_area.add_tick_callback(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move |gl_area, clock| {
+                    let now = clock.frame_time();
+                    if !imp.is_held() && now - imp.last_render_us.get() >= imp.frame_interval_us() {
+                        imp.last_render_us.set(now);
+                        gl_area.queue_render();
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ));
+            if let Some(old_tick) = self.tick_id.replace(Some(tick_id)) {
+                old_tick.remove();
+            }
+        }
+
+        fn render(&self, gl_area: &gtk::GLArea) {
+            let program = self.program.get();
+            let Some(gl) = gl() else {
+                return;
+            };
+            if program == 0 {
+                return;
+            }
+            let scale = gl_area.scale_factor();
+            let width = (gl_area.width() * scale).max(1);
+            let height = (gl_area.height() * scale).max(1);
+
+            let now = gl_area
+                .frame_clock()
+                .map(|clock| clock.frame_time())
+                .unwrap_or_default();
+            let mut delta = 0.0;
+            if !self.is_held() {
+                if let Some(last) = self.last_frame_us.get() {
+                    delta = (now - last).max(0) as f64 / 1_000_000.0;
+                }
+                self.last_frame_us.set(Some(now));
+                self.time.set(self.time.get() + delta);
+            }
+            let date = glib::DateTime::now_local().ok();
+
+            let uniforms = self.uniforms.get();
+            let (mouse_x, mouse_y) = self.mouse.get();
+            unsafe {
+                (gl.viewport)(0, 0, width, height);
+                (gl.use_program)(program);
+                (gl.uniform3f)(uniforms.resolution, width as f32, height as f32, 1.0);
+                (gl.uniform1f)(uniforms.time, self.time.get() as f32);
+                (gl.uniform1f)(uniforms.time_delta, delta as f32);
+                (gl.uniform1i)(uniforms.frame, self.frame.get());
+                (gl.uniform4f)(uniforms.mouse, mouse_x, mouse_y, 0.0, 0.0);
+                if let Some(date) = date {
+                    let seconds = date.hour() * 3600 + date.minute() * 60;
+                    (gl.uniform4f)(
+                        uniforms.date,
+                        date.year() as f32,
+                        // Shadertoy counts months from 0.
+                        (date.month() - 1) as f32,
+                        date.day_of_month() as f32,
+                        seconds as f32 + date.seconds() as f32,
+                    );
+                }
+                for (index, channel) in self.channels.get().into_iter().enumerate() {
+                    let Some((texture, width, height)) = channel else {
+                        continue;
+                    };
+                    (gl.active_texture)(GL_TEXTURE0 + index as u32);
+                    (gl.bind_texture)(GL_TEXTURE_2D, texture);
+                    (gl.uniform1i)(uniforms.channels[index], index as i32);
+                    (gl.uniform3f)(
+                        uniforms.channel_resolutions[index],
+                        width as f32,
+                        height as f32,
+                        1.0,
+                    );
+                }
+                (gl.bind_vertex_array)(self.vertex_array.get());
+                (gl.draw_arrays)(GL_TRIANGLES, 0, 3);
+                (gl.bind_vertex_array)(0);
+                (gl.active_texture)(GL_TEXTURE0);
+            }
+            if !self.is_held() {
+                self.frame.set(self.frame.get().wrapping_add(1));
+            }
+            report_first_frame(&*self.obj(), &self.first_frame);
+        }
+
+        fn feed_mouse(&self, x: f64, y: f64) {
+            let gl_area = self.gl_area.borrow();
+            let scale = gl_area.scale_factor() as f64;
+            // Shadertoy's origin is the bottom-left corner.
+            let y = gl_area.height() as f64 - y;
+            self.mouse.set(((x * scale) as f32, (y * scale) as f32));
+        }
+
+        fn teardown_context(&self, gl_area: &gtk::GLArea) {
+            if let Some(tick_id) = self.tick_id.take() {
+                tick_id.remove();
+            }
+            let program = self.program.replace(0);
+            let vertex_array = self.vertex_array.replace(0);
+            let channels = self.channels.take();
+            let Some(gl) = gl() else {
+                return;
+            };
+            // Freeing GL resources needs the context current.
+            gl_area.make_current();
+            unsafe {
+                if program != 0 {
+                    (gl.delete_program)(program);
+                }
+                if vertex_array != 0 {
+                    (gl.delete_vertex_arrays)(1, &vertex_array);
+                }
+                for (texture, _, _) in channels.into_iter().flatten() {
+                    (gl.delete_textures)(1, &texture);
+                }
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ShaderWidget {
+        const NAME: &'static str = "ShaderWidget";
+        type Type = super::ShaderWidget;
+        type ParentType = gtk::Box;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for ShaderWidget {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            info!("Using a GLArea for shader rendering");
+            let obj = self.obj();
+
+            let gl_area = gtk::GLArea::builder().hexpand(true).vexpand(true).build();
+            // The prelude is GLSL 3.30 core; never let GDK pick GLES.
+            gl_area.set_allowed_apis(gdk::GLAPI::GL);
+            obj.append(&gl_area);
+
+            gl_area.connect_realize(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |gl_area| imp.setup_context(gl_area)
+            ));
+            gl_area.connect_unrealize(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |gl_area| imp.teardown_context(gl_area)
+            ));
+            gl_area.connect_render(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |gl_area, _context| {
+                    imp.render(gl_area);
+                    glib::Propagation::Stop
+                }
+            ));
+
+            // `iMouse` follows the pointer.
+            let motion = gtk::EventControllerMotion::new();
+            motion.connect_motion(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, x, y| imp.feed_mouse(x, y)
+            ));
+            gl_area.add_controller(motion);
+
+            self.gl_area.replace(gl_area);
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
+    }
+
+    impl WidgetImpl for ShaderWidget {}
+
+    impl BoxImpl for ShaderWidget {}
+}
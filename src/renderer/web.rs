@@ -15,10 +15,18 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use glib::Object;
-use gtk::{gio, glib, prelude::*};
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
+
+use crate::model::{user_property, WpeProperty};
+use crate::wpe::WpePackage;
 
-use super::{mirror_by_snapshot, RendererWidget};
+use super::{
+    error_signal, first_frame_signal, mirror_by_snapshot, report_error, report_first_frame,
+    RendererErrorKind, RendererWidget,
+};
 
 glib::wrapper! {
     pub struct WebWidget(ObjectSubclass<imp::WebWidget>)
@@ -51,6 +59,58 @@ impl WebWidget {
             .property("sandbox-path", package_dir)
             .build()
     }
+
+    /// The user properties of this Wallpaper Engine package, valued as the
+    /// page last got them.
+    pub fn wpe_user_properties(&self) -> Result<Vec<WpeProperty>, String> {
+        Ok(self.wpe_package()?.user_properties(&self.wpe_values()))
+    }
+
+    /// Change one user property: remembered for page reloads and handed
+    /// to the page's `applyUserProperties` now.
+    pub fn set_wpe_property(&self, name: &str, value: serde_json::Value) -> Result<(), String> {
+        let package = self.wpe_package()?;
+        let known = package
+            .user_properties(&BTreeMap::new())
+            .iter()
+            .any(|property| property.name == name);
+        if !known {
+            return Err(format!(
+                "{} has no property {}",
+                package.dir.display(),
+                name
+            ));
+        }
+        let mut values = self.wpe_values();
+        values.insert(name.to_string(), value.clone());
+        self.set_wpe_properties(package.user_properties_json(&values));
+        let changed = serde_json::json!({ name: user_property(&value) });
+        self.imp().run_listener(&format!(
+            "if(l.applyUserProperties)l.applyUserProperties({changed});"
+        ));
+        Ok(())
+    }
+
+    /// The package this Wallpaper Engine wallpaper was loaded from.
+    fn wpe_package(&self) -> Result<WpePackage, String> {
+        if self.wpe_properties().is_empty() {
+            return Err(format!(
+                "{} is not a Wallpaper Engine wallpaper",
+                self.uri()
+            ));
+        }
+        WpePackage::from_dir(self.sandbox_path().into()).map_err(|e| format!("{:#}", e))
+    }
+
+    /// Property values by name, from `wpe-properties`.
+    fn wpe_values(&self) -> BTreeMap<String, serde_json::Value> {
+        let properties: BTreeMap<String, serde_json::Value> =
+            serde_json::from_str(&self.wpe_properties()).unwrap_or_default();
+        properties
+            .into_iter()
+            .filter_map(|(name, entry)| Some((name, entry.get("value")?.clone())))
+            .collect()
+    }
 }
 
 /// The absolute parent directory of `filepath`, for sandbox grants.
@@ -67,29 +127,74 @@ impl RendererWidget for WebWidget {
         mirror_by_snapshot(&self.webview(), enable_graphics_offload, content_fit)
     }
 
-    fn play(&self) {}
+    fn play(&self) {
+        // Only Wallpaper Engine wallpapers listen for the spectrum and
+        // media events.
+        let imp = self.imp();
+        if self.wpe_properties().is_empty() || imp.audio.borrow().is_some() {
+            return;
+        }
+        let obj = self.downgrade();
+        imp.audio
+            .replace(Some(crate::audio::subscribe(move |spectrum| {
+                if let Some(obj) = obj.upgrade() {
+                    obj.imp().set_audio_samples(spectrum);
+                }
+            })));
+        let obj = self.downgrade();
+        imp.media
+            .replace(Some(crate::mpris::subscribe(move |kind, event| {
+                if let Some(obj) = obj.upgrade() {
+                    obj.imp().send_media_event(kind, event);
+                }
+            })));
+    }
 
-    fn pause(&self) {}
+    fn pause(&self) {
+        let imp = self.imp();
+        imp.media.take();
+        if imp.audio.take().is_some() {
+            imp.set_audio_samples(&[0.0; 2 * crate::audio::SPECTRUM_BANDS]);
+        }
+    }
 
-    fn stop(&self) {}
+    fn stop(&self) {
+        self.pause();
+    }
 
     fn set_volume(&self, _volume: i32) {}
 
     fn set_mute(&self, _mute: bool) {}
 
     fn set_content_fit(&self, _fit: gtk::ContentFit) {}
+
+    fn set_fps_limit(&self, limit: Option<u32>) {
+        // A page can't be frozen from outside; Wallpaper Engine web
+        // wallpapers at least honour the fps general property.
+        let imp = self.imp();
+        imp.fps_limit.set(limit);
+        if !self.wpe_properties().is_empty() {
+            imp.run_listener(&format!(
+                "if(l.applyGeneralProperties)l.applyGeneralProperties({{fps:{}}});",
+                imp.fps()
+            ));
+        }
+    }
 }
 
 mod imp {
     use super::*;
 
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
 
+    use glib::subclass::Signal;
     use glib::Properties;
-    use gtk::subclass::prelude::*;
     use tracing::debug;
     use webkit::{prelude::*, WebView};
 
+    use crate::model::MediaEventKind;
+
     #[derive(Properties, Default)]
     #[properties(wrapper_type = super::WebWidget)]
     pub struct WebWidget {
@@ -111,6 +216,14 @@ mod imp {
         sandbox_path: RefCell<String>,
         #[property(get)]
         webview: RefCell<WebView>,
+        /// Runtime cap from the power policy, announced to WPE wallpapers.
+        pub(super) fps_limit: Cell<Option<u32>>,
+        /// Whether `first-frame` was emitted.
+        first_frame: Cell<bool>,
+        /// Spectrum feed for `wallpaperRegisterAudioListener`, while playing.
+        pub(super) audio: RefCell<Option<crate::audio::AudioSubscription>>,
+        /// Now-playing feed for the media listeners, while playing.
+        pub(super) media: RefCell<Option<crate::mpris::MediaSubscription>>,
     }
 
     /// Minimal Wallpaper Engine JS API, injected at document-start so web
@@ -121,22 +234,52 @@ mod imp {
   if (window.__hotaruWpeStub) return;
   window.__hotaruWpeStub = true;
   var noop = function () {};
-  // Feed a zeroed 128-sample spectrum (64 L + 64 R) so audio-reactive
-  // wallpapers run (flat, not reactive — hotaru has no spectrum feed).
+  // Feed every audio listener the 128-sample spectrum (64 L + 64 R) at
+  // ~30 Hz. Rust replaces __hotaruAudioSamples with captured levels while
+  // the wallpaper plays; it stays silent otherwise.
+  window.__hotaruAudioSamples = new Array(128).fill(0);
+  var audioListeners = [];
   window.wallpaperRegisterAudioListener = function (cb) {
     if (typeof cb !== 'function') return;
-    var silent = new Array(128).fill(0);
-    if (window.__hotaruAudioTimer) clearInterval(window.__hotaruAudioTimer);
+    audioListeners.push(cb);
+    if (window.__hotaruAudioTimer) return;
     window.__hotaruAudioTimer = setInterval(function () {
-      try { cb(silent); } catch (e) {}
+      audioListeners.forEach(function (l) {
+        try { l(window.__hotaruAudioSamples); } catch (e) {}
+      });
     }, 33);
   };
   window.wallpaperRequestRandomFileForProperty = noop;
-  window.wallpaperRegisterMediaStatusListener = noop;
-  window.wallpaperRegisterMediaPropertiesListener = noop;
-  window.wallpaperRegisterMediaThumbnailListener = noop;
-  window.wallpaperRegisterMediaTimelineListener = noop;
-  window.wallpaperRegisterMediaPlaybackListener = noop;
+  // Media integration: Rust hands now-playing events to __hotaruMedia by
+  // listener kind. A listener registering late gets its kind's last event
+  // at once, as the page may load after playback started.
+  window.wallpaperMediaIntegration = {
+    PLAYBACK_STOPPED: 0,
+    PLAYBACK_PLAYING: 1,
+    PLAYBACK_PAUSED: 2
+  };
+  var media = {};
+  var registerMedia = function (kind) {
+    media[kind] = { listeners: [], last: null };
+    return function (cb) {
+      if (typeof cb !== 'function') return;
+      media[kind].listeners.push(cb);
+      if (media[kind].last !== null) {
+        try { cb(media[kind].last); } catch (e) {}
+      }
+    };
+  };
+  window.wallpaperRegisterMediaStatusListener = registerMedia('status');
+  window.wallpaperRegisterMediaPropertiesListener = registerMedia('properties');
+  window.wallpaperRegisterMediaThumbnailListener = registerMedia('thumbnail');
+  window.wallpaperRegisterMediaTimelineListener = registerMedia('timeline');
+  window.wallpaperRegisterMediaPlaybackListener = registerMedia('playback');
+  window.__hotaruMedia = function (kind, event) {
+    media[kind].last = event;
+    media[kind].listeners.forEach(function (l) {
+      try { l(event); } catch (e) {}
+    });
+  };
 })();
 "#;
 
@@ -145,6 +288,52 @@ mod imp {
             debug!("start {}", self.uri.borrow());
             self.webview.borrow().load_uri(&self.uri.borrow());
         }
+
+        /// The fps announced to the wallpaper (a held frame still gets 1).
+        pub(super) fn fps(&self) -> i64 {
+            crate::wpe::capped_fps(self.fps_limit.get()).max(1)
+        }
+
+        /// Hand the page the spectrum its audio listeners receive next.
+        pub(super) fn set_audio_samples(&self, spectrum: &crate::audio::Spectrum) {
+            let samples: Vec<String> = spectrum.iter().map(|v| format!("{v:.3}")).collect();
+            let js = format!("window.__hotaruAudioSamples=[{}];", samples.join(","));
+            self.webview.borrow().evaluate_javascript(
+                &js,
+                None,
+                None,
+                gio::Cancellable::NONE,
+                |_result| {},
+            );
+        }
+
+        /// Hand `event` to the page's media listeners of `kind`.
+        pub(super) fn send_media_event(&self, kind: MediaEventKind, event: &serde_json::Value) {
+            let kind: &str = kind.into();
+            let js = format!("window.__hotaruMedia('{kind}',{event});");
+            self.webview.borrow().evaluate_javascript(
+                &js,
+                None,
+                None,
+                gio::Cancellable::NONE,
+                |_result| {},
+            );
+        }
+
+        /// Run `body` against the page's `wallpaperPropertyListener`
+        /// (bound to `l`), if it registered one.
+        pub(super) fn run_listener(&self, body: &str) {
+            let js = format!(
+                "(function(){{var l=window.wallpaperPropertyListener;if(!l)return;{body}}})();"
+            );
+            self.webview.borrow().evaluate_javascript(
+                &js,
+                None,
+                None,
+                gio::Cancellable::NONE,
+                |_result| {},
+            );
+        }
     }
 
     #[glib::object_subclass]
@@ -222,27 +411,48 @@ mod imp {
             webview.connect_load_changed(glib::clone!(
                 #[weak(rename_to = imp)]
                 self,
-                move |webview, event| {
+                move |_webview, event| {
                     if event != webkit::LoadEvent::Finished {
                         return;
                     }
-                    let props = imp.wpe_properties.borrow();
+                    report_first_frame(&*imp.obj(), &imp.first_frame);
+                    let props = imp.wpe_properties.borrow().clone();
                     if props.is_empty() {
                         return;
                     }
-                    let js = format!(
-                        "(function(){{var l=window.wallpaperPropertyListener;if(!l)return;\
-                         if(l.applyGeneralProperties)l.applyGeneralProperties({{fps:{fps}}});\
-                         if(l.applyUserProperties)l.applyUserProperties({props});}})();",
-                        fps = crate::wpe::fps_limit(),
-                        props = &*props
-                    );
-                    webview.evaluate_javascript(
-                        &js,
-                        None,
-                        None,
-                        gio::Cancellable::NONE,
-                        |_result| {},
+                    imp.run_listener(&format!(
+                        "if(l.applyGeneralProperties)l.applyGeneralProperties({{fps:{fps}}});\
+                         if(l.applyUserProperties)l.applyUserProperties({props});",
+                        fps = imp.fps(),
+                    ));
+                }
+            ));
+
+            webview.connect_load_failed(glib::clone!(
+                #[weak]
+                obj,
+                #[upgrade_or]
+                false,
+                move |_webview, _event, uri, error| {
+                    // Replacing a page mid-load cancels the old load.
+                    if !error.matches(webkit::NetworkError::Cancelled) {
+                        report_error(
+                            &obj,
+                            RendererErrorKind::Load,
+                            format!("Failed to load {}: {}", uri, error),
+                        );
+                    }
+                    false
+                }
+            ));
+            webview.connect_web_process_terminated(glib::clone!(
+                #[weak]
+                obj,
+                move |_webview, reason| {
+                    report_error(
+                        &obj,
+                        RendererErrorKind::Backend,
+                        format!("Web process for {} terminated: {:?}", obj.uri(), reason),
                     );
                 }
             ));
@@ -257,6 +467,11 @@ mod imp {
 
             self.webview.replace(webview);
         }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![error_signal(), first_frame_signal()])
+        }
     }
 
     impl WidgetImpl for WebWidget {}
@@ -0,0 +1,144 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Drives a config's `schedule` on the GLib main loop: tracks which slot
+//! is active and reports when a boundary changes it. The time math lives
+//! in `model::schedule`; this module only reads the clock and sets timers.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk::glib;
+use tracing::{debug, warn};
+
+use crate::model::{LocalTime, Schedule};
+
+/// Upper bound on a single wait. GLib timers run on the monotonic clock,
+/// which stops during suspend, so a long timer would fire late after a
+/// resume; re-checking every minute keeps slot changes on time.
+const MAX_WAIT_SECONDS: u32 = 60;
+
+pub struct Scheduler {
+    inner: Rc<SchedulerInner>,
+}
+
+struct SchedulerInner {
+    schedule: RefCell<Option<Schedule>>,
+    active: Cell<Option<usize>>,
+    timer: RefCell<Option<glib::SourceId>>,
+    on_change: Box<dyn Fn(Option<usize>)>,
+}
+
+impl Scheduler {
+    /// `on_change` runs on the main loop whenever a boundary switches the
+    /// active slot (not for the initial evaluation in [`start`](Self::start)).
+    pub fn new(on_change: impl Fn(Option<usize>) + 'static) -> Self {
+        Self {
+            inner: Rc::new(SchedulerInner {
+                schedule: RefCell::new(None),
+                active: Cell::new(None),
+                timer: RefCell::new(None),
+                on_change: Box::new(on_change),
+            }),
+        }
+    }
+
+    /// Follow `schedule`, replacing any previous one. Returns the slot
+    /// active right now.
+    pub fn start(&self, schedule: &Schedule) -> Option<usize> {
+        self.inner.schedule.replace(Some(schedule.clone()));
+        SchedulerInner::update(&self.inner, false);
+        self.inner.active.get()
+    }
+
+    pub fn stop(&self) {
+        self.inner.cancel_timer();
+        self.inner.schedule.replace(None);
+        self.inner.active.set(None);
+    }
+
+    /// Index of the active slot, `None` outside every slot or when no
+    /// schedule is running.
+    pub fn active(&self) -> Option<usize> {
+        self.inner.active.get()
+    }
+
+    /// Name of the active slot; empty when none is.
+    pub fn active_name(&self) -> String {
+        let schedule = self.inner.schedule.borrow();
+        self.active()
+            .and_then(|slot| schedule.as_ref()?.slots.get(slot))
+            .map(|slot| slot.name.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.inner.cancel_timer();
+    }
+}
+
+impl SchedulerInner {
+    fn cancel_timer(&self) {
+        if let Some(timer) = self.timer.take() {
+            timer.remove();
+        }
+    }
+
+    /// Re-evaluate the active slot and arm the timer for the next boundary.
+    fn update(this: &Rc<Self>, notify: bool) {
+        this.cancel_timer();
+        let Some(schedule) = this.schedule.borrow().clone() else {
+            return;
+        };
+
+        let wait = match glib::DateTime::now_local() {
+            Ok(now) => {
+                let local = LocalTime {
+                    day_of_year: now.day_of_year() as u32,
+                    minute_of_day: (now.hour() * 60 + now.minute()) as u32,
+                    utc_offset: now.utc_offset().as_minutes() as i32,
+                };
+                let active = schedule.active_slot(local);
+                if this.active.replace(active) != active {
+                    debug!("Schedule slot changed to {:?}", active);
+                    if notify {
+                        (this.on_change)(active);
+                    }
+                }
+                // Land just past the boundary minute, not on its edge.
+                let until = schedule.minutes_until_boundary(local) * 60;
+                until.saturating_sub(now.second() as u32) + 1
+            }
+            Err(e) => {
+                warn!("Failed to read the local time: {}", e);
+                MAX_WAIT_SECONDS
+            }
+        };
+
+        let weak = Rc::downgrade(this);
+        let timer = glib::timeout_add_seconds_local_once(wait.min(MAX_WAIT_SECONDS), move || {
+            if let Some(this) = weak.upgrade() {
+                // Fired sources are gone; don't remove it again.
+                this.timer.take();
+                Self::update(&this, true);
+            }
+        });
+        this.timer.replace(Some(timer));
+    }
+}
@@ -24,8 +24,11 @@ use gtk::prelude::*;
 use tracing::{info, warn};
 
 use crate::constants::APPLICATION_ID;
-use crate::model::VideoRenderer;
-use crate::renderer::{Renderer, RendererWidget};
+use crate::model::{
+    ContentFit, PowerAction, PowerPolicy, RenderOverrides, Transition, TransitionKind,
+    VideoRenderer,
+};
+use crate::renderer::{Renderer, RendererMap, RendererWidget};
 
 /// A point-in-time snapshot of the settings a renderer build needs.
 #[derive(Debug, Clone, Copy)]
@@ -36,6 +39,21 @@ pub struct RenderSettings {
     /// Audio volume (0-100).
     pub volume: i32,
     pub mute: bool,
+    pub power_policy: PowerPolicy,
+    pub transition: Transition,
+}
+
+impl RenderSettings {
+    /// These settings as one monitor entry's overrides leave them.
+    pub fn with_overrides(&self, overrides: &RenderOverrides) -> RenderSettings {
+        RenderSettings {
+            video_renderer: overrides.video_renderer.unwrap_or(self.video_renderer),
+            content_fit: overrides.content_fit.map_or(self.content_fit, Into::into),
+            volume: overrides.volume.unwrap_or(self.volume),
+            mute: overrides.mute.unwrap_or(self.mute),
+            ..*self
+        }
+    }
 }
 
 /// Watches the Hotaru GSettings schema and applies changes to active renderers.
@@ -67,6 +85,8 @@ impl SettingsWatcher {
             content_fit: self.content_fit(),
             volume: self.volume(),
             mute: self.is_mute(),
+            power_policy: self.power_policy(),
+            transition: self.transition(),
         }
     }
 
@@ -95,17 +115,39 @@ impl SettingsWatcher {
         content_fit_from_int(self.settings.int("content-fit"))
     }
 
+    /// The battery/power-saver policy table.
+    pub fn power_policy(&self) -> PowerPolicy {
+        let action = |key: &str| {
+            let value = self.settings.string(key);
+            PowerAction::from_str(&value).unwrap_or_else(|_| {
+                warn!("Unknown {} setting: {}, ignoring", key, value);
+                PowerAction::None
+            })
+        };
+        PowerPolicy {
+            on_battery: action("on-battery-action"),
+            power_saver: action("power-saver-action"),
+            reduced_fps: self.settings.int("reduced-fps").max(1) as u32,
+        }
+    }
+
+    /// How a new wallpaper replaces the one on screen.
+    pub fn transition(&self) -> Transition {
+        transition_from_settings(&self.settings)
+    }
+
     /// Connect GSettings change signals to update active renderers at runtime.
     /// The `renderers` Rc is shared with application state and updated when
-    /// wallpapers are applied or disabled.
-    pub fn connect_runtime_settings(&self, renderers: Rc<RefCell<Vec<Renderer>>>) {
+    /// wallpapers are applied or disabled. Renderers and mirrors whose
+    /// monitor entry overrides a setting keep their own value.
+    pub fn connect_runtime_settings(&self, renderers: Rc<RefCell<RendererMap>>) {
         let renderers_clone = renderers.clone();
         self.settings
             .connect_changed(Some("volume"), move |settings, _key| {
                 let volume = settings.int("volume");
                 info!("Volume changed to: {}%", volume);
-                for renderer in renderers_clone.borrow().iter() {
-                    renderer.set_volume(volume);
+                for active in renderers_clone.borrow().values() {
+                    active.renderer.set_volume(active.volume(volume));
                 }
             });
 
@@ -114,8 +156,8 @@ impl SettingsWatcher {
             .connect_changed(Some("mute"), move |settings, _key| {
                 let mute = settings.boolean("mute");
                 info!("Mute changed to: {}", mute);
-                for renderer in renderers_clone.borrow().iter() {
-                    renderer.set_mute(mute);
+                for active in renderers_clone.borrow().values() {
+                    active.renderer.set_mute(active.mute(mute));
                 }
             });
 
@@ -124,10 +166,26 @@ impl SettingsWatcher {
             .connect_changed(Some("content-fit"), move |settings, _key| {
                 let fit = content_fit_from_int(settings.int("content-fit"));
                 info!("Content fit changed to: {:?}", fit);
-                for renderer in renderers_clone.borrow().iter() {
-                    renderer.set_content_fit(fit);
+                for active in renderers_clone.borrow().values() {
+                    active.set_content_fit(fit);
                 }
             });
+
+        // Windows pick the transition up on their next swap; playlists swap
+        // on their own, so they are told now.
+        for key in ["transition", "transition-duration"] {
+            let renderers_clone = renderers.clone();
+            self.settings
+                .connect_changed(Some(key), move |settings, _key| {
+                    let transition = transition_from_settings(settings);
+                    info!("Transition changed to: {:?}", transition);
+                    for active in renderers_clone.borrow().values() {
+                        if let Renderer::Playlist(playlist) = &active.renderer {
+                            playlist.set_transition(transition);
+                        }
+                    }
+                });
+        }
     }
 
     // --- Last applied wallpaper persistence ---
@@ -158,3 +216,25 @@ fn content_fit_from_int(value: i32) -> gtk::ContentFit {
         _ => gtk::ContentFit::Cover,
     }
 }
+
+fn transition_from_settings(settings: &gio::Settings) -> Transition {
+    let value = settings.string("transition");
+    let kind = TransitionKind::from_str(&value).unwrap_or_else(|_| {
+        warn!("Unknown transition setting: {}, using default", value);
+        TransitionKind::default()
+    });
+    Transition {
+        kind,
+        duration_ms: settings.int("transition-duration").max(0) as u32,
+    }
+}
+
+impl From<ContentFit> for gtk::ContentFit {
+    fn from(fit: ContentFit) -> Self {
+        match fit {
+            ContentFit::Fill => gtk::ContentFit::Fill,
+            ContentFit::Contain => gtk::ContentFit::Contain,
+            ContentFit::Cover => gtk::ContentFit::Cover,
+        }
+    }
+}
@@ -15,10 +15,11 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-//! Active-wallpaper state and the single rebuild path, shared by
+//! Active-wallpaper state and the rebuild and reconcile paths, shared by
 //! standalone mode and the D-Bus daemon. Lives on the GLib main thread.
 
 use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 use std::str::FromStr as _;
 
@@ -28,12 +29,19 @@ use gtk::prelude::*;
 use tracing::{debug, info};
 
 use crate::application::HotaruApplication;
-use crate::model::{LaunchMode, MonitorListModelExt as _, WallpaperConfig};
+use crate::model::{
+    Diagnostic, LaunchMode, LayoutDiff, MonitorListModelExt as _, MonitorMap, PowerAction,
+    WallpaperConfig, WindowLayout, WindowRole,
+};
 use crate::monitor_watcher::MonitorWatcher;
-use crate::renderer::{Renderer, RendererWidget};
-use crate::settings_watcher::SettingsWatcher;
+use crate::policy::{PauseReason, Policy};
+use crate::renderer::{ActiveRenderer, Renderer, RendererError, RendererMap, RendererWidget};
+use crate::scheduler::Scheduler;
+use crate::settings_watcher::{RenderSettings, SettingsWatcher};
+use crate::window::HotaruApplicationWindow;
 
-/// The lowercase string form (strum) is the D-Bus `State` property value.
+/// Playback as requested by the user. The lowercase string form (strum) is
+/// the D-Bus `State` property value (see [`RendererState::effective_state`]).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
 #[strum(serialize_all = "lowercase")]
 pub enum PlaybackState {
@@ -42,39 +50,136 @@ pub enum PlaybackState {
     Paused,
 }
 
+/// Changes that originate on the main thread rather than from a D-Bus
+/// call, forwarded to the D-Bus thread as `PropertiesChanged` (and
+/// signals).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateEvent {
+    PlaylistIndexChanged,
+    ScheduleSlotChanged,
+    /// The auto-pause policy paused or resumed a monitor.
+    PlaybackChanged,
+    /// A monitor's volume changed.
+    MonitorStatesChanged,
+    /// The wallpaper was rebuilt or disabled, or the monitors changed:
+    /// anything describing the setup may have changed.
+    Rebuilt,
+    /// The renderer showing `connector` failed.
+    RendererError {
+        connector: String,
+        error: RendererError,
+    },
+}
+
+/// One monitor's playback, for the D-Bus `MonitorStates` property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorState {
+    /// `playing` or `paused`.
+    pub state: PlaybackState,
+    pub pause_reason: Option<PauseReason>,
+    /// Volume (0-100) of the renderer it shows.
+    pub volume: i32,
+}
+
+/// What one monitor shows, for the D-Bus `ActiveRenderers` property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RendererInfo {
+    /// See [`Renderer::kind`].
+    pub kind: &'static str,
+    /// The configured wallpaper source, as JSON.
+    pub source: String,
+}
+
 pub struct RendererState {
     pub app: HotaruApplication,
-    pub renderers: Rc<RefCell<Vec<Renderer>>>,
+    pub renderers: Rc<RefCell<RendererMap>>,
     pub config: RefCell<Option<WallpaperConfig>>,
+    /// The windows last built, which a monitor change is diffed against.
+    layout: RefCell<Option<WindowLayout>>,
     pub launch_mode: RefCell<LaunchMode>,
     pub playback_state: RefCell<PlaybackState>,
     pub settings_watcher: SettingsWatcher,
+    /// Follows the config's `schedule`; the active slot selects which
+    /// monitor list gets built.
+    pub scheduler: Scheduler,
+    /// Decides when renderers pause on their own (covered, locked, idle).
+    pub policy: Policy,
+    /// Why each paused monitor (by connector) is paused, as last applied.
+    pause_reasons: RefCell<BTreeMap<String, PauseReason>>,
+    /// Monitors paused with `PauseMonitor`, until `ResumeMonitor` or the
+    /// next apply.
+    paused_monitors: RefCell<BTreeSet<String>>,
+    /// `SetMonitorVolume` overrides by connector. Kept across rebuilds
+    /// and applies for as long as the process runs.
+    monitor_volumes: RefCell<BTreeMap<String, i32>>,
+    /// The latest failure of each monitor's renderer, by connector. Cleared
+    /// whenever the renderers are rebuilt.
+    last_errors: Rc<RefCell<BTreeMap<String, RendererError>>>,
     /// The daemon's main loop. `GApplication::quit()` only stops a loop
     /// started by `app.run()`, which daemon mode never calls, so Quit must
     /// stop this loop explicitly.
     pub main_loop: RefCell<Option<glib::MainLoop>>,
+    /// Set by the D-Bus service; `None` in standalone mode, where nobody
+    /// listens for events.
+    events: RefCell<Option<async_channel::Sender<StateEvent>>>,
 }
 
 impl RendererState {
     pub fn new(app: HotaruApplication) -> Rc<Self> {
-        let renderers = Rc::new(RefCell::new(Vec::new()));
+        let renderers = Rc::new(RefCell::new(RendererMap::new()));
         let settings_watcher = SettingsWatcher::new();
         settings_watcher.connect_runtime_settings(renderers.clone());
 
-        Rc::new(Self {
-            app,
-            renderers,
-            config: RefCell::new(None),
-            launch_mode: RefCell::new(LaunchMode::default()),
-            playback_state: RefCell::new(PlaybackState::Idle),
-            settings_watcher,
-            main_loop: RefCell::new(None),
+        Rc::new_cyclic(|weak: &std::rc::Weak<Self>| {
+            let state = weak.clone();
+            let policy = Policy::new(settings_watcher.settings(), move || {
+                if let Some(state) = state.upgrade() {
+                    state.apply_playback();
+                }
+            });
+            let state = weak.clone();
+            let scheduler = Scheduler::new(move |slot| {
+                let Some(state) = state.upgrade() else {
+                    return;
+                };
+                info!("Schedule slot changed to {:?}, reconciling", slot);
+                state.reconcile();
+                state.send_event(StateEvent::ScheduleSlotChanged);
+            });
+            Self {
+                app,
+                renderers,
+                config: RefCell::new(None),
+                layout: RefCell::new(None),
+                launch_mode: RefCell::new(LaunchMode::default()),
+                playback_state: RefCell::new(PlaybackState::Idle),
+                settings_watcher,
+                scheduler,
+                policy,
+                pause_reasons: RefCell::new(BTreeMap::new()),
+                paused_monitors: RefCell::new(BTreeSet::new()),
+                monitor_volumes: RefCell::new(BTreeMap::new()),
+                last_errors: Rc::new(RefCell::new(BTreeMap::new())),
+                main_loop: RefCell::new(None),
+                events: RefCell::new(None),
+            }
         })
     }
 
-    /// Rebuild the wallpaper whenever the monitors change or the
+    pub fn set_event_sender(&self, sender: async_channel::Sender<StateEvent>) {
+        self.events.replace(Some(sender));
+    }
+
+    fn send_event(&self, event: StateEvent) {
+        if let Some(events) = self.events.borrow().as_ref() {
+            let _ = events.try_send(event);
+        }
+    }
+
+    /// Follow monitor changes and rebuild the wallpaper whenever the
     /// video-renderer setting is switched (no-op while no wallpaper is
-    /// active). Wires both modes' triggers in one place.
+    /// active), and report volume changes to D-Bus clients. Wires both
+    /// modes' triggers in one place.
     pub fn watch_changes(self: &Rc<Self>, monitor_watcher: &MonitorWatcher) {
         let state = self.clone();
         monitor_watcher.connect_closure(
@@ -83,7 +188,12 @@ impl RendererState {
             glib::closure_local!(move |_monitor_tracker: MonitorWatcher, list: ListModel| {
                 let monitor_map = list.monitor_map().unwrap();
                 debug!("monitor changed: {:?}", monitor_map);
-                state.rebuild_ui();
+                state.reconcile();
+                // A reconcile reports itself; without a wallpaper only the
+                // monitor list changed.
+                if state.config.borrow().is_none() {
+                    state.send_event(StateEvent::Rebuilt);
+                }
             }),
         );
 
@@ -91,24 +201,47 @@ impl RendererState {
         self.settings_watcher.settings().connect_changed(
             Some("video-renderer"),
             move |_settings, _key| {
-                info!("Video renderer setting changed, rebuilding");
-                state.rebuild_ui();
+                info!("Video renderer setting changed, replacing renderers");
+                state.refill();
             },
         );
+
+        // Every monitor without an override reports the global volume.
+        let state = self.clone();
+        self.settings_watcher
+            .settings()
+            .connect_changed(Some("volume"), move |_settings, _key| {
+                state.send_event(StateEvent::MonitorStatesChanged);
+            });
     }
 
-    /// Apply a wallpaper config: store it and (re)build the UI. Fails when
-    /// the launch mode needs a different GDK backend than this process runs
-    /// on (the backend is fixed at startup).
+    /// Apply a wallpaper config: store it and reconcile the UI with it, or
+    /// rebuild it when the launch mode changes (windows of another mode
+    /// cannot be reused). Fails when the launch mode needs a different GDK
+    /// backend than this process runs on (the backend is fixed at startup).
     pub fn apply(&self, config: &WallpaperConfig, launch_mode: LaunchMode) -> Result<(), String> {
         crate::application::check_launch_mode_backend(launch_mode)?;
+        let relaunch = *self.launch_mode.borrow() != launch_mode;
 
-        // Update state before build_ui so the monitor-changed handler sees
-        // the correct values.
+        // Update state before build_ui so the monitor-changed handler (and
+        // the playback policy applied after the build) sees the correct
+        // values.
         *self.launch_mode.borrow_mut() = launch_mode;
-        *self.config.borrow_mut() = Some(config.clone());
-        self.rebuild(config, launch_mode);
         *self.playback_state.borrow_mut() = PlaybackState::Playing;
+        self.paused_monitors.borrow_mut().clear();
+        *self.config.borrow_mut() = Some(config.clone());
+        match &config.schedule {
+            Some(schedule) => {
+                let slot = self.scheduler.start(schedule);
+                info!("Schedule slot {:?} is active", slot);
+            }
+            None => self.scheduler.stop(),
+        }
+        if relaunch {
+            self.rebuild(config, launch_mode);
+        } else {
+            self.reconcile();
+        }
         Ok(())
     }
 
@@ -122,8 +255,8 @@ impl RendererState {
         config_json: &str,
         launch_mode_str: &str,
     ) -> Result<bool, String> {
-        let config: WallpaperConfig =
-            serde_json::from_str(config_json).map_err(|e| format!("Invalid config JSON: {}", e))?;
+        let config = WallpaperConfig::from_json(config_json)
+            .map_err(|e| format!("Invalid config JSON: {}", e))?;
         let launch_mode = LaunchMode::from_str(launch_mode_str)
             .map_err(|e| format!("Invalid launch mode: {}", e))?;
 
@@ -132,7 +265,11 @@ impl RendererState {
             config.mode, launch_mode
         );
 
-        self.settings_watcher.set_last_wallpaper_config(config_json);
+        // Persist the migrated config, so an older stored version is
+        // upgraded the first time the daemon restores it.
+        let config_json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+        self.settings_watcher
+            .set_last_wallpaper_config(&config_json);
         self.settings_watcher.set_last_launch_mode(launch_mode_str);
 
         self.apply(&config, launch_mode)?;
@@ -140,6 +277,15 @@ impl RendererState {
         Ok(true)
     }
 
+    /// Check a JSON config against the connected monitors without applying
+    /// it. A config that does not parse is a single error diagnostic.
+    pub fn validate_config(&self, config_json: &str) -> Vec<Diagnostic> {
+        match WallpaperConfig::from_json(config_json) {
+            Ok(config) => config.validate(&self.monitors()),
+            Err(e) => vec![Diagnostic::parse_error(&e)],
+        }
+    }
+
     /// Rebuild the wallpaper UI from the currently stored config, reading
     /// renderer/display settings fresh. No-op when no wallpaper is active.
     pub fn rebuild_ui(&self) {
@@ -150,25 +296,224 @@ impl RendererState {
         self.rebuild(&config, launch_mode);
     }
 
-    /// The single rebuild path: close all windows and rebuild with
-    /// freshly-read settings.
+    /// The full rebuild path: close all windows and rebuild with
+    /// freshly-read settings, from the monitor list of the active schedule
+    /// slot (if any). [`reconcile`](Self::reconcile) falls back to it.
     fn rebuild(&self, config: &WallpaperConfig, launch_mode: LaunchMode) {
         self.app.windows().into_iter().for_each(|w| w.close());
+        self.policy.forget_windows();
+        self.last_errors.borrow_mut().clear();
+
+        let config = config.effective(self.scheduler.active());
+        let settings = self.settings_watcher.snapshot();
+        let layout = self
+            .app
+            .build_ui(&config, &settings, &self.renderers, launch_mode);
+        for window in self.app.windows() {
+            if let Ok(window) = window.downcast::<HotaruApplicationWindow>() {
+                self.policy.watch_window(&window);
+            }
+        }
+        for active in self.renderers.borrow().values() {
+            active.volume_override.set(self.volume_override(active));
+        }
+        // build_ui starts every renderer; hold back the ones the policy
+        // (or a user pause from before the rebuild) says should be paused.
+        self.apply_playback();
 
+        let connectors: Vec<String> = self.renderers.borrow().keys().cloned().collect();
+        for connector in &connectors {
+            self.watch_renderer(connector);
+        }
+        self.apply_settings_when_idle(connectors, settings);
+        self.layout.replace(Some(layout));
+        self.send_event(StateEvent::Rebuilt);
+    }
+
+    /// Bring the windows in line with the stored config, the active
+    /// schedule slot and the connected monitors without a full rebuild:
+    /// diff the new layout against the one last built and only close, move
+    /// or refill the windows that changed, so wallpapers on untouched
+    /// monitors keep playing. A window whose monitor stays gets its new
+    /// content swapped in (see `build_windows`). No-op when no wallpaper is
+    /// active.
+    fn reconcile(&self) {
+        self.update_windows(WindowLayout::diff);
+    }
+
+    /// Give every window new renderers built with freshly-read settings,
+    /// keeping the windows themselves: the old content transitions out
+    /// like on any other wallpaper change.
+    fn refill(&self) {
+        self.update_windows(WindowLayout::replace_all);
+    }
+
+    /// Reconcile the windows with the changes `diff` finds between the
+    /// layout last built and the current one.
+    fn update_windows(&self, diff: fn(&WindowLayout, &WindowLayout) -> LayoutDiff) {
+        let Some(config) = self.config.borrow().clone() else {
+            return;
+        };
+        // Without a layout to diff against there is nothing to keep.
+        let old = self.layout.borrow().clone();
+        let Some(old) = old else {
+            self.rebuild_ui();
+            return;
+        };
+        let config = config.effective(self.scheduler.active());
         let settings = self.settings_watcher.snapshot();
-        self.app
-            .build_ui(config, &settings, &self.renderers, launch_mode);
+        let new = crate::application::current_layout(&config);
+        let diff = diff(&old, &new);
+        info!(
+            "Reconciling windows: {} kept, {} moved, {} removed, {} added",
+            diff.kept.len(),
+            diff.moved.len(),
+            diff.removed.len(),
+            diff.added.len()
+        );
+
+        let refilled: BTreeSet<&str> = diff
+            .added
+            .iter()
+            .map(|info| info.monitor.as_str())
+            .filter(|monitor| diff.removed.iter().any(|removed| removed == monitor))
+            .collect();
+        let mut renderers = self.renderers.take();
+        let mut retired_renderers = RendererMap::new();
+        for connector in &diff.removed {
+            let retired = renderers.remove(connector);
+            for active in renderers.values_mut() {
+                active.outputs.retain(|output| output != connector);
+                active.mirrors.retain(|mirror| mirror.monitor != *connector);
+            }
+            self.last_errors.borrow_mut().remove(connector);
+            if refilled.contains(connector.as_str()) {
+                // Still on screen until the new content replaces it, but
+                // no longer heard; stopped once it is gone.
+                if let Some(active) = retired {
+                    active.renderer.set_mute(true);
+                    retired_renderers.insert(connector.clone(), active);
+                }
+                continue;
+            }
+            if let Some(active) = retired {
+                active.renderer.stop();
+            }
+            if let Some(window) = self.app.window_on(connector) {
+                window.close();
+            }
+            self.policy.forget_window(connector);
+        }
+        for info in &diff.moved {
+            if let Some(window) = self.app.window_on(&info.monitor) {
+                crate::application::place_window(&window, info);
+            }
+        }
+        let launch_mode = *self.launch_mode.borrow();
+        self.app.build_windows(
+            &diff.added,
+            &settings,
+            &mut renderers,
+            retired_renderers,
+            launch_mode,
+        );
+        self.renderers.replace(renderers);
+        for info in &diff.added {
+            if refilled.contains(info.monitor.as_str()) {
+                continue;
+            }
+            if let Some(window) = self.app.window_on(&info.monitor) {
+                self.policy.watch_window(&window);
+            }
+        }
 
+        let added: Vec<String> = diff
+            .added
+            .iter()
+            .filter(|info| matches!(info.role, WindowRole::Primary { .. }))
+            .map(|info| info.monitor.clone())
+            .collect();
+        // Clones that came or went can change which monitor's volume
+        // applies to a renderer that kept playing.
+        for (connector, active) in self.renderers.borrow().iter() {
+            let volume = self.volume_override(active);
+            if active.volume_override.replace(volume) != volume && !added.contains(connector) {
+                active.renderer.set_volume(active.volume(settings.volume));
+            }
+        }
+        self.apply_playback();
+
+        for connector in &added {
+            self.watch_renderer(connector);
+        }
+        self.apply_settings_when_idle(added, settings);
+        self.layout.replace(Some(new));
+        self.send_event(StateEvent::Rebuilt);
+    }
+
+    /// Report the failures of the renderer on `connector` against every
+    /// monitor showing it at the time, and (for a playlist) its steps,
+    /// to D-Bus clients.
+    fn watch_renderer(&self, connector: &str) {
+        let renderers = self.renderers.borrow();
+        let Some(active) = renderers.get(connector) else {
+            return;
+        };
+
+        // Playlists advance on their own timers; tell D-Bus clients.
+        if let Renderer::Playlist(playlist) = &active.renderer {
+            let events = self.events.borrow().clone();
+            playlist.connect_position_notify(move |_| {
+                if let Some(events) = &events {
+                    let _ = events.try_send(StateEvent::PlaylistIndexChanged);
+                }
+            });
+        }
+
+        // Renderer failures count against every monitor showing them,
+        // which clones coming and going on hotplug can change.
+        let weak = Rc::downgrade(&self.renderers);
+        let connector = connector.to_string();
+        let last_errors = self.last_errors.clone();
+        let events = self.events.borrow().clone();
+        active.renderer.connect_error(move |error| {
+            let Some(renderers) = weak.upgrade() else {
+                return;
+            };
+            let outputs = renderers
+                .borrow()
+                .get(&connector)
+                .map(|active| active.outputs.clone())
+                .unwrap_or_default();
+            for connector in &outputs {
+                last_errors
+                    .borrow_mut()
+                    .insert(connector.clone(), error.clone());
+                if let Some(events) = &events {
+                    let _ = events.try_send(StateEvent::RendererError {
+                        connector: connector.clone(),
+                        error: error.clone(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// Apply the runtime settings to the renderers on `connectors` once
+    /// the main loop is idle.
+    fn apply_settings_when_idle(&self, connectors: Vec<String>, settings: RenderSettings) {
         // Defer settings application to avoid a GStreamer deadlock:
-        // build_ui() starts pipeline state transitions via renderer.play(),
-        // and setting properties (volume, mute) during the transition blocks
-        // the main loop. An idle callback runs after the transition completes.
+        // build_windows() starts pipeline state transitions via
+        // renderer.play(), and setting properties (volume, mute) during the
+        // transition blocks the main loop. An idle callback runs after the
+        // transition completes.
         let renderers = self.renderers.clone();
         glib::idle_add_local_once(move || {
-            for renderer in renderers.borrow().iter() {
-                renderer.set_volume(settings.volume);
-                renderer.set_mute(settings.mute);
-                renderer.set_content_fit(settings.content_fit);
+            let renderers = renderers.borrow();
+            for active in connectors.iter().filter_map(|c| renderers.get(c)) {
+                active.renderer.set_volume(active.volume(settings.volume));
+                active.renderer.set_mute(active.mute(settings.mute));
+                active.set_content_fit(settings.content_fit);
             }
         });
     }
@@ -176,15 +521,21 @@ impl RendererState {
     pub fn disable_wallpaper(&self) -> bool {
         info!("Disabling wallpaper");
 
-        for renderer in self.renderers.borrow().iter() {
-            renderer.stop();
+        for active in self.renderers.borrow().values() {
+            active.renderer.stop();
         }
 
         self.app.windows().into_iter().for_each(|w| w.close());
 
         self.renderers.borrow_mut().clear();
+        self.last_errors.borrow_mut().clear();
         *self.config.borrow_mut() = None;
+        *self.layout.borrow_mut() = None;
+        self.scheduler.stop();
         *self.playback_state.borrow_mut() = PlaybackState::Idle;
+        self.paused_monitors.borrow_mut().clear();
+        self.apply_playback();
+        self.send_event(StateEvent::Rebuilt);
 
         // Clear persisted config
         self.settings_watcher.set_last_wallpaper_config("");
@@ -198,10 +549,8 @@ impl RendererState {
             return false;
         }
         info!("Pausing playback");
-        for renderer in self.renderers.borrow().iter() {
-            renderer.pause();
-        }
         *self.playback_state.borrow_mut() = PlaybackState::Paused;
+        self.apply_playback();
         true
     }
 
@@ -210,13 +559,306 @@ impl RendererState {
             return false;
         }
         info!("Resuming playback");
-        for renderer in self.renderers.borrow().iter() {
-            renderer.play();
-        }
         *self.playback_state.borrow_mut() = PlaybackState::Playing;
+        self.apply_playback();
+        true
+    }
+
+    /// Pause the renderer showing `connector` — and with it every monitor
+    /// mirroring the same renderer. False when no wallpaper shows on it or
+    /// it is already paused this way.
+    pub fn pause_monitor(&self, connector: &str) -> bool {
+        if !self.shows(connector) || !self.paused_monitors.borrow_mut().insert(connector.into()) {
+            return false;
+        }
+        info!("Pausing monitor {}", connector);
+        self.apply_playback();
+        true
+    }
+
+    /// Undo [`pause_monitor`](Self::pause_monitor). A global `Pause` or an
+    /// auto-pause may still hold the monitor.
+    pub fn resume_monitor(&self, connector: &str) -> bool {
+        if !self.paused_monitors.borrow_mut().remove(connector) {
+            return false;
+        }
+        info!("Resuming monitor {}", connector);
+        self.apply_playback();
         true
     }
 
+    /// Set the volume (0-100) of the renderer showing `connector`, or with
+    /// `None` go back to the `volume` setting. A renderer on several
+    /// monitors follows the first of them with a volume set, its own first
+    /// (see [`volume_override`](Self::volume_override)). False when no
+    /// wallpaper shows on it.
+    pub fn set_monitor_volume(&self, connector: &str, volume: Option<i32>) -> bool {
+        if !self.shows(connector) {
+            return false;
+        }
+        info!("Volume of monitor {} set to {:?}", connector, volume);
+        match volume {
+            Some(volume) => self
+                .monitor_volumes
+                .borrow_mut()
+                .insert(connector.into(), volume),
+            None => self.monitor_volumes.borrow_mut().remove(connector),
+        };
+        let setting = self.settings_watcher.volume();
+        for active in self.renderers.borrow().values() {
+            if active.outputs.iter().any(|output| output == connector) {
+                active.volume_override.set(self.volume_override(active));
+                active.renderer.set_volume(active.volume(setting));
+            }
+        }
+        self.send_event(StateEvent::MonitorStatesChanged);
+        true
+    }
+
+    /// The `SetMonitorVolume` volume that applies to `active`: that of the
+    /// first of its outputs with one set. The primary's own output comes
+    /// first in `outputs`, so a clone's only counts when the primary has
+    /// none.
+    fn volume_override(&self, active: &ActiveRenderer) -> Option<i32> {
+        let volumes = self.monitor_volumes.borrow();
+        active
+            .outputs
+            .iter()
+            .find_map(|output| volumes.get(output))
+            .copied()
+    }
+
+    /// The Wallpaper Engine user properties of what `connector` shows,
+    /// with their current values, as a JSON array for `GetWpeProperties`.
+    pub fn wpe_properties(&self, connector: &str) -> Result<String, String> {
+        let renderers = self.renderers.borrow();
+        let active = renderers
+            .values()
+            .find(|active| active.outputs.iter().any(|output| output == connector))
+            .ok_or_else(|| format!("No wallpaper shows on {}", connector))?;
+        let properties = active.renderer.wpe_properties()?;
+        serde_json::to_string(&properties).map_err(|e| e.to_string())
+    }
+
+    /// Set a user property of the Wallpaper Engine web wallpaper showing
+    /// on `connector` (a playlist's current entry), live. It lasts until
+    /// the renderer is rebuilt; the config's `wpe_properties` keeps it for
+    /// good. False when no wallpaper shows on the monitor.
+    pub fn set_wpe_property(
+        &self,
+        connector: &str,
+        name: &str,
+        value: serde_json::Value,
+    ) -> Result<bool, String> {
+        let renderers = self.renderers.borrow();
+        let Some(active) = renderers
+            .values()
+            .find(|active| active.outputs.iter().any(|output| output == connector))
+        else {
+            return Ok(false);
+        };
+        info!(
+            "WPE property {} of monitor {} set to {}",
+            name, connector, value
+        );
+        active.renderer.set_wpe_property(name, value)?;
+        Ok(true)
+    }
+
+    /// Whether a wallpaper renderer shows on `connector`.
+    fn shows(&self, connector: &str) -> bool {
+        self.renderers
+            .borrow()
+            .values()
+            .any(|active| active.outputs.iter().any(|output| output == connector))
+    }
+
+    /// Playback of every monitor showing the wallpaper, keyed by connector.
+    pub fn monitor_states(&self) -> BTreeMap<String, MonitorState> {
+        let reasons = self.pause_reasons.borrow();
+        let setting = self.settings_watcher.volume();
+        let mut states = BTreeMap::new();
+        for active in self.renderers.borrow().values() {
+            for output in &active.outputs {
+                let pause_reason = reasons.get(output).copied();
+                let state = match pause_reason {
+                    Some(_) => PlaybackState::Paused,
+                    None => PlaybackState::Playing,
+                };
+                states.insert(
+                    output.clone(),
+                    MonitorState {
+                        state,
+                        pause_reason,
+                        volume: active.volume(setting),
+                    },
+                );
+            }
+        }
+        states
+    }
+
+    /// Step every active playlist forward (`forward`) or back. False when
+    /// no playlist is showing.
+    pub fn step_playlist(&self, forward: bool) -> bool {
+        let mut stepped = false;
+        for active in self.renderers.borrow().values() {
+            if let Renderer::Playlist(playlist) = &active.renderer {
+                if forward {
+                    playlist.next();
+                } else {
+                    playlist.previous();
+                }
+                stepped = true;
+            }
+        }
+        stepped
+    }
+
+    /// Entry index of the first active playlist, or -1 when none is showing.
+    pub fn playlist_index(&self) -> i32 {
+        self.renderers
+            .borrow()
+            .values()
+            .find_map(|active| match &active.renderer {
+                Renderer::Playlist(playlist) => Some(playlist.position() as i32),
+                _ => None,
+            })
+            .unwrap_or(-1)
+    }
+
+    /// Bring every renderer in line with the requested playback state and
+    /// the auto-pause and power policies: a renderer plays unless the user
+    /// paused (everything, or one of its monitors), a session-wide reason
+    /// (locked, idle, the power `pause` action) holds, or every monitor
+    /// showing it is covered; the power policy also caps its frame rate.
+    /// Renderers only see transitions.
+    pub fn apply_playback(&self) {
+        let requested = *self.playback_state.borrow();
+        let global = self.policy.global_reasons().first().copied();
+        let covered = self.policy.covered();
+        let power = self.settings_watcher.power_policy();
+        let action = power.action(self.policy.power_state());
+        let fps_limit = power.fps_limit(action);
+        let paused_monitors = self.paused_monitors.borrow();
+
+        let mut reasons = BTreeMap::new();
+        for (connector, active) in self.renderers.borrow().iter() {
+            let user_paused = requested == PlaybackState::Paused
+                || active
+                    .outputs
+                    .iter()
+                    .any(|output| paused_monitors.contains(output));
+            let reason = if user_paused {
+                Some(PauseReason::User)
+            } else if global.is_some() {
+                global
+            } else if action == PowerAction::Pause {
+                Some(PauseReason::Power)
+            } else if active.outputs.iter().all(|output| covered.contains(output)) {
+                Some(PauseReason::Covered)
+            } else {
+                None
+            };
+
+            if active.fps_limit.replace(fps_limit) != fps_limit {
+                debug!("{}: FPS limit {:?} ({})", connector, fps_limit, action);
+                active.renderer.set_fps_limit(fps_limit);
+            }
+
+            let play = reason.is_none();
+            if active.playing.replace(play) != play {
+                debug!("{}: play {} (pause reason {:?})", connector, play, reason);
+                if play {
+                    active.renderer.play();
+                } else {
+                    active.renderer.pause();
+                }
+            }
+            if let Some(reason) = reason {
+                for output in &active.outputs {
+                    reasons.insert(output.clone(), reason);
+                }
+            }
+        }
+
+        if *self.pause_reasons.borrow() != reasons {
+            self.pause_reasons.replace(reasons);
+            self.send_event(StateEvent::PlaybackChanged);
+        }
+    }
+
+    /// The state reported over D-Bus: the requested one, except that it
+    /// reads `paused` while the policy holds every renderer.
+    pub fn effective_state(&self) -> PlaybackState {
+        let requested = *self.playback_state.borrow();
+        let renderers = self.renderers.borrow();
+        if requested == PlaybackState::Playing
+            && !renderers.is_empty()
+            && renderers.values().all(|active| !active.playing.get())
+        {
+            PlaybackState::Paused
+        } else {
+            requested
+        }
+    }
+
+    /// Why each paused monitor is paused, keyed by connector.
+    pub fn pause_reasons(&self) -> BTreeMap<String, PauseReason> {
+        self.pause_reasons.borrow().clone()
+    }
+
+    /// The latest renderer failure of each monitor, keyed by connector.
+    pub fn last_errors(&self) -> BTreeMap<String, RendererError> {
+        self.last_errors.borrow().clone()
+    }
+
+    /// The stored config as JSON; empty while no wallpaper is active.
+    pub fn current_config_json(&self) -> String {
+        self.config
+            .borrow()
+            .as_ref()
+            .and_then(|config| serde_json::to_string(config).ok())
+            .unwrap_or_default()
+    }
+
+    /// The active wallpaper's launch mode; empty while none is active.
+    pub fn active_launch_mode(&self) -> String {
+        match *self.config.borrow() {
+            Some(_) => self.launch_mode.borrow().to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// The connected monitors and their geometry.
+    pub fn monitors(&self) -> MonitorMap {
+        MonitorWatcher::monitors()
+            .and_then(|list| list.monitor_map())
+            .unwrap_or_default()
+    }
+
+    /// What each monitor showing the wallpaper shows, keyed by connector;
+    /// clones report their primary's renderer.
+    pub fn active_renderers(&self) -> BTreeMap<String, RendererInfo> {
+        let mut infos = BTreeMap::new();
+        for active in self.renderers.borrow().values() {
+            let info = RendererInfo {
+                kind: active.renderer.kind(),
+                source: serde_json::to_string(&active.source).unwrap_or_default(),
+            };
+            for output in &active.outputs {
+                infos.insert(output.clone(), info.clone());
+            }
+        }
+        infos
+    }
+
+    /// Name of the active schedule slot; empty outside every slot or when
+    /// the config has no schedule.
+    pub fn active_schedule_slot(&self) -> String {
+        self.scheduler.active_name()
+    }
+
     pub fn quit(&self) {
         info!("Quitting");
         self.app.quit();
@@ -0,0 +1,39 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Helpers shared by the test modules.
+
+use std::os::unix::net::UnixStream;
+
+use zbus::blocking::{connection::Builder, Connection};
+
+/// A peer-to-peer connection pair standing in for a message bus: the
+/// services `serve` adds on the first end, the watchers' side on the
+/// second.
+pub fn mock_bus(
+    serve: impl FnOnce(Builder<'static>) -> zbus::Result<Builder<'static>> + Send + 'static,
+) -> (Connection, Connection) {
+    let (service, client) = UnixStream::pair().unwrap();
+    let guid = zbus::Guid::generate();
+    // Both ends handshake at once, so the service builds on a thread.
+    let service = std::thread::spawn(move || {
+        let builder = Builder::unix_stream(service).server(guid).unwrap().p2p();
+        serve(builder).unwrap().build().unwrap()
+    });
+    let client = Builder::unix_stream(client).p2p().build().unwrap();
+    (service.join().unwrap(), client)
+}
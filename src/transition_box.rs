@@ -0,0 +1,337 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! A container that swaps its content with a transition.
+//!
+//! New content goes in beneath what is shown ([`TransitionBox::push`]), and
+//! [`TransitionBox::reveal`] animates the old content away — crossfade,
+//! slide or dissolve, drawn with GSK opacity, transform and mask nodes —
+//! then removes it. The new content is never hidden or moved meanwhile:
+//! GLArea-based renderers only draw while mapped, and reparenting them
+//! would unrealize their GL context.
+
+use std::time::Duration;
+
+use glib::subclass::types::ObjectSubclassIsExt as _;
+use glib::Object;
+use gtk::{glib, prelude::*};
+
+use crate::model::Transition;
+use crate::renderer::Renderer;
+
+/// Longest the old content waits for the new renderer's first frame.
+const REVEAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+glib::wrapper! {
+    pub struct TransitionBox(ObjectSubclass<imp::TransitionBox>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for TransitionBox {
+    fn default() -> Self {
+        Object::builder().build()
+    }
+}
+
+impl TransitionBox {
+    /// The transition played by later `reveal`s.
+    pub fn set_transition(&self, transition: Transition) {
+        self.imp().transition.set(transition);
+    }
+
+    /// Show `child` beneath the current content, which stays on top until
+    /// [`reveal`](Self::reveal). `retired` runs once the old content is
+    /// gone (right away when there is none). A transition still waiting or
+    /// running is cut short.
+    pub fn push(&self, child: &impl IsA<gtk::Widget>, retired: impl FnOnce() + 'static) {
+        self.finish();
+        let imp = self.imp();
+        imp.generation.set(imp.generation.get().wrapping_add(1));
+
+        let mut outgoing = Vec::new();
+        let mut widget = self.first_child();
+        while let Some(current) = widget {
+            widget = current.next_sibling();
+            outgoing.push(current);
+        }
+        child.insert_after(self, None::<&gtk::Widget>);
+        if outgoing.is_empty() {
+            retired();
+            return;
+        }
+        imp.outgoing.replace(outgoing);
+        imp.retired.replace(Some(Box::new(retired)));
+    }
+
+    /// Play the transition from the old content to the one pushed last.
+    /// Does nothing when nothing waits or the transition already started.
+    pub fn reveal(&self) {
+        let imp = self.imp();
+        if imp.outgoing.borrow().is_empty() || imp.tick.borrow().is_some() {
+            return;
+        }
+        let transition = imp.transition.get();
+        if transition.is_instant() {
+            self.finish();
+            return;
+        }
+        let tick = self.add_tick_callback(move |obj, clock| {
+            let imp = obj.imp();
+            let now = clock.frame_time();
+            let start = imp.start_us.get().unwrap_or_else(|| {
+                imp.start_us.set(Some(now));
+                now
+            });
+            imp.progress.set(transition.progress(now - start));
+            obj.queue_draw();
+            if imp.progress.get() < 1.0 {
+                return glib::ControlFlow::Continue;
+            }
+            // The callback is gone once it returns Break.
+            imp.tick.take();
+            obj.finish();
+            glib::ControlFlow::Break
+        });
+        imp.tick.replace(Some(tick));
+    }
+
+    /// Reveal the content pushed last once `renderer` (which draws it) has
+    /// a first frame or fails, or after `REVEAL_TIMEOUT` at the latest.
+    pub fn reveal_on_first_frame(&self, renderer: &Renderer) {
+        let generation = self.imp().generation.get();
+        let reveal = glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move || {
+                // Only for the push this was set up for.
+                if obj.imp().generation.get() == generation {
+                    obj.reveal();
+                }
+            }
+        );
+        let on_frame = reveal.clone();
+        renderer.connect_first_frame(move || on_frame());
+        let on_error = reveal.clone();
+        renderer.connect_error(move |_error| on_error());
+        glib::timeout_add_local_once(REVEAL_TIMEOUT, reveal);
+    }
+
+    /// Remove all content, old and new.
+    pub fn clear(&self) {
+        self.finish();
+        while let Some(child) = self.first_child() {
+            child.unparent();
+        }
+    }
+
+    /// Drop the old content now and end any transition.
+    fn finish(&self) {
+        let imp = self.imp();
+        if let Some(tick) = imp.tick.take() {
+            tick.remove();
+        }
+        imp.start_us.take();
+        imp.progress.set(0.0);
+        for widget in imp.outgoing.take() {
+            if widget.parent().as_ref() == Some(self.upcast_ref()) {
+                widget.unparent();
+            }
+        }
+        if let Some(retired) = imp.retired.take() {
+            retired();
+        }
+        self.queue_draw();
+    }
+}
+
+mod imp {
+    use super::*;
+    use gtk::subclass::prelude::*;
+    use gtk::{gdk, graphene, gsk};
+    use std::cell::{Cell, RefCell};
+
+    use crate::model::{dissolve_threshold, TransitionKind};
+
+    /// Edge length of the blocks a dissolve drops, in pixels.
+    const DISSOLVE_BLOCK: f32 = 32.0;
+
+    #[derive(Default)]
+    pub struct TransitionBox {
+        pub(super) transition: Cell<Transition>,
+        /// The children on their way out, on top of the new content.
+        pub(super) outgoing: RefCell<Vec<gtk::Widget>>,
+        pub(super) retired: RefCell<Option<Box<dyn FnOnce()>>>,
+        /// Counts pushes, so a late reveal for an earlier one is ignored.
+        pub(super) generation: Cell<u32>,
+        pub(super) tick: RefCell<Option<gtk::TickCallbackId>>,
+        /// Frame-clock time (µs) of the transition's first frame.
+        pub(super) start_us: Cell<Option<i64>>,
+        /// Eased progress of the running transition, 0 to 1.
+        pub(super) progress: Cell<f64>,
+    }
+
+    impl TransitionBox {
+        /// Draw the old content with the share of the transition left.
+        fn snapshot_outgoing(&self, child: &gtk::Widget, snapshot: &gtk::Snapshot) {
+            let obj = self.obj();
+            let progress = self.progress.get();
+            match self.transition.get().kind {
+                TransitionKind::None => obj.snapshot_child(child, snapshot),
+                TransitionKind::Crossfade => {
+                    snapshot.push_opacity(1.0 - progress);
+                    obj.snapshot_child(child, snapshot);
+                    snapshot.pop();
+                }
+                TransitionKind::Slide => {
+                    snapshot.save();
+                    snapshot.translate(&graphene::Point::new(
+                        -obj.width() as f32 * progress as f32,
+                        0.0,
+                    ));
+                    obj.snapshot_child(child, snapshot);
+                    snapshot.restore();
+                }
+                TransitionKind::Dissolve => {
+                    snapshot.push_mask(gsk::MaskMode::Alpha);
+                    self.snapshot_dissolve_mask(snapshot, progress);
+                    snapshot.pop();
+                    obj.snapshot_child(child, snapshot);
+                    snapshot.pop();
+                }
+            }
+        }
+
+        /// The blocks of the old content still standing, as an alpha mask.
+        /// Runs of neighbouring blocks in a row become one rectangle.
+        fn snapshot_dissolve_mask(&self, snapshot: &gtk::Snapshot, progress: f64) {
+            let obj = self.obj();
+            let columns = (obj.width() as f32 / DISSOLVE_BLOCK).ceil() as u32;
+            let rows = (obj.height() as f32 / DISSOLVE_BLOCK).ceil() as u32;
+            for row in 0..rows {
+                let mut run_start = None;
+                for column in 0..=columns {
+                    let standing =
+                        column < columns && dissolve_threshold(row * columns + column) >= progress;
+                    match (standing, run_start) {
+                        (true, None) => run_start = Some(column),
+                        (false, Some(start)) => {
+                            snapshot.append_color(
+                                &gdk::RGBA::BLACK,
+                                &graphene::Rect::new(
+                                    start as f32 * DISSOLVE_BLOCK,
+                                    row as f32 * DISSOLVE_BLOCK,
+                                    (column - start) as f32 * DISSOLVE_BLOCK,
+                                    DISSOLVE_BLOCK,
+                                ),
+                            );
+                            run_start = None;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TransitionBox {
+        const NAME: &'static str = "TransitionBox";
+        type Type = super::TransitionBox;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for TransitionBox {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_hexpand(true);
+            obj.set_vexpand(true);
+        }
+
+        fn dispose(&self) {
+            if let Some(tick) = self.tick.take() {
+                tick.remove();
+            }
+            if let Some(retired) = self.retired.take() {
+                retired();
+            }
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for TransitionBox {
+        fn measure(&self, orientation: gtk::Orientation, for_size: i32) -> (i32, i32, i32, i32) {
+            // As large as the largest content, old or new.
+            let mut child = self.obj().first_child();
+            let (mut minimum, mut natural) = (0, 0);
+            while let Some(widget) = child {
+                let (child_minimum, child_natural, _, _) = widget.measure(orientation, for_size);
+                minimum = minimum.max(child_minimum);
+                natural = natural.max(child_natural);
+                child = widget.next_sibling();
+            }
+            (minimum, natural, -1, -1)
+        }
+
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            let mut child = self.obj().first_child();
+            while let Some(widget) = child {
+                widget.allocate(width, height, baseline, None);
+                child = widget.next_sibling();
+            }
+        }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let obj = self.obj();
+            let running = self.tick.borrow().is_some();
+            let slide = running && self.transition.get().kind == TransitionKind::Slide;
+            if slide {
+                snapshot.push_clip(&graphene::Rect::new(
+                    0.0,
+                    0.0,
+                    obj.width() as f32,
+                    obj.height() as f32,
+                ));
+            }
+            let outgoing = self.outgoing.borrow();
+            let mut child = obj.first_child();
+            while let Some(widget) = child {
+                if running && outgoing.contains(&widget) {
+                    self.snapshot_outgoing(&widget, snapshot);
+                } else if slide {
+                    // The new content follows the old one in from the right.
+                    snapshot.save();
+                    snapshot.translate(&graphene::Point::new(
+                        obj.width() as f32 * (1.0 - self.progress.get() as f32),
+                        0.0,
+                    ));
+                    obj.snapshot_child(&widget, snapshot);
+                    snapshot.restore();
+                } else {
+                    obj.snapshot_child(&widget, snapshot);
+                }
+                child = widget.next_sibling();
+            }
+            if slide {
+                snapshot.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (C) 2026 Jeff Shee <jeffshee8969@gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! `hotaru validate`: check a config file without applying it. Every
+//! diagnostic is printed; any error-severity one fails the command, so
+//! `main` exits non-zero.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use hotaru::config_watcher::load_config;
+use hotaru::model::{DiagnosticKind, Severity};
+use hotaru::prelude::*;
+
+pub fn run(config_file: &Path) -> Result<()> {
+    let config = load_config(config_file)?;
+
+    // Monitor names can only be checked against a display; without one,
+    // skip that check rather than report every monitor as disconnected.
+    let monitors = gtk::init()
+        .ok()
+        .and_then(|_| MonitorWatcher::monitors().ok())
+        .and_then(|list| list.monitor_map().ok());
+    let mut diagnostics = config.validate(monitors.as_ref().unwrap_or(&MonitorMap::new()));
+    if monitors.is_none() {
+        eprintln!("No display; monitor names are not checked");
+        diagnostics.retain(|d| !matches!(d.kind, DiagnosticKind::UnknownMonitor(_)));
+    }
+
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity() == Severity::Error)
+        .count();
+    if errors > 0 {
+        bail!("{} has {} error(s)", config_file.display(), errors);
+    }
+    if diagnostics.is_empty() {
+        println!("{} is valid", config_file.display());
+    }
+    Ok(())
+}
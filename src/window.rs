@@ -200,9 +200,9 @@ mod imp {
                     obj.connect_realize(move |window| {
                         let connector = window.monitor_connector();
                         let display = Display::default().expect("Could not connect to a display");
-                        if let Ok(monitors) = display.monitors().monitor_vec() {
-                            for monitor in &monitors {
-                                if monitor.connector().as_deref() == Some(&connector) {
+                        if let Ok(monitors) = display.monitors().keyed_monitors() {
+                            for (key, monitor) in &monitors {
+                                if *key == connector {
                                     window.set_monitor(Some(monitor));
                                     break;
                                 }
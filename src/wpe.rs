@@ -24,6 +24,7 @@
 //! packages to linux-wallpaperengine and video/web packages to hotaru's own
 //! renderers.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::{env, fs};
 
@@ -31,7 +32,7 @@ use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 
 use crate::constants::WPE_WORKSHOP_APP_ID;
-use crate::model::WallpaperSource;
+use crate::model::{WallpaperSource, WpeProperty};
 
 /// Environment override pointing directly at the workshop content directory
 /// (the one containing `<workshop-id>` subdirectories), e.g.
@@ -57,6 +58,12 @@ pub fn fps_limit() -> i64 {
     })
 }
 
+/// [`fps_limit`], lowered to a renderer's runtime cap (see
+/// `RendererWidget::set_fps_limit`) when one is set.
+pub fn capped_fps(limit: Option<u32>) -> i64 {
+    limit.map_or(fps_limit(), |limit| fps_limit().min(limit.into()))
+}
+
 /// The renderer a WPE package maps to, from its `project.json` `type`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WpeType {
@@ -88,7 +95,7 @@ pub struct WpePackage {
     /// Entry file relative to `dir` (the video file or `index.html`); unused
     /// for scene packages, which are handed to the engine as a directory.
     file: Option<String>,
-    /// Raw `general.properties` from project.json (used for web packages).
+    /// Raw `general.properties` from project.json.
     properties: serde_json::Map<String, serde_json::Value>,
 }
 
@@ -101,11 +108,16 @@ impl WpePackage {
             WallpaperSource::Uri { .. } => {
                 bail!("a wpe wallpaper cannot be specified as a URI (use filepath or workshop_id)")
             }
+            WallpaperSource::Playlist { .. } | WallpaperSource::Directory { .. } => {
+                bail!("a playlist is not a Wallpaper Engine package")
+            }
         };
         Self::from_dir(dir)
     }
 
-    fn from_dir(dir: PathBuf) -> Result<Self> {
+    /// Read the package in `dir`. Also used to vet package folders found
+    /// by directory sources.
+    pub(crate) fn from_dir(dir: PathBuf) -> Result<Self> {
         let project = dir.join("project.json");
         let data = fs::read_to_string(&project)
             .with_context(|| format!("reading {}", project.display()))?;
@@ -142,20 +154,21 @@ impl WpePackage {
         Ok(self.dir.join(file))
     }
 
-    /// The default user properties as the argument Wallpaper Engine passes to
+    /// The package's user properties (`general.properties`), valued
+    /// from `overrides` where they name one and the defaults elsewhere.
+    pub fn user_properties(
+        &self,
+        overrides: &BTreeMap<String, serde_json::Value>,
+    ) -> Vec<WpeProperty> {
+        WpeProperty::parse_all(&self.properties, overrides)
+    }
+
+    /// The user properties as the argument Wallpaper Engine passes to
     /// `wallpaperPropertyListener.applyUserProperties` — `{name: {value: …}}`
-    /// built from each property's default `value` in project.json. Serialized
-    /// as a JSON string ready to embed in injected JavaScript.
-    pub fn user_properties_json(&self) -> String {
-        let mut out = serde_json::Map::new();
-        for (name, def) in &self.properties {
-            if let Some(value) = def.get("value") {
-                let mut entry = serde_json::Map::new();
-                entry.insert("value".to_string(), value.clone());
-                out.insert(name.clone(), serde_json::Value::Object(entry));
-            }
-        }
-        serde_json::Value::Object(out).to_string()
+    /// — with `overrides` in place of the defaults. Serialized as a JSON
+    /// string ready to embed in injected JavaScript.
+    pub fn user_properties_json(&self, overrides: &BTreeMap<String, serde_json::Value>) -> String {
+        WpeProperty::user_properties(&self.user_properties(overrides)).to_string()
     }
 }
 